@@ -10,6 +10,10 @@ pub struct AddressSuggestion {
     pub postcode: String, // Changed from zip_code to postcode for UK
     pub country: String,
     pub combined: String, // New field for combined display
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lon: Option<f64>,
 }
 
 impl AddressSuggestion {
@@ -21,6 +25,17 @@ impl AddressSuggestion {
             postcode: postcode.to_string(),
             country: country.to_string(),
             combined,
+            lat: None,
+            lon: None,
+        }
+    }
+
+    /// Known coordinates, in degrees
+    pub fn at(self, lat: f64, lon: f64) -> Self {
+        Self {
+            lat: Some(lat),
+            lon: Some(lon),
+            ..self
         }
     }
 }
@@ -28,19 +43,29 @@ impl AddressSuggestion {
 #[derive(Debug, Deserialize)]
 struct AddressQuery {
     query: String,
+    /// Maximum number of results, 5 if omitted
+    limit: Option<usize>,
+    /// ISO 3166 alpha-2 code, case-insensitive. Unknown codes match nothing.
+    country: Option<String>,
 }
 
-// Hardcoded list of London address suggestions
+const DEFAULT_LIMIT: usize = 5;
+
+// Hardcoded list of address suggestions, mostly London
 fn get_all_suggestions() -> Vec<AddressSuggestion> {
     vec![
         // Central London
-        AddressSuggestion::new("10 Downing Street", "London", "SW1A 2AA", "UK"),
-        AddressSuggestion::new("221B Baker Street", "London", "NW1 6XE", "UK"),
-        AddressSuggestion::new("30 St Mary Axe", "London", "EC3A 8BF", "UK"), // The Gherkin
-        AddressSuggestion::new("20 Fenchurch Street", "London", "EC3M 3BY", "UK"), // The Walkie Talkie
-        AddressSuggestion::new("122 Leadenhall Street", "London", "EC3V 4AB", "UK"), // The Cheesegrater
+        AddressSuggestion::new("10 Downing Street", "London", "SW1A 2AA", "UK")
+            .at(51.5034, -0.1276),
+        AddressSuggestion::new("221B Baker Street", "London", "NW1 6XE", "UK").at(51.5238, -0.1586),
+        AddressSuggestion::new("30 St Mary Axe", "London", "EC3A 8BF", "UK").at(51.5145, -0.0803), // The Gherkin
+        AddressSuggestion::new("20 Fenchurch Street", "London", "EC3M 3BY", "UK")
+            .at(51.5113, -0.0836), // The Walkie Talkie
+        AddressSuggestion::new("122 Leadenhall Street", "London", "EC3V 4AB", "UK")
+            .at(51.5136, -0.0822), // The Cheesegrater
         // West End
-        AddressSuggestion::new("1 Piccadilly Circus", "London", "W1J 0DA", "UK"),
+        AddressSuggestion::new("1 Piccadilly Circus", "London", "W1J 0DA", "UK")
+            .at(51.5100, -0.1345),
         AddressSuggestion::new("15 Regent Street", "London", "SW1Y 4LR", "UK"),
         AddressSuggestion::new("28 Oxford Street", "London", "W1D 2AU", "UK"),
         AddressSuggestion::new("40 Bond Street", "London", "W1S 2QP", "UK"),
@@ -52,7 +77,7 @@ fn get_all_suggestions() -> Vec<AddressSuggestion> {
         AddressSuggestion::new("60 Lombard Street", "London", "EC3V 9EA", "UK"),
         AddressSuggestion::new("88 Wood Street", "London", "EC2V 7RS", "UK"),
         // Canary Wharf
-        AddressSuggestion::new("1 Canada Square", "London", "E14 5AB", "UK"),
+        AddressSuggestion::new("1 Canada Square", "London", "E14 5AB", "UK").at(51.5049, -0.0195),
         AddressSuggestion::new("25 Bank Street", "London", "E14 5JP", "UK"),
         AddressSuggestion::new("40 Marsh Wall", "London", "E14 9TP", "UK"),
         AddressSuggestion::new("10 Upper Bank Street", "London", "E14 5BB", "UK"),
@@ -69,23 +94,77 @@ fn get_all_suggestions() -> Vec<AddressSuggestion> {
         AddressSuggestion::new("40 Spitalfields", "London", "E1 6EW", "UK"),
         AddressSuggestion::new("55 Shoreditch High Street", "London", "E1 6JJ", "UK"),
         AddressSuggestion::new("70 Old Street", "London", "EC1V 9BD", "UK"),
+        // Ireland
+        AddressSuggestion::new("1 O'Connell Street", "Dublin", "D01 F5P2", "IE"),
+        AddressSuggestion::new("30 Grafton Street", "Dublin", "D02 VK65", "IE"),
+        AddressSuggestion::new("12 Patrick Street", "Cork", "T12 X8NE", "IE"),
+        // United States
+        AddressSuggestion::new("350 Fifth Avenue", "New York", "NY 10118", "US")
+            .at(40.7484, -73.9857),
+        AddressSuggestion::new("1600 Pennsylvania Avenue", "Washington", "DC 20500", "US")
+            .at(38.8977, -77.0365),
+        AddressSuggestion::new("221 Baker Street", "Chicago", "IL 60601", "US"),
     ]
 }
 
-async fn get_suggestions(Query(params): Query<AddressQuery>) -> axum::Json<Vec<AddressSuggestion>> {
-    let query = params.query.to_lowercase();
-    let suggestions = get_all_suggestions()
+/// The dataset labels Great Britain "UK", which ISO 3166 reserves as an
+/// alias of GB; accept both.
+fn country_matches(address: &AddressSuggestion, code: &str) -> bool {
+    let code = code.trim();
+    address.country.eq_ignore_ascii_case(code)
+        || (address.country == "UK" && code.eq_ignore_ascii_case("GB"))
+}
+
+fn search(query: &str, country: Option<&str>, limit: usize) -> Vec<AddressSuggestion> {
+    let query = query.to_lowercase();
+    get_all_suggestions()
         .into_iter()
+        .filter(|addr| match country {
+            Some(code) => country_matches(addr, code),
+            None => true,
+        })
         .filter(|addr| {
             addr.street.to_lowercase().contains(&query)
                 || addr.city.to_lowercase().contains(&query)
                 || addr.postcode.to_lowercase().contains(&query)
                 || addr.combined.to_lowercase().contains(&query)
         })
-        .take(5) // Limit to first 5 results
-        .collect();
+        .take(limit)
+        .collect()
+}
+
+async fn get_suggestions(Query(params): Query<AddressQuery>) -> axum::Json<Vec<AddressSuggestion>> {
+    axum::Json(search(
+        &params.query,
+        params.country.as_deref(),
+        params.limit.unwrap_or(DEFAULT_LIMIT),
+    ))
+}
 
-    axum::Json(suggestions)
+#[derive(Debug, Deserialize)]
+struct EmailQuery {
+    check: String,
+}
+
+#[derive(Debug, Serialize)]
+struct EmailAvailability {
+    available: bool,
+}
+
+// Hardcoded accounts, so the form's pre-submit check can be exercised
+const REGISTERED_EMAILS: &[&str] = &["admin@example.com", "taken@example.com"];
+
+fn email_available(email: &str) -> bool {
+    let email = email.trim();
+    !REGISTERED_EMAILS
+        .iter()
+        .any(|registered| registered.eq_ignore_ascii_case(email))
+}
+
+async fn check_email(Query(params): Query<EmailQuery>) -> axum::Json<EmailAvailability> {
+    axum::Json(EmailAvailability {
+        available: email_available(&params.check),
+    })
 }
 
 #[tokio::main]
@@ -102,6 +181,7 @@ async fn main() {
     // Build our application with a route
     let app = Router::new()
         .route("/api/suggestions", get(get_suggestions))
+        .route("/api/email", get(check_email))
         .layer(cors);
 
     // Run it with hyper
@@ -110,3 +190,41 @@ async fn main() {
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     axum::serve(listener, app).await.unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn country_filter_is_case_insensitive() {
+        let results = search("baker", Some("us"), DEFAULT_LIMIT);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].city, "Chicago");
+        assert_eq!(results[0].country, "US");
+
+        let results = search("baker", Some("GB"), DEFAULT_LIMIT);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].country, "UK");
+
+        let results = search("street", Some("IE"), DEFAULT_LIMIT);
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|addr| addr.country == "IE"));
+    }
+
+    #[test]
+    fn unknown_country_returns_no_results() {
+        assert!(search("street", Some("ZZ"), DEFAULT_LIMIT).is_empty());
+    }
+
+    #[test]
+    fn registered_emails_are_unavailable_in_any_case() {
+        assert!(!email_available("Taken@Example.com"));
+        assert!(email_available("new@example.com"));
+    }
+
+    #[test]
+    fn no_country_searches_everything() {
+        let results = search("baker", None, DEFAULT_LIMIT);
+        assert_eq!(results.len(), 2);
+    }
+}
@@ -1,67 +1,671 @@
-use crux_core::{macros::effect, render::RenderOperation, Command};
+use chrono::NaiveDate;
+use crux_core::{
+    macros::effect,
+    render::{render, RenderOperation},
+    Command,
+};
 use crux_http::protocol::HttpRequest;
 use serde::{Deserialize, Serialize};
 
-use crate::events::address::{AddressHandler, AddressSuggestion};
-use crate::events::form::{FormHandler, FormViewModel};
+use crate::capabilities::analytics::AnalyticsOperation;
+use crate::capabilities::diagnostics::{DiagnosticsOperation, DiagnosticsReport};
+use crate::capabilities::navigate::NavigateOperation;
+use crate::capabilities::random::{RandomOperation, RandomValue};
+use crate::capabilities::storage::StorageOperation;
+use crate::capabilities::timer::{TimerKind, TimerOperation};
+use crate::capabilities::validate::{AsyncValidateOperation, AsyncVerdict};
+use crate::config::{AppConfig, ConfigError, SubmissionMode, SubmissionReceipt};
+use crate::events::address::{
+    Address, AddressHandler, AddressSuggestionsResult, Coordinates, SuggestionViewModel,
+};
+use crate::events::diagnostics::{ErrorMemory, SkipReason, SuggestionLog, SuggestionOutcome};
+use crate::events::draft::DEFAULT_DRAFT_SIZE_LIMIT;
+use crate::events::dynamic::{DynamicFieldViewModel, DynamicForm};
+use crate::events::form::{
+    is_suggestion_query, FieldIdent, FormHandler, FormViewModel, ServerFieldError, SubmitResult,
+};
+use crate::events::history::{FormHistory, UNDO_WINDOW_MS};
+use crate::events::retry::{Backoff, Jitter};
+use crate::events::signals::RaisedSignal;
+use crate::events::snapshot::FormSnapshot;
+use crate::render_hint::{hints_for, RenderHint, RenderHints};
 
-const ADDRESS_API_URL: &str = "http://localhost:8000/api/suggestions";
+/// Version of the serialized `Model`. Bump it when a change needs more than
+/// defaults for the new fields, and teach `Model::migrate` the step.
+pub const MODEL_VERSION: u32 = 2;
 
+/// Fields added after the first release must have a serde default, so that
+/// models saved by older versions still load.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Model {
+    /// `MODEL_VERSION` when saved; 0 for models saved before versioning
+    #[serde(default)]
+    model_version: u32,
     form_handler: FormHandler,
     address_handler: AddressHandler,
+    #[serde(default)]
+    config: AppConfig,
+    #[serde(default)]
+    config_error: Option<ConfigError>,
+    #[serde(default)]
+    history: FormHistory,
+    #[serde(default)]
+    suggestion_log: SuggestionLog,
+    #[serde(default)]
+    error_memory: ErrorMemory,
+    /// The server's receipt for the last accepted submission
+    #[serde(default)]
+    receipt: Option<SubmissionReceipt>,
+    #[serde(default)]
+    jitter: Jitter,
+    /// Fetches made with a backoff, which numbers their retry timers
+    #[serde(default)]
+    fetches: u64,
+    /// Shown instead of the form's status message until the form changes
+    #[serde(default)]
+    status_override: Option<String>,
+    #[serde(skip)]
+    render_hints: RenderHints,
 }
 
 impl Default for Model {
     fn default() -> Self {
         Self {
+            model_version: MODEL_VERSION,
             form_handler: FormHandler::new(),
-            address_handler: AddressHandler::new(ADDRESS_API_URL.to_string()),
+            address_handler: AddressHandler::new(None),
+            config: AppConfig::default(),
+            config_error: None,
+            history: FormHistory::default(),
+            suggestion_log: SuggestionLog::default(),
+            error_memory: ErrorMemory::default(),
+            receipt: None,
+            jitter: Jitter::default(),
+            fetches: 0,
+            status_override: None,
+            render_hints: RenderHints::default(),
+        }
+    }
+}
+
+impl Model {
+    /// Loads a model serialized as JSON by this or any earlier version.
+    /// Fields added since are filled with defaults and fields from a later
+    /// version are ignored.
+    pub fn migrate(bytes: &[u8]) -> Result<Model, serde_json::Error> {
+        let mut saved: serde_json::Value = serde_json::from_slice(bytes)?;
+        let version = saved
+            .get("model_version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0);
+        if version < 1 {
+            // Suggestions were one list before they were kept per field.
+            // They are transient, so the migrated model starts without any.
+            if let Some(suggestions) = saved.pointer_mut("/address_handler/suggestions") {
+                if suggestions.is_array() {
+                    *suggestions = serde_json::json!({});
+                }
+            }
+        }
+        if version < 2 {
+            // Dynamic fields sat beside the form before they were part of
+            // it, and were saved without the specs that rebuild their rules
+            if let Some(serde_json::Value::Object(mut dynamic)) = saved
+                .as_object_mut()
+                .and_then(|saved| saved.remove("dynamic_form"))
+            {
+                let specs = saved
+                    .pointer("/config/dynamic_fields")
+                    .cloned()
+                    .unwrap_or_else(|| serde_json::json!([]));
+                dynamic.insert("specs".to_string(), specs);
+                if let Some(serde_json::Value::Object(form)) =
+                    saved.pointer_mut("/form_handler/form")
+                {
+                    form.insert("dynamic".to_string(), dynamic.into());
+                }
+            }
+        }
+        let mut model: Model = serde_json::from_value(saved)?;
+        model.model_version = MODEL_VERSION;
+        Ok(model)
+    }
+
+    /// Takes on a validated config and everything derived from it.
+    fn apply_config(&mut self, config: AppConfig) {
+        self.address_handler
+            .set_providers(config.address_providers.clone());
+        self.address_handler
+            .set_api_url(config.address_api_url.clone());
+        self.form_handler
+            .set_username_policy(config.username_policy);
+        self.form_handler
+            .set_validate_on_change(config.validate_on_change);
+        self.form_handler
+            .set_username_ignored_characters(config.username_ignored_characters.clone());
+        self.form_handler.set_age_required(config.age_required);
+        self.form_handler
+            .set_max_address_length(config.max_address_length);
+        self.form_handler
+            .set_suggestion_debounce(config.suggestion_debounce_ms);
+        self.form_handler
+            .set_min_suggestion_query_len(config.min_suggestion_query_len);
+        self.form_handler
+            .set_error_display_order(config.error_display_order.clone());
+        self.form_handler
+            .set_email_check_url(config.email_check_url.clone());
+        self.form_handler.set_submit_url(config.submit_url.clone());
+        self.form_handler
+            .set_remote_checks(config.remote_checks.clone());
+        self.form_handler
+            .set_async_validated(config.async_validated.clone());
+        self.form_handler.set_dynamic_form(
+            DynamicForm::from_specs(&config.dynamic_fields)
+                .expect("checked by AppConfig::validate"),
+        );
+        if let Some(seed) = config.random_seed {
+            self.jitter = Jitter::seeded(seed);
+        }
+        self.error_memory.reset();
+        self.config = config;
+        self.config_error = None;
+    }
+
+    /// Clears form data and transient UI state. Every field is named here,
+    /// so a new one does not compile until it is put in a bucket.
+    fn reset_form(&mut self) -> Command<Effect, Event> {
+        let Model {
+            // Form data
+            form_handler,
+            history,
+            error_memory,
+            receipt,
+            suggestion_log,
+            // Transient UI state
+            status_override,
+            // Fetches in flight are dismissed; the suggestions go through
+            // the dispatched `ClearSuggestions`, providers, country and
+            // location stay
+            address_handler,
+            // Retained: config and what was derived from it
+            config: _,
+            config_error: _,
+            jitter: _,
+            fetches: _,
+            // Describes the render this reset is part of
+            render_hints: _,
+            // Describes the serialized shape
+            model_version: _,
+        } = self;
+        address_handler.dismiss_all();
+        history.clear();
+        error_memory.clear();
+        *receipt = None;
+        suggestion_log.clear();
+        *status_override = None;
+        form_handler.handle_reset()
+    }
+
+    /// Puts everything back as it was right after the last accepted config.
+    /// Signal numbering carries on, so the shell's acknowledgements stay valid.
+    fn reset_all(&mut self) -> Command<Effect, Event> {
+        let config = self.config.clone();
+        let last_signal = self.form_handler.signals().last_seq();
+        *self = Model {
+            render_hints: std::mem::take(&mut self.render_hints),
+            ..Model::default()
+        };
+        self.form_handler.resume_signals_after(last_signal);
+        self.apply_config(config);
+        render()
+    }
+
+    /// Updates a field, recording the edit for undo and diagnostics.
+    fn set_value(
+        &mut self,
+        ident: FieldIdent,
+        value: String,
+        fetch: bool,
+    ) -> Command<Effect, Event> {
+        self.edit(ident, |handler| {
+            handler.handle_set_value(ident, value, fetch)
+        })
+    }
+
+    /// Runs `step`, an edit of `ident`, recording it for undo and diagnostics
+    /// if it changed the form.
+    fn edit(
+        &mut self,
+        ident: FieldIdent,
+        step: impl FnOnce(&mut FormHandler) -> Command<Effect, Event>,
+    ) -> Command<Effect, Event> {
+        let before = self.form_handler.inputs();
+        let cmd = step(&mut self.form_handler);
+        if self.form_handler.inputs() != before {
+            let edit = self.history.record(ident, before);
+            return cmd
+                .and(Self::undo_window(edit))
+                .and(self.record_errors([ident]));
+        }
+        cmd
+    }
+
+    /// Waits out the undo window after edit `edit`.
+    fn undo_window(edit: u64) -> Command<Effect, Event> {
+        Command::request_from_shell(TimerOperation::Start {
+            id: TimerKind::UndoWindow.id(edit),
+            millis: UNDO_WINDOW_MS,
+        })
+        .then_send(move |_| Event::UndoWindowElapsed { edit })
+    }
+
+    /// Remembers the current validation errors of `idents` for diagnostics,
+    /// asking for the salt again if a round trip lost it.
+    fn record_errors(
+        &mut self,
+        idents: impl IntoIterator<Item = FieldIdent>,
+    ) -> Command<Effect, Event> {
+        let form = self.form_handler.get_form();
+        for ident in idents {
+            if let Some(error) = form.field(ident).error() {
+                self.error_memory.record(ident, error, &form.input(ident));
+            }
+        }
+        if self.error_memory.wants_salt() {
+            request_error_salt()
+        } else {
+            Command::done()
+        }
+    }
+
+    /// Runs `step` and, if it is what submitted the form, the submit hooks.
+    ///
+    /// The effects come in a fixed order: the render of the submitted form
+    /// first, then one effect per hook in configuration order.
+    fn submit_with(
+        &mut self,
+        step: impl FnOnce(&mut FormHandler) -> Command<Effect, Event>,
+    ) -> Command<Effect, Event> {
+        let was_submitted = self.form_handler.get_form().submitted;
+        let cmd = step(&mut self.form_handler);
+        if !was_submitted && self.form_handler.get_form().submitted {
+            self.render_hints.merge_next();
+            let delays = self.jitter.schedule(&self.config.submission_retry);
+            let fetches = &mut self.fetches;
+            let hooks = self.config.submit_hooks_command(self.receipt.as_ref(), || {
+                Backoff::next(fetches, delays.clone())
+            });
+            if self
+                .config
+                .resubmit_policy
+                .updates(self.receipt.as_ref())
+                .is_none()
+            {
+                // A new submission supersedes the old receipt
+                self.receipt = None;
+            }
+            cmd.then(hooks)
+        } else {
+            cmd
+        }
+    }
+
+    /// Hides the suggestions for `ident`, `None` for every field. A typing
+    /// pause still pending would fetch them again, so it is cancelled too.
+    fn dismiss_suggestions(&mut self, ident: Option<FieldIdent>) {
+        match ident {
+            Some(ident) => self.address_handler.dismiss(ident),
+            None => self.address_handler.dismiss_all(),
+        }
+        if ident.map_or(true, |ident| ident == FieldIdent::Address) {
+            self.form_handler.cancel_address_pause();
         }
     }
 }
 
 // Main ViewModel
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ViewModel {
     pub form: FormViewModel,
-    pub address_suggestions: Vec<AddressSuggestion>,
+    pub address_suggestions: Vec<SuggestionViewModel>,
+    /// Address suggestions have been asked for and not answered yet
+    pub suggestions_loading: bool,
+    pub config_error: Option<ConfigError>,
+    /// One-shot instructions, oldest first, until acknowledged with
+    /// `AcknowledgeSignals`
+    pub signals: Vec<RaisedSignal>,
+    pub can_undo: bool,
+    pub can_redo: bool,
+    /// The fields declared by `AppConfig::dynamic_fields`, in that order
+    pub dynamic_fields: Vec<DynamicFieldViewModel>,
+    /// Whether any field differs from its baseline
+    pub is_dirty: bool,
+    /// Fields differing from their baseline, in form order, for an
+    /// unsaved-changes warning. Submitting or `MarkAsPristine` makes the
+    /// values the baseline.
+    pub dirty_fields: Vec<FieldIdent>,
+    /// Fields changed since the last accepted submit, in form order, for an
+    /// "unsaved edits" badge. `MarkAsPristine` leaves them be.
+    pub changed_since_submit: Vec<FieldIdent>,
+    /// Fields holding an invalid value, shown or not, in form order
+    pub invalid_fields: Vec<FieldIdent>,
+    pub error_count: usize,
+    /// The field to focus after a failed submit
+    pub first_invalid_field: Option<FieldIdent>,
+    /// Whether submitting creates a submission or updates the accepted one
+    pub submission_mode: SubmissionMode,
+    pub render_hints: Vec<RenderHint>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum Event {
+    Configure(AppConfig),
+    /// A message of the shell's own shown in place of the form's status
+    /// until the form next changes; `None` removes it
+    SetStatusOverride(Option<String>),
+    /// The shell has acted on the signals up to and including `up_to_seq`
+    AcknowledgeSignals {
+        up_to_seq: u64,
+    },
+
     // Form events
     UpdateValue {
-        ident: crate::events::form::FieldIdent,
+        ident: FieldIdent,
+        value: String,
+    },
+    /// A new value for the field declared under `key` in
+    /// `AppConfig::dynamic_fields`
+    UpdateDynamicValue {
+        key: String,
         value: String,
     },
+    /// Several values at once, e.g. from saved data, rendered once. Like
+    /// `SetFieldValue`, it fetches no suggestions.
+    BatchUpdate(Vec<(FieldIdent, String)>),
+    /// A value filled in for the user, e.g. by autofill. Unlike
+    /// `UpdateValue`, the address only fetches suggestions if `fetch` is set.
+    SetFieldValue {
+        ident: FieldIdent,
+        value: String,
+        fetch: bool,
+    },
     TouchField {
-        ident: crate::events::form::FieldIdent,
+        ident: FieldIdent,
     },
     SetFieldEditing {
-        ident: crate::events::form::FieldIdent,
+        ident: FieldIdent,
         editing: bool,
     },
+    /// Reveals a masked field, or masks it again
+    ToggleFieldMask {
+        ident: FieldIdent,
+    },
     Submit,
+    /// Answer to the email availability check a submit waits for; `None`
+    /// if there was no usable answer
+    EmailChecked {
+        email: String,
+        available: Option<bool>,
+    },
+    /// Answer to the POST of a submit to the configured `submit_url`
+    SubmitResponseReceived(SubmitResult),
+    /// The shell's verdict on `value` of an async-validated field
+    AsyncValidationResult {
+        ident: FieldIdent,
+        value: String,
+        result: AsyncVerdict,
+    },
+    /// Checks the field's input against its configured `RemoteCheck`
+    ValidateFieldRemote {
+        ident: FieldIdent,
+    },
+    /// Answer to remote check `generation` of `ident`; `valid` is `None`
+    /// if there was no usable answer
+    RemoteValidationReceived {
+        ident: FieldIdent,
+        generation: u64,
+        valid: Option<bool>,
+        message: Option<String>,
+    },
+    /// The server accepted the submission made by an `HttpPost` hook
+    SubmissionAccepted {
+        receipt: SubmissionReceipt,
+    },
+    /// The server refused the submitted form
+    SubmitRejected {
+        errors: Vec<ServerFieldError>,
+    },
+    /// An error from outside the validator, e.g. the server, shown on the
+    /// field until its value changes; `None` withdraws it
+    SetFieldError {
+        ident: FieldIdent,
+        error: Option<String>,
+    },
     Edit,
+    /// Clears form data and transient UI state; config, providers and
+    /// other session state are kept
     ResetForm,
+    /// Back to the state right after the last accepted config
+    ResetAll,
+    /// The user left the form for good
+    Abandon {
+        discard_draft: bool,
+    },
+    /// Reverts the last run of edits, across all fields
+    Undo,
+    Redo,
+    /// Restores the field's value before its last change
+    UndoField {
+        ident: FieldIdent,
+    },
+    SetErrorDebounce {
+        ident: FieldIdent,
+        millis: Option<u64>,
+    },
+    ErrorDebounceElapsed {
+        ident: FieldIdent,
+        generation: u64,
+    },
+    /// The pause after address edit `generation` is over
+    AddressDebounceElapsed {
+        generation: u64,
+    },
+    /// The pause before the remote check numbered `generation` is over
+    RemoteCheckDebounceElapsed {
+        ident: FieldIdent,
+        generation: u64,
+    },
+    /// No edit followed edit `edit` within the undo window
+    UndoWindowElapsed {
+        edit: u64,
+    },
+    /// Sent periodically by the shell with today's date as `YYYY-MM-DD`.
+    Tick {
+        today: String,
+    },
+    SaveDraft,
+    /// Draft payload previously saved by the shell, as stored
+    RestoreDraft {
+        payload: String,
+    },
+    /// Puts the form back as `Form::snapshot` captured it
+    RestoreSnapshot(FormSnapshot),
+    /// The values have been saved: nothing is unsaved or touched any more
+    MarkAsPristine,
 
-    // Address events
+    // Suggestion events, for any field with autocomplete
     FetchSuggestions {
+        ident: FieldIdent,
         query: String,
     },
-    SuggestionsReceived(crate::events::address::AddressSuggestionsResult),
+    /// Asked for by the user, e.g. with a search button: fetched at once,
+    /// however short the query, and a typing pause still pending is dropped
+    FetchSuggestionsForced {
+        ident: FieldIdent,
+        query: String,
+    },
+    /// Answer to fetch `sequence` of the field; answers to older fetches
+    /// are dropped
+    SuggestionsReceived {
+        ident: FieldIdent,
+        sequence: u64,
+        result: crate::events::address::AddressSuggestionsResult,
+    },
+    /// Fetches the field's last query again, as from a retry button after
+    /// a failed fetch
+    RetrySuggestions {
+        ident: FieldIdent,
+    },
+    SelectCountry {
+        country: Option<String>,
+    },
+    /// The user's position, used to sort suggestions by proximity
+    SetUserLocation {
+        location: Option<Coordinates>,
+    },
     SelectSuggestion {
-        suggestion: AddressSuggestion,
+        suggestion: Address,
+    },
+    /// Selects by position in `address_suggestions`, reporting the rank
+    SelectSuggestionAt {
+        index: usize,
     },
     ClearSuggestions,
+
+    /// Asks for a `Diagnostics` report of recent activity
+    ExportDiagnostics,
+
+    /// A value from the shell's random source, seeding retry jitter when
+    /// the config gives no `random_seed`
+    JitterSeeded {
+        seed: u64,
+    },
+    /// A value from the shell's random source, salting the hashes of
+    /// values in diagnostics
+    ErrorSaltReceived {
+        salt: u64,
+    },
 }
 
 #[effect(typegen)]
 pub enum Effect {
     Render(RenderOperation),
     Http(HttpRequest),
+    Timer(TimerOperation),
+    Analytics(AnalyticsOperation),
+    Navigate(NavigateOperation),
+    Storage(StorageOperation),
+    Diagnostics(DiagnosticsOperation),
+    AsyncValidate(AsyncValidateOperation),
+    Random(RandomOperation),
+}
+
+/// Whether `event` is ignored while the form is locked (not editing).
+///
+/// Events that unlock or reset the form, deliver results of earlier
+/// requests, or only change configuration are always processed.
+pub fn requires_editing(event: &Event) -> bool {
+    match event {
+        Event::UpdateValue { .. }
+        | Event::BatchUpdate(_)
+        | Event::UpdateDynamicValue { .. }
+        | Event::SetFieldValue { .. }
+        | Event::TouchField { .. }
+        | Event::Submit
+        | Event::SelectCountry { .. }
+        | Event::RestoreDraft { .. }
+        | Event::RestoreSnapshot(_)
+        | Event::MarkAsPristine
+        | Event::Undo
+        | Event::Redo
+        | Event::UndoField { .. }
+        | Event::SelectSuggestion { .. }
+        | Event::SelectSuggestionAt { .. }
+        | Event::RetrySuggestions { .. } => true,
+        // Only an editable form is waiting on an email check, or on the
+        // server's answer to a submission: abandoning drops both
+        Event::EmailChecked { .. } | Event::SubmitResponseReceived(_) => true,
+        Event::ValidateFieldRemote { .. }
+        | Event::RemoteValidationReceived { .. }
+        | Event::AsyncValidationResult { .. } => true,
+        // or on a pause in typing
+        Event::AddressDebounceElapsed { .. }
+        | Event::RemoteCheckDebounceElapsed { .. }
+        | Event::UndoWindowElapsed { .. } => true,
+        // Leaving a field is always allowed, entering one is not
+        Event::SetFieldEditing { editing, .. } => *editing,
+        // Gated by `can_fetch_suggestions`, which counts the skip
+        Event::FetchSuggestions { .. } | Event::FetchSuggestionsForced { .. } => false,
+        Event::Configure(_)
+        | Event::SetStatusOverride(_)
+        | Event::AcknowledgeSignals { .. }
+        | Event::SubmissionAccepted { .. }
+        | Event::SubmitRejected { .. }
+        | Event::SetFieldError { .. }
+        | Event::Edit
+        | Event::ResetForm
+        | Event::ResetAll
+        | Event::Abandon { .. }
+        | Event::ToggleFieldMask { .. }
+        | Event::SetErrorDebounce { .. }
+        | Event::ErrorDebounceElapsed { .. }
+        | Event::Tick { .. }
+        | Event::SaveDraft
+        | Event::SuggestionsReceived { .. }
+        | Event::SetUserLocation { .. }
+        | Event::ClearSuggestions
+        | Event::ExportDiagnostics
+        | Event::JitterSeeded { .. }
+        | Event::ErrorSaltReceived { .. } => false,
+    }
+}
+
+/// Asks the shell's random source for the session's diagnostics salt.
+fn request_error_salt() -> Command<Effect, Event> {
+    Command::request_from_shell(RandomOperation)
+        .then_send(|RandomValue(salt)| Event::ErrorSaltReceived { salt })
+}
+
+/// Whether suggestions for `query` may be fetched now. Every fetch is
+/// checked here, including one queued before the form was locked. A
+/// `forced` fetch may be shorter than `min_suggestion_query_len`.
+fn can_fetch_suggestions(model: &Model, query: &str, forced: bool) -> Result<(), SkipReason> {
+    let form = model.form_handler.get_form();
+    if !form.is_editing {
+        Err(SkipReason::Locked)
+    } else if form.submitting {
+        Err(SkipReason::Submitting)
+    } else if query.trim().is_empty() {
+        Err(SkipReason::EmptyQuery)
+    } else if !forced && !is_suggestion_query(query, model.config.min_suggestion_query_len) {
+        Err(SkipReason::ShortQuery)
+    } else if !model.address_handler.has_endpoint() {
+        Err(SkipReason::NoEndpoint)
+    } else {
+        Ok(())
+    }
+}
+
+fn start_suggestion_fetch(
+    model: &mut Model,
+    ident: FieldIdent,
+    query: String,
+    forced: bool,
+) -> Command<Effect, Event> {
+    if let Err(reason) = can_fetch_suggestions(model, &query, forced) {
+        model.suggestion_log.record_skip(reason);
+        return Command::done();
+    }
+    if forced {
+        model.form_handler.cancel_address_pause();
+    }
+    let delays = model.jitter.schedule(&model.config.suggestion_retry);
+    let backoff = Backoff::next(&mut model.fetches, delays);
+    // Shows that suggestions are loading
+    model
+        .address_handler
+        .handle_fetch_suggestions_with_retries(ident, query, backoff)
+        .and(render())
 }
 
 #[derive(Default)]
@@ -75,37 +679,309 @@ impl crux_core::App for App {
     type Effect = Effect;
 
     fn update(&self, event: Event, model: &mut Model, _caps: &()) -> Command<Effect, Event> {
-        match event {
+        // Late responses and timers included: nothing reaches an abandoned
+        // form until it is reset.
+        if model.form_handler.get_form().abandoned
+            && !matches!(
+                event,
+                Event::ResetForm
+                    | Event::ResetAll
+                    | Event::Configure(_)
+                    | Event::JitterSeeded { .. }
+                    | Event::ErrorSaltReceived { .. }
+            )
+        {
+            return Command::done();
+        }
+
+        if requires_editing(&event) && !model.form_handler.get_form().is_editing {
+            // Stale suggestions must not linger over a locked form.
+            if matches!(
+                event,
+                Event::UpdateValue {
+                    ident: FieldIdent::Address,
+                    ..
+                } | Event::SetFieldValue {
+                    ident: FieldIdent::Address,
+                    ..
+                }
+            ) && !model.address_handler.get_suggestions().is_empty()
+            {
+                model.render_hints.record(vec![RenderHint::Suggestions]);
+                return model.address_handler.handle_clear_suggestions();
+            }
+            return Command::done();
+        }
+
+        if let Some(hints) = hints_for(&event) {
+            model.render_hints.record(hints);
+        }
+        // These dispatch an event back to the core before the shell renders
+        if matches!(
+            event,
+            Event::ResetForm | Event::SelectSuggestion { .. } | Event::SelectSuggestionAt { .. }
+        ) {
+            model.render_hints.merge_next();
+        }
+
+        let form_before = model
+            .status_override
+            .is_some()
+            .then(|| model.form_handler.view());
+        let cmd = match event {
+            Event::Configure(config) => match config.validate() {
+                Ok(()) => {
+                    let seed = match config.random_seed {
+                        Some(_) => Command::done(),
+                        None => Command::request_from_shell(RandomOperation)
+                            .then_send(|RandomValue(seed)| Event::JitterSeeded { seed }),
+                    };
+                    model.apply_config(config);
+                    // A new session, with a new salt
+                    seed.and(request_error_salt()).and(render())
+                }
+                Err(error) => {
+                    model.config_error = Some(error);
+                    render()
+                }
+            },
+            Event::SetStatusOverride(message) => {
+                model.status_override = message;
+                render()
+            }
+            Event::AcknowledgeSignals { up_to_seq } => {
+                model.form_handler.handle_acknowledge_signals(up_to_seq)
+            }
+
             // Form events
-            Event::UpdateValue { ident, value } => {
-                model.form_handler.handle_update_value(ident, value)
+            Event::UpdateValue { ident, value } => model.set_value(ident, value, true),
+            Event::BatchUpdate(values) => match values.first() {
+                Some(&(first, _)) => {
+                    let idents: Vec<FieldIdent> = values.iter().map(|(ident, _)| *ident).collect();
+                    // A step of its own, so one undo takes the batch back
+                    model.history.end_run(first);
+                    let cmd = model.edit(first, |handler| handler.handle_batch_update(values));
+                    model.history.end_run(first);
+                    cmd.and(model.record_errors(idents))
+                }
+                None => Command::done(),
+            },
+            Event::UpdateDynamicValue { key, value } => {
+                model.form_handler.handle_update_dynamic_value(&key, value)
+            }
+            Event::SetFieldValue {
+                ident,
+                value,
+                fetch,
+            } => model.set_value(ident, value, fetch),
+            Event::TouchField { ident } => {
+                model.dismiss_suggestions(Some(ident));
+                model.form_handler.handle_touch_field(ident)
             }
-            Event::TouchField { ident } => model.form_handler.handle_touch_field(ident),
             Event::SetFieldEditing { ident, editing } => {
+                if !editing {
+                    model.history.end_run(ident);
+                    model.dismiss_suggestions(Some(ident));
+                }
                 model.form_handler.handle_set_field_editing(ident, editing)
             }
-            Event::Submit => model.form_handler.handle_submit(),
+            Event::ToggleFieldMask { ident } => model.form_handler.handle_toggle_field_mask(ident),
+            Event::Submit => {
+                model.dismiss_suggestions(None);
+                let cmd = model.submit_with(|handler| handler.handle_submit());
+                cmd.and(model.record_errors(FieldIdent::ALL))
+            }
+            Event::EmailChecked { email, available } => {
+                model.submit_with(|handler| handler.handle_email_checked(email, available))
+            }
+            Event::SubmitResponseReceived(result) => {
+                model.submit_with(|handler| handler.handle_submit_response(result))
+            }
+            Event::AsyncValidationResult {
+                ident,
+                value,
+                result,
+            } => model
+                .form_handler
+                .handle_async_validation_result(ident, value, result),
+            Event::ValidateFieldRemote { ident } => {
+                model.form_handler.handle_validate_field_remote(ident)
+            }
+            Event::RemoteValidationReceived {
+                ident,
+                generation,
+                valid,
+                message,
+            } => model
+                .form_handler
+                .handle_remote_validation_received(ident, generation, valid, message),
+            Event::SubmissionAccepted { receipt } => {
+                model.receipt = Some(receipt);
+                render()
+            }
+            Event::SubmitRejected { errors } => model.form_handler.handle_submit_rejected(errors),
+            Event::SetFieldError { ident, error } => {
+                model.form_handler.handle_set_field_error(ident, error)
+            }
             Event::Edit => model.form_handler.handle_edit(),
-            Event::ResetForm => model.form_handler.handle_reset(),
+            Event::ResetForm => model.reset_form(),
+            Event::ResetAll => model.reset_all(),
+            Event::Abandon { discard_draft } => {
+                model.address_handler.clear();
+                model.history.clear();
+                model.form_handler.handle_abandon(discard_draft)
+            }
+            Event::Undo => match model.history.undo(model.form_handler.inputs()) {
+                Some(inputs) => model.form_handler.handle_restore_inputs(inputs),
+                None => Command::done(),
+            },
+            Event::Redo => match model.history.redo(model.form_handler.inputs()) {
+                Some(inputs) => model.form_handler.handle_restore_inputs(inputs),
+                None => Command::done(),
+            },
+            Event::UndoField { ident } => {
+                // A step of its own, so form-wide undo can take it back
+                model.history.end_run(ident);
+                let cmd = model.edit(ident, |handler| handler.handle_undo_field(ident));
+                model.history.end_run(ident);
+                cmd
+            }
+            Event::SetErrorDebounce { ident, millis } => {
+                model.form_handler.handle_set_error_debounce(ident, millis)
+            }
+            Event::AddressDebounceElapsed { generation } => model
+                .form_handler
+                .handle_address_debounce_elapsed(generation),
+            Event::RemoteCheckDebounceElapsed { ident, generation } => model
+                .form_handler
+                .handle_remote_check_debounce_elapsed(ident, generation),
+            Event::ErrorDebounceElapsed { ident, generation } => model
+                .form_handler
+                .handle_error_debounce_elapsed(ident, generation),
+            Event::UndoWindowElapsed { edit } => {
+                model.history.end_run_after(edit);
+                Command::done()
+            }
+            Event::Tick { today } => match NaiveDate::parse_from_str(&today, "%Y-%m-%d") {
+                Ok(today) => model.form_handler.handle_tick(today),
+                Err(_) => Command::done(),
+            },
+            Event::SaveDraft => model.form_handler.handle_save_draft(
+                model
+                    .config
+                    .draft_size_limit
+                    .unwrap_or(DEFAULT_DRAFT_SIZE_LIMIT),
+            ),
+            Event::RestoreDraft { payload } => model.form_handler.handle_restore_draft(payload),
+            Event::RestoreSnapshot(snapshot) => {
+                model.form_handler.handle_restore_snapshot(snapshot)
+            }
+            Event::MarkAsPristine => model.form_handler.handle_mark_as_pristine(),
 
             // Address events
-            Event::FetchSuggestions { query } => {
-                model.address_handler.handle_fetch_suggestions(query)
+            Event::FetchSuggestions { ident, query } => {
+                start_suggestion_fetch(model, ident, query, false)
+            }
+            Event::FetchSuggestionsForced { ident, query } => {
+                start_suggestion_fetch(model, ident, query, true)
             }
-            Event::SuggestionsReceived(result) => {
-                model.address_handler.handle_suggestions_received(result)
+            Event::SuggestionsReceived {
+                ident,
+                sequence,
+                result,
+            } => {
+                let outcome = match &result {
+                    AddressSuggestionsResult::Success(suggestions) => SuggestionOutcome::Ok {
+                        count: suggestions.len(),
+                    },
+                    AddressSuggestionsResult::Error => SuggestionOutcome::Error,
+                    AddressSuggestionsResult::Unreachable => SuggestionOutcome::Unreachable,
+                };
+                model
+                    .suggestion_log
+                    .record(model.address_handler.query_for(ident).to_string(), outcome);
+                let current = model.form_handler.get_form().input(ident);
+                // Typed on since: suggestions for the old text would flicker
+                if model.address_handler.query_for(ident) != current {
+                    model.address_handler.handle_answer_dropped(ident, sequence)
+                } else {
+                    model
+                        .address_handler
+                        .handle_suggestions_received(ident, sequence, result, &current)
+                }
+            }
+            Event::RetrySuggestions { ident } => {
+                model.address_handler.handle_retry_suggestions(ident)
+            }
+            Event::SelectCountry { country } => {
+                model.address_handler.handle_select_country(country)
+            }
+            Event::SetUserLocation { location } => {
+                model.address_handler.handle_set_user_location(location)
             }
             Event::SelectSuggestion { suggestion } => {
+                model
+                    .form_handler
+                    .expect_core_value(FieldIdent::Address, suggestion.display.clone());
                 model.address_handler.handle_select_suggestion(suggestion)
             }
+            Event::SelectSuggestionAt { index } => {
+                if let Some(suggestion) = model.address_handler.suggestion_at(index) {
+                    let value = suggestion.display.clone();
+                    model
+                        .form_handler
+                        .expect_core_value(FieldIdent::Address, value);
+                }
+                model.address_handler.handle_select_suggestion_at(index)
+            }
             Event::ClearSuggestions => model.address_handler.handle_clear_suggestions(),
+
+            Event::ExportDiagnostics => {
+                Command::notify_shell(DiagnosticsOperation::Report(DiagnosticsReport {
+                    field_errors: model.error_memory.report(),
+                    ..model.suggestion_log.report()
+                }))
+                .into()
+            }
+
+            Event::JitterSeeded { seed } => {
+                model.jitter = Jitter::seeded(seed);
+                Command::done()
+            }
+            Event::ErrorSaltReceived { salt } => {
+                model.error_memory.set_salt(salt);
+                Command::done()
+            }
+        };
+        if form_before.map_or(false, |before| before != model.form_handler.view()) {
+            model.status_override = None;
         }
+        cmd
     }
 
     fn view(&self, model: &Self::Model) -> Self::ViewModel {
+        let mut form = model.form_handler.view();
+        let invalid_fields = model.form_handler.get_form().invalid_fields();
+        if let Some(message) = &model.status_override {
+            form.status_message = message.clone();
+        }
         ViewModel {
-            form: model.form_handler.view(),
-            address_suggestions: model.address_handler.get_suggestions().to_vec(),
+            form,
+            address_suggestions: model.address_handler.view(),
+            suggestions_loading: model.address_handler.is_loading(FieldIdent::Address),
+            config_error: model.config_error.clone(),
+            signals: model.form_handler.signals().pending().to_vec(),
+            can_undo: model.history.can_undo(),
+            can_redo: model.history.can_redo(),
+            dynamic_fields: model.form_handler.get_form().dynamic.view(),
+            is_dirty: model.form_handler.get_form().is_dirty(),
+            dirty_fields: model.form_handler.get_form().dirty_fields(),
+            changed_since_submit: model.form_handler.get_form().changed_since_submit(),
+            error_count: invalid_fields.len(),
+            first_invalid_field: invalid_fields.first().copied(),
+            invalid_fields,
+            submission_mode: model.config.resubmit_policy.mode(model.receipt.as_ref()),
+            render_hints: model.render_hints.current().to_vec(),
         }
     }
 }
@@ -113,12 +989,247 @@ impl crux_core::App for App {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::events::form::FieldIdent;
+    use crate::capabilities::timer::{TimerKind, TimerOutput};
+    use crate::config::{RemoteCheck, ResubmitPolicy, RetryPolicy, SubmitHook};
+    use crate::descriptor::ValidatorSpec;
+    use crate::events::address::{AddressProvider, AddressSuggestionsResult};
+    use crate::events::diagnostics::SkippedFetches;
+    use crate::events::form::{
+        Form, FormStatus, FormSubmission, UsernamePolicy, ADDRESS_TOO_LONG_MESSAGE,
+        AGE_NOT_A_NUMBER_MESSAGE, AGE_REQUIRED_MESSAGE, DEFAULT_MAX_ADDRESS_LENGTH,
+        DEFAULT_REMOTE_CHECK_DEBOUNCE_MS, DEFAULT_SUGGESTION_DEBOUNCE_MS, EMAIL_TAKEN_MESSAGE,
+        REMOTE_CHECK_FAILED_MESSAGE, SUBMIT_UNREACHABLE_MESSAGE,
+    };
+    use crate::events::signals::Signal;
     use crux_core::App as _;
+    use crux_http::protocol::{HttpResponse, HttpResult};
+    use crux_http::HttpError;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_dirty_tracking() {
+        let app = App;
+        let mut model = Model::default();
+        let dirty = |model: &Model| {
+            let view = app.view(model);
+            (view.is_dirty, view.dirty_fields)
+        };
+        assert_eq!(dirty(&model), (false, vec![]));
+
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: "test@example.com".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(dirty(&model), (true, vec![FieldIdent::Email]));
+
+        // Editing back to the baseline is not a change
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: String::new(),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(dirty(&model), (false, vec![]));
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: "test@example.com".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+
+        let _ = app.update(Event::ResetForm, &mut model, &());
+        assert_eq!(dirty(&model), (false, vec![]));
+
+        // Submitting makes the values the baseline for later edits
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(Event::Submit, &mut model, &());
+        let _ = app.update(Event::Edit, &mut model, &());
+        assert_eq!(dirty(&model), (false, vec![]));
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: "other@example.com".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(dirty(&model), (true, vec![FieldIdent::Email]));
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Age,
+                value: "31".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(
+            dirty(&model),
+            (true, vec![FieldIdent::Email, FieldIdent::Age])
+        );
+        assert_eq!(
+            app.view(&model).form.status_message,
+            FormStatus::UnsavedChanges.message()
+        );
+    }
+
+    #[test]
+    fn test_changed_since_submit_survives_mark_as_pristine() {
+        let app = App;
+        let mut model = Model::default();
+        let update = |model: &mut Model, ident: FieldIdent, value: &str| {
+            let _ = app.update(
+                Event::UpdateValue {
+                    ident,
+                    value: value.to_string(),
+                },
+                model,
+                &(),
+            );
+        };
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(Event::Submit, &mut model, &());
+        let _ = app.update(Event::Edit, &mut model, &());
+        assert!(app.view(&model).changed_since_submit.is_empty());
+
+        update(&mut model, FieldIdent::Email, "other@example.com");
+        let _ = app.update(Event::MarkAsPristine, &mut model, &());
+        update(&mut model, FieldIdent::Age, "31");
+        let view = app.view(&model);
+        assert_eq!(view.dirty_fields, vec![FieldIdent::Age]);
+        assert_eq!(
+            view.changed_since_submit,
+            vec![FieldIdent::Email, FieldIdent::Age]
+        );
+
+        // Only the next submit starts over
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(app.view(&model).changed_since_submit.is_empty());
+    }
+
+    #[test]
+    fn test_mark_as_pristine_keeps_values_but_not_changes() {
+        let app = App;
+        let mut model = Model::default();
+        let update_email = |model: &mut Model, value: &str| {
+            let _ = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Email,
+                    value: value.to_string(),
+                },
+                model,
+                &(),
+            );
+        };
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(
+            Event::TouchField {
+                ident: FieldIdent::Email,
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(
+            app.view(&model).form.status_message,
+            FormStatus::UnsavedChanges.message()
+        );
+
+        let mut cmd = app.update(Event::MarkAsPristine, &mut model, &());
+        assert_eq!(effect_kinds(&mut cmd), vec!["render"]);
+        let view = app.view(&model);
+        assert!(!view.is_dirty);
+        assert!(view.dirty_fields.is_empty());
+        assert!(!view.form.email.touched);
+        assert_eq!(view.form.email.value, "test@example.com");
+        assert_eq!(view.form.status_message, FormStatus::Ready.message());
+
+        // The saved value is the baseline now
+        update_email(&mut model, "test@example.com");
+        assert!(!app.view(&model).form.email.dirty);
+        update_email(&mut model, "other@example.com");
+        let view = app.view(&model);
+        assert!(view.form.email.dirty);
+        assert_eq!(view.dirty_fields, vec![FieldIdent::Email]);
+        assert_eq!(
+            view.form.status_message,
+            FormStatus::UnsavedChanges.message()
+        );
+    }
+
+    #[test]
+    fn test_invalid_fields_through_the_lifecycle() {
+        let app = App;
+        let mut model = Model::default();
+        let invalid = |model: &Model| {
+            let view = app.view(model);
+            assert_eq!(view.error_count, view.invalid_fields.len());
+            assert_eq!(
+                view.error_count,
+                model.form_handler.get_form().error_count()
+            );
+            assert_eq!(
+                view.first_invalid_field,
+                view.invalid_fields.first().copied()
+            );
+            view.invalid_fields
+        };
+        assert_eq!(
+            invalid(&model),
+            vec![FieldIdent::Username, FieldIdent::Email, FieldIdent::Address]
+        );
+
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: "test@example.com".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Age,
+                value: "200".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(
+            invalid(&model),
+            vec![FieldIdent::Username, FieldIdent::Age, FieldIdent::Address]
+        );
+
+        fill_valid_form(&app, &mut model);
+        assert_eq!(invalid(&model), vec![]);
+        assert_eq!(app.view(&model).first_invalid_field, None);
+
+        let _ = app.update(Event::Submit, &mut model, &());
+        let _ = app.update(Event::Edit, &mut model, &());
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: "not-an-email".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(invalid(&model), vec![FieldIdent::Email]);
+        assert_eq!(
+            app.view(&model).first_invalid_field,
+            Some(FieldIdent::Email)
+        );
+    }
 
     #[test]
     fn test_update_value() {
-        let app = App::default();
+        let app = App;
         let mut model = Model::default();
 
         let mut cmd = app.update(
@@ -140,11 +1251,12 @@ mod tests {
 
     #[test]
     fn test_fetch_suggestions() {
-        let app = App::default();
-        let mut model = Model::default();
+        let app = App;
+        let mut model = suggesting_model();
 
         let mut cmd = app.update(
             Event::FetchSuggestions {
+                ident: FieldIdent::Address,
                 query: "test".to_string(),
             },
             &mut model,
@@ -155,15 +1267,3874 @@ mod tests {
         assert!(matches!(effect, Effect::Http(_)));
     }
 
+    const ADDRESS_API_URL: &str = "http://localhost:8000/api/suggestions";
+
+    /// A fresh model with a suggestion endpoint, as if configured with
+    /// only `address_api_url` but without the validation `Configure` runs.
+    fn suggesting_model() -> Model {
+        let mut model = Model::default();
+        model.config.address_api_url = Some(ADDRESS_API_URL.to_string());
+        model
+            .address_handler
+            .set_api_url(model.config.address_api_url.clone());
+        model
+    }
+
     #[test]
-    fn test_view_model() {
-        let app = App::default();
-        let model = Model::default();
-        let view = app.view(&model);
+    fn test_suggestions_use_the_configured_endpoint() {
+        let app = App;
+        let mut model = Model::default();
+        assert!(fetch_suggestions(&app, &mut model, "Baker").is_empty());
 
-        assert_eq!(view.form.username.value, "");
-        assert!(!view.form.submitted);
-        assert!(view.form.is_editing_form);
-        assert!(view.address_suggestions.is_empty());
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                address_api_url: Some("http://10.0.2.2:8000/api/suggestions".to_string()),
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        let mut cmd = app.update(
+            Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query: "Baker".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let Some(Effect::Http(request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        assert_eq!(
+            request.operation.url,
+            "http://10.0.2.2:8000/api/suggestions?query=Baker&limit=6"
+        );
+
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                address_api_url: Some("ftp://10.0.2.2/suggestions".to_string()),
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        assert!(matches!(
+            model.config_error,
+            Some(ConfigError::InvalidAddressApiUrl { .. })
+        ));
+        assert!(fetch_suggestions(&app, &mut model, "Baker").is_empty());
+    }
+
+    /// Types `value` into the address and waits out the typing pause,
+    /// returning the events that follow.
+    fn type_address(app: &App, model: &mut Model, value: &str) -> impl FnOnce() -> Vec<Event> {
+        let mut cmd = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Address,
+                value: value.to_string(),
+            },
+            model,
+            &(),
+        );
+        assert!(cmd.events().next().is_none());
+        let mut pause = cmd
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::Timer(request) => Some(request),
+                _ => None,
+            })
+            .expect("expected a debounce timer");
+        let TimerOperation::Start { id, millis } = pause.operation;
+        assert_eq!(TimerKind::of(id), Some(TimerKind::SuggestionDebounce));
+        assert_eq!(millis, DEFAULT_SUGGESTION_DEBOUNCE_MS);
+        move || {
+            pause.resolve(TimerOutput::Elapsed { id }).unwrap();
+            cmd.events().collect()
+        }
+    }
+
+    #[test]
+    fn test_address_fetch_waits_for_typing_pause() {
+        let app = App;
+        let mut model = Model::default();
+        let first_pause = type_address(&app, &mut model, "Bak");
+        let second_pause = type_address(&app, &mut model, "Baker");
+
+        // Typing went on, so the first pause fetches nothing
+        for event in first_pause() {
+            let mut cmd = app.update(event, &mut model, &());
+            assert!(cmd.events().next().is_none());
+            assert!(effect_kinds(&mut cmd).is_empty());
+        }
+        let fetches: Vec<Event> = second_pause()
+            .into_iter()
+            .flat_map(|event| {
+                app.update(event, &mut model, &())
+                    .events()
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(
+            fetches,
+            vec![Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query: "Baker".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_leaving_the_address_cancels_the_typing_pause() {
+        let app = App;
+        for leave in [
+            Event::SetFieldEditing {
+                ident: FieldIdent::Address,
+                editing: false,
+            },
+            Event::TouchField {
+                ident: FieldIdent::Address,
+            },
+        ] {
+            let mut model = suggesting_model();
+            let pause = type_address(&app, &mut model, "Baker");
+            let _ = app.update(leave, &mut model, &());
+            for event in pause() {
+                let mut cmd = app.update(event, &mut model, &());
+                assert!(cmd.events().next().is_none(), "nothing fetched");
+                assert!(effect_kinds(&mut cmd).is_empty());
+            }
+            assert!(app.view(&model).address_suggestions.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_short_address_clears_suggestions_instead_of_fetching() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                address_api_url: Some(ADDRESS_API_URL.to_string()),
+                suggestion_debounce_ms: 0,
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        // The URL of the request typing `value` sends, if any
+        let type_address = |model: &mut Model, value: &str| {
+            let mut cmd = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Address,
+                    value: value.to_string(),
+                },
+                model,
+                &(),
+            );
+            let mut urls = Vec::new();
+            for event in cmd.events().collect::<Vec<_>>() {
+                let mut cmd = app.update(event, model, &());
+                urls.extend(cmd.effects().filter_map(|effect| match effect {
+                    Effect::Http(request) => Some(request.operation.url.clone()),
+                    _ => None,
+                }));
+            }
+            urls.pop()
+        };
+
+        let url = type_address(&mut model, "Bak").expect("expected a suggestions request");
+        assert!(url.contains("?query=Bak&"), "{url}");
+        let _ = app.update(
+            Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 1,
+                result: AddressSuggestionsResult::Success(vec![sample_suggestion()]),
+            },
+            &mut model,
+            &(),
+        );
+        assert!(!model.address_handler.get_suggestions().is_empty());
+
+        assert_eq!(type_address(&mut model, "B"), None);
+        assert!(model.address_handler.get_suggestions().is_empty());
+
+        let url = type_address(&mut model, " 10 Downing St & Co ").expect("expected a request");
+        assert!(
+            url.contains("?query=10%20Downing%20St%20%26%20Co&"),
+            "{url}"
+        );
+    }
+
+    #[test]
+    fn test_rapid_typing_sends_one_request() {
+        let app = App;
+        let mut model = suggesting_model();
+        let pauses: Vec<_> = ["Bak", "Bake", "Baker", "Baker S", "Baker St"]
+            .into_iter()
+            .map(|value| type_address(&app, &mut model, value))
+            .collect();
+
+        // Every pause elapses eventually; only the last one is still current
+        let mut requests = 0;
+        for pause in pauses {
+            for event in pause() {
+                let mut cmd = app.update(event, &mut model, &());
+                for event in cmd.events().collect::<Vec<_>>() {
+                    let mut fetch = app.update(event, &mut model, &());
+                    requests += effect_kinds(&mut fetch)
+                        .into_iter()
+                        .filter(|kind| *kind == "http")
+                        .count();
+                }
+            }
+        }
+        assert_eq!(requests, 1);
+    }
+
+    #[test]
+    fn test_retry_suggestions_refetches_the_failed_query() {
+        let app = App;
+        let mut model = suggesting_model();
+        let retry = Event::RetrySuggestions {
+            ident: FieldIdent::Address,
+        };
+        let mut cmd = app.update(retry.clone(), &mut model, &());
+        assert!(cmd.events().next().is_none());
+        assert!(effect_kinds(&mut cmd).is_empty());
+
+        let fetch = Event::FetchSuggestions {
+            ident: FieldIdent::Address,
+            query: "Baker".to_string(),
+        };
+        let _ = app.update(
+            Event::SetFieldValue {
+                ident: FieldIdent::Address,
+                value: "Baker".to_string(),
+                fetch: false,
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(fetch.clone(), &mut model, &());
+        let _ = app.update(
+            Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 1,
+                result: AddressSuggestionsResult::Error,
+            },
+            &mut model,
+            &(),
+        );
+
+        let mut cmd = app.update(retry, &mut model, &());
+        let events: Vec<Event> = cmd.events().collect();
+        assert_eq!(events, vec![fetch]);
+        let mut cmd = app.update(events[0].clone(), &mut model, &());
+        let urls: Vec<String> = cmd
+            .effects()
+            .filter_map(|effect| match effect {
+                Effect::Http(request) => Some(request.operation.url.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(urls.len(), 1);
+        assert!(urls[0].contains("Baker"), "{}", urls[0]);
+    }
+
+    #[test]
+    fn test_answer_after_leaving_the_field_is_dropped() {
+        let app = App;
+        let mut model = suggesting_model();
+        let _ = app.update(
+            Event::SetFieldValue {
+                ident: FieldIdent::Address,
+                value: "Baker".to_string(),
+                fetch: false,
+            },
+            &mut model,
+            &(),
+        );
+        let mut cmd = app.update(
+            Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query: "Baker".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+
+        let _ = app.update(
+            Event::SetFieldEditing {
+                ident: FieldIdent::Address,
+                editing: false,
+            },
+            &mut model,
+            &(),
+        );
+        let effects = answer_suggestions(&app, &mut model, &mut cmd, Address::london_sample(3));
+        assert!(effects.is_empty());
+        assert!(model.address_handler.get_suggestions().is_empty());
+    }
+
+    /// Answers the suggestions request pending in `cmd` with `suggestions`
+    /// and feeds the outcome back, returning the effects that follow.
+    fn answer_suggestions(
+        app: &App,
+        model: &mut Model,
+        cmd: &mut Command<Effect, Event>,
+        suggestions: Vec<Address>,
+    ) -> Vec<&'static str> {
+        let mut request = cmd
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::Http(request) => Some(request),
+                _ => None,
+            })
+            .expect("expected a suggestions request");
+        request
+            .resolve(HttpResult::Ok(HttpResponse::ok().json(suggestions).build()))
+            .unwrap();
+        cmd.events()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flat_map(|event| effect_kinds(&mut app.update(event, model, &())))
+            .collect()
+    }
+
+    #[test]
+    fn test_suggestions_load_from_fetch_to_answer() {
+        let app = App;
+        let mut model = suggesting_model();
+        let loading = |model: &Model| app.view(model).suggestions_loading;
+        let fetch = |model: &mut Model| {
+            app.update(
+                Event::FetchSuggestions {
+                    ident: FieldIdent::Address,
+                    query: "Baker".to_string(),
+                },
+                model,
+                &(),
+            )
+        };
+
+        let pause = type_address(&app, &mut model, "Baker");
+        assert!(!loading(&model), "nothing is asked before the pause");
+        let followups: Vec<Event> = pause()
+            .into_iter()
+            .flat_map(|event| {
+                app.update(event, &mut model, &())
+                    .events()
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let mut cmd = match followups.as_slice() {
+            [fetch @ Event::FetchSuggestions { .. }] => app.update(fetch.clone(), &mut model, &()),
+            events => panic!("expected a fetch, got {events:?}"),
+        };
+        assert!(loading(&model));
+        assert_eq!(
+            answer_suggestions(&app, &mut model, &mut cmd, Address::london_sample(2)),
+            vec!["render"]
+        );
+        assert!(!loading(&model));
+        assert_eq!(app.view(&model).address_suggestions.len(), 2);
+
+        // Leaving the field drops the answer, so nothing is loading
+        let mut cmd = fetch(&mut model);
+        let _ = app.update(
+            Event::TouchField {
+                ident: FieldIdent::Address,
+            },
+            &mut model,
+            &(),
+        );
+        assert!(!loading(&model));
+        assert!(
+            answer_suggestions(&app, &mut model, &mut cmd, Address::london_sample(2)).is_empty()
+        );
+
+        // An answer for text typed over still ends the loading
+        let mut cmd = fetch(&mut model);
+        let _ = app.update(
+            Event::SetFieldValue {
+                ident: FieldIdent::Address,
+                value: "Baker Street".to_string(),
+                fetch: false,
+            },
+            &mut model,
+            &(),
+        );
+        assert!(loading(&model));
+        assert_eq!(
+            answer_suggestions(&app, &mut model, &mut cmd, Address::london_sample(2)),
+            vec!["render"]
+        );
+        assert!(!loading(&model));
+        assert!(app.view(&model).address_suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_out_of_order_answers_keep_the_newest_suggestions() {
+        let app = App;
+        let mut model = suggesting_model();
+        let fetch = |model: &mut Model, query: &str| {
+            let _ = app.update(
+                Event::SetFieldValue {
+                    ident: FieldIdent::Address,
+                    value: query.to_string(),
+                    fetch: false,
+                },
+                model,
+                &(),
+            );
+            app.update(
+                Event::FetchSuggestions {
+                    ident: FieldIdent::Address,
+                    query: query.to_string(),
+                },
+                model,
+                &(),
+            )
+        };
+        let mut bak = fetch(&mut model, "bak");
+        let mut baker = fetch(&mut model, "baker");
+
+        let effects = answer_suggestions(&app, &mut model, &mut baker, Address::london_sample(2));
+        assert_eq!(effects, vec!["render"]);
+        let effects = answer_suggestions(&app, &mut model, &mut bak, Address::london_sample(5));
+        assert!(effects.is_empty());
+        assert_eq!(
+            model.address_handler.get_suggestions(),
+            Address::london_sample(2)
+        );
+    }
+
+    #[test]
+    fn test_typed_address_is_percent_encoded_in_the_request() {
+        let app = App;
+        for (typed, encoded) in [
+            ("10 Downing St & Co", "query=10%20Downing%20St%20%26%20Co&"),
+            ("Flat #2", "query=Flat%20%232&"),
+            ("1+1 Lane", "query=1%2B1%20Lane&"),
+            ("a=b?", "query=a%3Db%3F&"),
+            ("Straße 5", "query=Stra%C3%9Fe%205&"),
+        ] {
+            let mut model = Model::default();
+            let _ = app.update(
+                Event::Configure(AppConfig {
+                    address_api_url: Some(ADDRESS_API_URL.to_string()),
+                    suggestion_debounce_ms: 0,
+                    ..Default::default()
+                }),
+                &mut model,
+                &(),
+            );
+            let mut cmd = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Address,
+                    value: typed.to_string(),
+                },
+                &mut model,
+                &(),
+            );
+            let fetch = cmd.events().next().expect("expected a fetch");
+            let mut cmd = app.update(fetch, &mut model, &());
+            let Some(Effect::Http(request)) = cmd.effects().next() else {
+                panic!("expected an http request");
+            };
+            assert!(
+                request.operation.url.contains(encoded),
+                "{typed:?} sent as {}",
+                request.operation.url
+            );
+        }
+    }
+
+    #[test]
+    fn test_stale_suggestions_are_dropped() {
+        let app = App;
+        let mut model = suggesting_model();
+        let _ = app.update(
+            Event::SetFieldValue {
+                ident: FieldIdent::Address,
+                value: "Baker".to_string(),
+                fetch: false,
+            },
+            &mut model,
+            &(),
+        );
+        for query in ["Bak", "Baker"] {
+            let _ = app.update(
+                Event::FetchSuggestions {
+                    ident: FieldIdent::Address,
+                    query: query.to_string(),
+                },
+                &mut model,
+                &(),
+            );
+        }
+        let received = |sequence| Event::SuggestionsReceived {
+            ident: FieldIdent::Address,
+            sequence,
+            result: AddressSuggestionsResult::Success(vec![sample_suggestion()]),
+        };
+
+        // The answer to "Bak" lands last
+        let _ = app.update(received(2), &mut model, &());
+        let _ = app.update(Event::ClearSuggestions, &mut model, &());
+        let _ = app.update(received(1), &mut model, &());
+        assert!(model.address_handler.get_suggestions().is_empty());
+
+        let _ = app.update(received(2), &mut model, &());
+        assert_eq!(
+            model.address_handler.get_suggestions(),
+            vec![sample_suggestion()]
+        );
+
+        // An answer for text the user has since changed is not shown either
+        let _ = app.update(Event::ClearSuggestions, &mut model, &());
+        let _ = app.update(
+            Event::SetFieldValue {
+                ident: FieldIdent::Address,
+                value: "Baker St".to_string(),
+                fetch: false,
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(received(2), &mut model, &());
+        assert!(model.address_handler.get_suggestions().is_empty());
+    }
+
+    /// Fails every request of `cmd` with `failure` and collects the retry
+    /// delays it schedules, until it gives up or a request is answered with
+    /// `success`.
+    fn retry_delays(
+        cmd: &mut Command<Effect, Event>,
+        failure: HttpResult,
+        success: Option<HttpResult>,
+    ) -> Vec<u64> {
+        let mut delays = Vec::new();
+        let mut timers = BTreeSet::new();
+        let mut failures = 0;
+        while let Some(effect) = cmd.effects().next() {
+            match effect {
+                Effect::Http(mut request) if success.is_some() && failures == 2 => {
+                    request.resolve(success.clone().unwrap()).unwrap();
+                }
+                Effect::Http(mut request) => {
+                    failures += 1;
+                    request.resolve(failure.clone()).unwrap();
+                }
+                Effect::Timer(mut request) => {
+                    let TimerOperation::Start { id, millis } = request.operation;
+                    assert_eq!(TimerKind::of(id), Some(TimerKind::RetryBackoff));
+                    timers.insert(id);
+                    delays.push(millis);
+                    request.resolve(TimerOutput::Elapsed { id }).unwrap();
+                }
+                _ => {}
+            }
+        }
+        assert!(timers.len() <= 1, "one fetch, one timer id");
+        delays
+    }
+
+    #[test]
+    fn test_failed_requests_retry_after_seeded_delays() {
+        let app = App;
+        let mut model = Model::default();
+        let policy = RetryPolicy {
+            max_retries: 2,
+            base_delay_ms: 100,
+            jitter_ms: 50,
+        };
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                address_api_url: Some(ADDRESS_API_URL.to_string()),
+                submit_hooks: vec![SubmitHook::HttpPost {
+                    url: "https://api.example.com/submit".to_string(),
+                }],
+                suggestion_retry: policy,
+                submission_retry: policy,
+                random_seed: Some(7),
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+
+        let mut fetch = app.update(
+            Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query: "london".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let unreachable = HttpResult::Err(HttpError::Io("connection refused".to_string()));
+        assert_eq!(retry_delays(&mut fetch, unreachable, None), vec![100, 224]);
+        assert!(matches!(
+            fetch.events().next(),
+            Some(Event::SuggestionsReceived {
+                result: AddressSuggestionsResult::Unreachable,
+                sequence: 1,
+                ..
+            })
+        ));
+
+        // The seed's sequence carries on into the submission's schedule
+        fill_valid_form(&app, &mut model);
+        let mut submit = app.update(Event::Submit, &mut model, &());
+        let accepted = HttpResult::Ok(
+            HttpResponse::ok()
+                .json(serde_json::json!({ "id": "r-1" }))
+                .build(),
+        );
+        let delays = retry_delays(
+            &mut submit,
+            HttpResult::Ok(HttpResponse::status(503).build()),
+            Some(accepted),
+        );
+        assert_eq!(delays, vec![112, 245]);
+        assert!(submit
+            .events()
+            .any(|event| matches!(event, Event::SubmissionAccepted { .. })));
+
+        // Client errors are not retried
+        let mut fetch = app.update(
+            Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query: "london".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let rejected = HttpResult::Ok(HttpResponse::status(404).build());
+        assert!(retry_delays(&mut fetch, rejected, None).is_empty());
+    }
+
+    #[test]
+    fn test_jitter_is_seeded_by_the_shell_without_a_config_seed() {
+        let app = App;
+        let mut model = Model::default();
+        let mut cmd = app.update(Event::Configure(AppConfig::default()), &mut model, &());
+        let mut request = cmd
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::Random(request) => Some(request),
+                _ => None,
+            })
+            .expect("expected a request for a random value");
+        request.resolve(RandomValue(42)).unwrap();
+        let seeded = cmd.events().next().expect("expected the seed");
+        assert_eq!(seeded, Event::JitterSeeded { seed: 42 });
+        let _ = app.update(seeded, &mut model, &());
+        assert_eq!(model.jitter, Jitter::seeded(42));
+
+        // A configured seed is used as is
+        let mut cmd = app.update(
+            Event::Configure(AppConfig {
+                random_seed: Some(7),
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        assert_eq!(effect_kinds(&mut cmd), vec!["random", "render"]);
+        assert_eq!(model.jitter, Jitter::seeded(7));
+    }
+
+    #[test]
+    fn test_timers_in_flight_have_their_own_ids() {
+        let app = App;
+        let mut model = Model::default();
+        let post = |url: &str| SubmitHook::HttpPost {
+            url: url.to_string(),
+        };
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_hooks: vec![
+                    post("https://api.example.com/submit"),
+                    post("https://backup.example.com/submit"),
+                ],
+                submission_retry: RetryPolicy {
+                    max_retries: 1,
+                    base_delay_ms: 100,
+                    jitter_ms: 0,
+                },
+                random_seed: Some(7),
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        let timer_id = |cmd: &mut Command<Effect, Event>| {
+            cmd.effects()
+                .find_map(|effect| match effect {
+                    Effect::Timer(request) => {
+                        let TimerOperation::Start { id, .. } = request.operation;
+                        Some(id)
+                    }
+                    _ => None,
+                })
+                .expect("expected a timer")
+        };
+
+        // Both fields are at their first edit
+        let debounced: Vec<u64> = [FieldIdent::Username, FieldIdent::Email]
+            .into_iter()
+            .map(|ident| {
+                let _ = app.update(
+                    Event::SetErrorDebounce {
+                        ident,
+                        millis: Some(300),
+                    },
+                    &mut model,
+                    &(),
+                );
+                timer_id(&mut app.update(
+                    Event::UpdateValue {
+                        ident,
+                        value: "x".to_string(),
+                    },
+                    &mut model,
+                    &(),
+                ))
+            })
+            .collect();
+        assert_ne!(debounced[0], debounced[1]);
+        assert!(debounced
+            .iter()
+            .all(|id| TimerKind::of(*id) == Some(TimerKind::ErrorDebounce)));
+
+        fill_valid_form(&app, &mut model);
+        let mut submit = app.update(Event::Submit, &mut model, &());
+        let mut backoffs = BTreeSet::new();
+        while let Some(effect) = submit.effects().next() {
+            match effect {
+                Effect::Http(mut request) => {
+                    let unavailable = HttpResult::Ok(HttpResponse::status(503).build());
+                    request.resolve(unavailable).unwrap();
+                }
+                Effect::Timer(request) => {
+                    let TimerOperation::Start { id, .. } = request.operation;
+                    backoffs.insert(id);
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(backoffs.len(), 2, "each hook waits on its own timer");
+    }
+
+    #[test]
+    fn test_set_field_value_fetches_only_when_asked() {
+        let app = App;
+        let mut model = Model::default();
+        let set_address = |fetch| Event::SetFieldValue {
+            ident: FieldIdent::Address,
+            value: "10 Downing Street".to_string(),
+            fetch,
+        };
+
+        let mut cmd = app.update(set_address(false), &mut model, &());
+        assert!(cmd.events().next().is_none());
+        assert_eq!(effect_kinds(&mut cmd), vec!["render", "timer"]);
+        assert_eq!(app.view(&model).form.address.value, "10 Downing Street");
+
+        // Fetched after the typing pause
+        let mut cmd = app.update(set_address(true), &mut model, &());
+        assert_eq!(effect_kinds(&mut cmd), vec!["timer", "render"]);
+    }
+
+    fn fill_valid_form(app: &App, model: &mut Model) {
+        for (ident, value) in [
+            (FieldIdent::Username, "TestUser"),
+            (FieldIdent::Email, "test@example.com"),
+            (FieldIdent::Age, "30"),
+            (FieldIdent::Address, "10 Downing Street"),
+        ] {
+            let _ = app.update(
+                Event::UpdateValue {
+                    ident,
+                    value: value.to_string(),
+                },
+                model,
+                &(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_age_required_by_config() {
+        let app = App;
+        let update_age = |model: &mut Model, value: &str| {
+            let _ = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Age,
+                    value: value.to_string(),
+                },
+                model,
+                &(),
+            );
+        };
+        let configure = |model: &mut Model, age_required| {
+            let _ = app.update(
+                Event::Configure(AppConfig {
+                    age_required,
+                    ..Default::default()
+                }),
+                model,
+                &(),
+            );
+        };
+
+        // Optional by default
+        let mut model = Model::default();
+        fill_valid_form(&app, &mut model);
+        update_age(&mut model, "");
+        assert!(!app.view(&model).form.age.required);
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(model.form_handler.get_form().submitted);
+
+        let mut model = Model::default();
+        configure(&mut model, true);
+        assert!(app.view(&model).form.age.required);
+        fill_valid_form(&app, &mut model);
+        update_age(&mut model, "");
+        assert!(model.form_handler.get_form().progress_percent() < 100);
+        let _ = app.update(
+            Event::TouchField {
+                ident: FieldIdent::Age,
+            },
+            &mut model,
+            &(),
+        );
+        let age = app.view(&model).form.age;
+        assert!(age.touched);
+        assert_eq!(age.display_error.as_deref(), Some(AGE_REQUIRED_MESSAGE));
+        assert_eq!(age.error_code.as_deref(), Some("age.required"));
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(!model.form_handler.get_form().submitted);
+
+        update_age(&mut model, "30");
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(model.form_handler.get_form().submitted);
+
+        // Toggling re-checks the empty age straight away
+        let mut model = Model::default();
+        fill_valid_form(&app, &mut model);
+        update_age(&mut model, "");
+        configure(&mut model, true);
+        assert_eq!(
+            app.view(&model).form.age.error.as_deref(),
+            Some(AGE_REQUIRED_MESSAGE)
+        );
+        assert!(!model.form_handler.get_form().can_submit());
+        configure(&mut model, false);
+        assert!(app.view(&model).form.age.valid);
+        assert!(model.form_handler.get_form().can_submit());
+    }
+
+    #[test]
+    fn test_age_that_is_not_a_number_is_an_error() {
+        let app = App;
+        let mut model = Model::default();
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(
+            Event::SetFieldEditing {
+                ident: FieldIdent::Age,
+                editing: true,
+            },
+            &mut model,
+            &(),
+        );
+        for (event, editing) in [
+            (
+                Event::UpdateValue {
+                    ident: FieldIdent::Age,
+                    value: "abc".to_string(),
+                },
+                true,
+            ),
+            (
+                Event::SetFieldEditing {
+                    ident: FieldIdent::Age,
+                    editing: false,
+                },
+                false,
+            ),
+        ] {
+            let _ = app.update(event, &mut model, &());
+            let age = app.view(&model).form.age;
+            assert_eq!(age.value, "abc", "editing: {editing}");
+            assert_eq!(age.error.as_deref(), Some(AGE_NOT_A_NUMBER_MESSAGE));
+            assert!(!model.form_handler.get_form().can_submit());
+        }
+        assert_eq!(model.form_handler.get_form().age.value, None);
+
+        // Also when the age is required
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                age_required: true,
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        assert_eq!(
+            app.view(&model).form.age.error.as_deref(),
+            Some(AGE_NOT_A_NUMBER_MESSAGE)
+        );
+
+        for (value, error) in [
+            ("", Some(AGE_REQUIRED_MESSAGE)),
+            ("99999999999", Some(AGE_NOT_A_NUMBER_MESSAGE)),
+            ("30", None),
+        ] {
+            let _ = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Age,
+                    value: value.to_string(),
+                },
+                &mut model,
+                &(),
+            );
+            assert_eq!(
+                app.view(&model).form.age.error.as_deref(),
+                error,
+                "{value:?}"
+            );
+        }
+        assert!(model.form_handler.get_form().can_submit());
+    }
+
+    #[test]
+    fn test_late_suggestions_do_not_land_on_a_submitted_form() {
+        let app = App;
+        let mut model = Model::default();
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(
+            Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query: "10 Downing Street".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(!model.form_handler.get_form().is_editing);
+
+        // A late response no longer lands on the submitted form
+        let _ = app.update(
+            Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 1,
+                result: AddressSuggestionsResult::Success(vec![Address {
+                    street: "10 Downing Street".to_string(),
+                    city: "London".to_string(),
+                    postcode: "SW1A 2AA".to_string(),
+                    country: "UK".to_string(),
+                    display: "10 Downing Street, London, SW1A 2AA UK".to_string(),
+                    lat: None,
+                    lon: None,
+                }]),
+            },
+            &mut model,
+            &(),
+        );
+        assert!(model.address_handler.get_suggestions().is_empty());
+
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Address,
+                value: "10 Down".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert!(model.address_handler.get_suggestions().is_empty());
+        assert_eq!(
+            model.form_handler.get_form().address.value,
+            "10 Downing Street"
+        );
+    }
+
+    #[test]
+    fn test_error_display_is_debounced() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::SetErrorDebounce {
+                ident: FieldIdent::Email,
+                millis: Some(300),
+            },
+            &mut model,
+            &(),
+        );
+
+        let mut timers = Vec::new();
+        for value in ["a", "ab"] {
+            let mut cmd = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Email,
+                    value: value.to_string(),
+                },
+                &mut model,
+                &(),
+            );
+            let timer = cmd
+                .effects()
+                .find_map(|effect| match effect {
+                    Effect::Timer(request) => Some(request),
+                    _ => None,
+                })
+                .expect("expected a timer effect");
+            timers.push((cmd, timer));
+
+            // validity updates immediately, the shown error lags behind
+            let view = app.view(&model);
+            assert!(!view.form.email.valid);
+            assert!(!view.form.can_submit);
+            assert_eq!(
+                view.form.email.display_error,
+                Some("Email cannot be empty".to_string())
+            );
+        }
+
+        // the stale timer does not change what is shown
+        let (mut first_cmd, mut first_timer) = timers.remove(0);
+        first_timer
+            .resolve(TimerOutput::Elapsed { id: 1 })
+            .expect("to resolve timer");
+        for event in first_cmd.events() {
+            let _ = app.update(event, &mut model, &());
+        }
+        assert_eq!(
+            app.view(&model).form.email.display_error,
+            Some("Email cannot be empty".to_string())
+        );
+
+        let (mut last_cmd, mut last_timer) = timers.remove(0);
+        last_timer
+            .resolve(TimerOutput::Elapsed { id: 2 })
+            .expect("to resolve timer");
+        for event in last_cmd.events() {
+            let _ = app.update(event, &mut model, &());
+        }
+        assert_eq!(
+            app.view(&model).form.email.display_error,
+            model.form_handler.get_form().email.error
+        );
+        assert_ne!(
+            app.view(&model).form.email.display_error,
+            Some("Email cannot be empty".to_string())
+        );
+    }
+
+    #[test]
+    fn test_submit_hooks_run_in_order_after_successful_submit() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_hooks: vec![
+                    SubmitHook::EmitAnalytics {
+                        name: "form_submitted".to_string(),
+                    },
+                    SubmitHook::Navigate {
+                        target: "/thanks".to_string(),
+                    },
+                ],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        assert!(model.config_error.is_none());
+        fill_valid_form(&app, &mut model);
+
+        let mut cmd = app.update(Event::Submit, &mut model, &());
+        let effects: Vec<Effect> = cmd.effects().collect();
+
+        assert_eq!(effects.len(), 3);
+        assert!(matches!(effects[0], Effect::Render(_)));
+        let Effect::Analytics(ref analytics) = effects[1] else {
+            panic!("expected analytics effect, got {:?}", effects[1]);
+        };
+        assert_eq!(
+            analytics.operation,
+            AnalyticsOperation::Event {
+                name: "form_submitted".to_string()
+            }
+        );
+        let Effect::Navigate(ref navigate) = effects[2] else {
+            panic!("expected navigate effect, got {:?}", effects[2]);
+        };
+        assert_eq!(navigate.operation.target, "/thanks");
+    }
+
+    /// Submits a valid form configured with an email check and answers the
+    /// check, returning the effects of handling the answer.
+    fn submit_with_email_check(app: &App, model: &mut Model, available: bool) -> Vec<Effect> {
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_hooks: vec![SubmitHook::HttpPost {
+                    url: "https://api.example.com/submit".to_string(),
+                }],
+                email_check_url: Some("https://api.example.com/email".to_string()),
+                ..Default::default()
+            }),
+            model,
+            &(),
+        );
+        fill_valid_form(app, model);
+
+        let mut cmd = app.update(Event::Submit, model, &());
+        let mut request = cmd
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::Http(request) => Some(request),
+                _ => None,
+            })
+            .expect("expected an email check");
+        assert_eq!(
+            request.operation.url,
+            "https://api.example.com/email?check=test%40example.com"
+        );
+        assert!(!model.form_handler.get_form().submitted);
+
+        request
+            .resolve(HttpResult::Ok(
+                HttpResponse::ok()
+                    .json(serde_json::json!({ "available": available }))
+                    .build(),
+            ))
+            .unwrap();
+        let checked = cmd.events().next().expect("expected the check result");
+        assert_eq!(
+            checked,
+            Event::EmailChecked {
+                email: "test@example.com".to_string(),
+                available: Some(available),
+            }
+        );
+        app.update(checked, model, &()).effects().collect()
+    }
+
+    #[test]
+    fn test_submit_proceeds_when_email_is_available() {
+        let app = App;
+        let mut model = Model::default();
+        let effects = submit_with_email_check(&app, &mut model, true);
+
+        assert!(model.form_handler.get_form().submitted);
+        let posts: Vec<&str> = effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::Http(request) => Some(request.operation.url.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(posts, vec!["https://api.example.com/submit"]);
+    }
+
+    #[test]
+    fn test_unreachable_email_check_stops_the_submit_with_a_message() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_hooks: vec![SubmitHook::HttpPost {
+                    url: "https://api.example.com/submit".to_string(),
+                }],
+                email_check_url: Some("https://api.example.com/email".to_string()),
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(model.form_handler.get_form().submitting);
+
+        let mut cmd = app.update(
+            Event::EmailChecked {
+                email: "test@example.com".to_string(),
+                available: None,
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(effect_kinds(&mut cmd), vec!["render"]);
+        assert!(!model.form_handler.get_form().submitting);
+        let view = app.view(&model);
+        assert!(!view.form.submitted);
+        assert_eq!(view.form.status_message, SUBMIT_UNREACHABLE_MESSAGE);
+    }
+
+    #[test]
+    fn test_submit_ignored_while_email_check_in_flight() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_hooks: vec![SubmitHook::HttpPost {
+                    url: "https://api.example.com/submit".to_string(),
+                }],
+                email_check_url: Some("https://api.example.com/email".to_string()),
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+
+        let mut cmd = app.update(Event::Submit, &mut model, &());
+        let mut request = cmd
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::Http(request) => Some(request),
+                _ => None,
+            })
+            .expect("expected an email check");
+        for _ in 0..2 {
+            let mut again = app.update(Event::Submit, &mut model, &());
+            assert!(effect_kinds(&mut again).is_empty());
+        }
+
+        request
+            .resolve(HttpResult::Ok(
+                HttpResponse::ok()
+                    .json(serde_json::json!({ "available": true }))
+                    .build(),
+            ))
+            .unwrap();
+        let checked = cmd.events().next().expect("expected the check result");
+        let mut cmd = app.update(checked, &mut model, &());
+        assert!(model.form_handler.get_form().submitted);
+        assert_eq!(
+            effect_kinds(&mut cmd)
+                .into_iter()
+                .filter(|kind| *kind == "http")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_abandoning_during_the_email_check_does_not_block_submit() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_hooks: vec![SubmitHook::HttpPost {
+                    url: "https://api.example.com/submit".to_string(),
+                }],
+                email_check_url: Some("https://api.example.com/email".to_string()),
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+
+        let mut cmd = app.update(Event::Submit, &mut model, &());
+        let mut request = cmd
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::Http(request) => Some(request),
+                _ => None,
+            })
+            .expect("expected an email check");
+        let _ = app.update(
+            Event::Abandon {
+                discard_draft: false,
+            },
+            &mut model,
+            &(),
+        );
+        assert!(!model.form_handler.get_form().submitting);
+        request
+            .resolve(HttpResult::Ok(
+                HttpResponse::ok()
+                    .json(serde_json::json!({ "available": true }))
+                    .build(),
+            ))
+            .unwrap();
+        let checked = cmd.events().next().expect("expected the check result");
+        let mut cmd = app.update(checked.clone(), &mut model, &());
+        assert!(
+            effect_kinds(&mut cmd).is_empty(),
+            "dropped by the locked form"
+        );
+
+        // An abandoned form only reopens through a reset
+        let _ = app.update(Event::ResetForm, &mut model, &());
+        fill_valid_form(&app, &mut model);
+        let mut cmd = app.update(checked, &mut model, &());
+        assert!(effect_kinds(&mut cmd).is_empty(), "no check in flight");
+        let mut cmd = app.update(Event::Submit, &mut model, &());
+        assert_eq!(effect_kinds(&mut cmd), vec!["http", "render"]);
+        assert!(model.form_handler.get_form().submitting);
+    }
+
+    #[test]
+    fn test_submit_rejects_taken_email() {
+        let app = App;
+        let mut model = Model::default();
+        let effects = submit_with_email_check(&app, &mut model, false);
+
+        let form = model.form_handler.get_form();
+        assert!(!form.submitted);
+        assert!(form.is_editing);
+        assert_eq!(
+            form.email.display_error.as_deref(),
+            Some(EMAIL_TAKEN_MESSAGE)
+        );
+        assert!(effects
+            .iter()
+            .all(|effect| matches!(effect, Effect::Render(_))));
+    }
+
+    #[test]
+    fn test_field_error_blocks_resubmit_until_the_value_changes() {
+        let app = App;
+        let mut model = Model::default();
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(Event::Submit, &mut model, &());
+        let mut cmd = app.update(
+            Event::SetFieldError {
+                ident: FieldIdent::Email,
+                error: Some(EMAIL_TAKEN_MESSAGE.to_string()),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(effect_kinds(&mut cmd), vec!["render"]);
+        let email = app.view(&model).form.email;
+        assert!(email.touched);
+        assert!(!email.valid);
+        assert!(email.has_server_error);
+        assert_eq!(email.display_error.as_deref(), Some(EMAIL_TAKEN_MESSAGE));
+
+        let _ = app.update(Event::Edit, &mut model, &());
+        assert!(!app.view(&model).form.can_submit);
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(!model.form_handler.get_form().submitted);
+        assert_eq!(
+            app.view(&model).form.email.display_error.as_deref(),
+            Some(EMAIL_TAKEN_MESSAGE)
+        );
+
+        // The next edit drops it and the local rules decide again
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: "other@".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let email = app.view(&model).form.email;
+        assert!(!email.has_server_error);
+        assert_eq!(
+            email.error.as_deref(),
+            Some("Please enter a valid email address (e.g. user@example.com)")
+        );
+
+        // Withdrawn without an edit
+        for error in [Some(EMAIL_TAKEN_MESSAGE.to_string()), None] {
+            let _ = app.update(
+                Event::SetFieldError {
+                    ident: FieldIdent::Email,
+                    error,
+                },
+                &mut model,
+                &(),
+            );
+        }
+        let email = app.view(&model).form.email;
+        assert!(!email.has_server_error);
+        assert_eq!(
+            email.error.as_deref(),
+            Some("Please enter a valid email address (e.g. user@example.com)")
+        );
+
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: "other@example.com".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert!(app.view(&model).form.can_submit);
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(model.form_handler.get_form().submitted);
+    }
+
+    #[test]
+    fn test_latest_remote_check_decides_the_field() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                remote_checks: vec![RemoteCheck {
+                    ident: FieldIdent::Username,
+                    url: "https://api.example.com/usernames".to_string(),
+                    on_edit: false,
+                    debounce_ms: 0,
+                }],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "Alice.Smith".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let check = Event::ValidateFieldRemote {
+            ident: FieldIdent::Username,
+        };
+        let mut checks: Vec<_> = (0..2)
+            .map(|_| {
+                let mut cmd = app.update(check.clone(), &mut model, &());
+                let request = cmd
+                    .effects()
+                    .find_map(|effect| match effect {
+                        Effect::Http(request) => Some(request),
+                        _ => None,
+                    })
+                    .expect("expected a remote check");
+                // Normalized, as compared for uniqueness
+                assert_eq!(
+                    request.operation.url,
+                    "https://api.example.com/usernames?value=alicesmith"
+                );
+                (cmd, request)
+            })
+            .collect();
+        assert!(app.view(&model).form.username.validating);
+
+        let mut answer = |index: usize, verdict: serde_json::Value| {
+            let (cmd, request) = &mut checks[index];
+            request
+                .resolve(HttpResult::Ok(HttpResponse::ok().json(verdict).build()))
+                .unwrap();
+            cmd.events().next().expect("expected the verdict")
+        };
+        let newer = answer(1, serde_json::json!({ "valid": false, "message": "Taken" }));
+        let older = answer(0, serde_json::json!({ "valid": true, "message": null }));
+
+        let mut cmd = app.update(newer, &mut model, &());
+        assert_eq!(effect_kinds(&mut cmd), vec!["render"]);
+        let username = app.view(&model).form.username;
+        assert!(!username.validating);
+        assert_eq!(username.display_error.as_deref(), Some("Taken"));
+
+        let mut cmd = app.update(older, &mut model, &());
+        assert!(effect_kinds(&mut cmd).is_empty(), "superseded");
+        assert!(!app.view(&model).form.can_submit);
+
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "alicia".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert!(app.view(&model).form.username.valid);
+    }
+
+    #[test]
+    fn test_username_is_checked_once_typing_pauses() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                remote_checks: vec![RemoteCheck {
+                    ident: FieldIdent::Username,
+                    url: "https://api.example.com/usernames".to_string(),
+                    on_edit: true,
+                    debounce_ms: DEFAULT_REMOTE_CHECK_DEBOUNCE_MS,
+                }],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+        // Returns the pause, if the edit waits for one
+        let type_username = |model: &mut Model, value: &str| {
+            let mut cmd = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Username,
+                    value: value.to_string(),
+                },
+                model,
+                &(),
+            );
+            let mut timer = cmd.effects().find_map(|effect| match effect {
+                Effect::Timer(request) => {
+                    let TimerOperation::Start { id, .. } = request.operation;
+                    (TimerKind::of(id) == Some(TimerKind::RemoteCheckDebounce)).then_some(request)
+                }
+                _ => None,
+            })?;
+            let TimerOperation::Start { id, millis } = timer.operation;
+            assert_eq!(millis, DEFAULT_REMOTE_CHECK_DEBOUNCE_MS);
+            timer.resolve(TimerOutput::Elapsed { id }).unwrap();
+            let elapsed = cmd.events().next();
+            elapsed
+        };
+        // Returns the request made once the pause is over, if any
+        let pause = |model: &mut Model, elapsed: Event| {
+            let mut cmd = app.update(elapsed, model, &());
+            let request = cmd.effects().find_map(|effect| match effect {
+                Effect::Http(request) => Some(request),
+                _ => None,
+            });
+            (cmd, request)
+        };
+        let answer = |(mut cmd, request): (
+            Command<Effect, Event>,
+            Option<crux_core::Request<HttpRequest>>,
+        ),
+                      response: HttpResult| {
+            let mut request = request.expect("expected a remote check");
+            request.resolve(response).unwrap();
+            cmd.events().next().expect("expected the verdict")
+        };
+        let verdict = |valid: bool, message: Option<&str>| {
+            HttpResult::Ok(
+                HttpResponse::ok()
+                    .json(serde_json::json!({ "valid": valid, "message": message }))
+                    .build(),
+            )
+        };
+
+        assert!(type_username(&mut model, "al").is_none(), "too short");
+
+        // Keystrokes within the pause send nothing
+        let typing = type_username(&mut model, "alic").unwrap();
+        let paused = type_username(&mut model, "alice").unwrap();
+        assert!(app.view(&model).form.username.validating, "waiting counts");
+        assert!(pause(&mut model, typing).1.is_none());
+        let stale = pause(&mut model, paused);
+        let request = stale.1.as_ref().expect("the pause sends the check");
+        assert_eq!(
+            request.operation.url,
+            "https://api.example.com/usernames?value=alice"
+        );
+
+        // An answer about an edited value comes too late
+        let latest = type_username(&mut model, "alicia").unwrap();
+        let latest = pause(&mut model, latest);
+        let latest = answer(latest, verdict(true, None));
+        let stale = answer(stale, verdict(false, Some("Username is already taken")));
+        let _ = app.update(latest, &mut model, &());
+        let _ = app.update(stale, &mut model, &());
+        let username = app.view(&model).form.username;
+        assert!(!username.validating);
+        assert!(username.valid, "the answer about alice came too late");
+
+        let taken = type_username(&mut model, "bob").unwrap();
+        let taken = answer(
+            pause(&mut model, taken),
+            verdict(false, Some("Username is already taken")),
+        );
+        let _ = app.update(taken, &mut model, &());
+        let view = app.view(&model).form;
+        assert_eq!(
+            view.username.display_error.as_deref(),
+            Some("Username is already taken")
+        );
+        assert!(!view.can_submit);
+
+        let failed = type_username(&mut model, "carol").unwrap();
+        let failed = answer(
+            pause(&mut model, failed),
+            HttpResult::Err(HttpError::Timeout),
+        );
+        let _ = app.update(failed, &mut model, &());
+        let view = app.view(&model).form;
+        assert!(!view.username.validating);
+        assert!(view.username.valid, "an unanswered check rejects nothing");
+        assert!(view.can_submit);
+    }
+
+    #[test]
+    fn test_shell_verdict_on_latest_input_decides_the_field() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                async_validated: vec![FieldIdent::Username],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+        let type_username = |model: &mut Model, value: &str| {
+            let mut cmd = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Username,
+                    value: value.to_string(),
+                },
+                model,
+                &(),
+            );
+            let request = cmd
+                .effects()
+                .find_map(|effect| match effect {
+                    Effect::AsyncValidate(request) => Some(request),
+                    _ => None,
+                })
+                .expect("expected an async validation");
+            assert_eq!(request.operation.value, value);
+            move |verdict| {
+                let mut request = request;
+                request.resolve(verdict).unwrap();
+                cmd.events().next().expect("expected the verdict")
+            }
+        };
+
+        let stale = type_username(&mut model, "alice");
+        let latest = type_username(&mut model, "alicia");
+        assert!(app.view(&model).form.username.validating);
+        assert!(!app.view(&model).form.can_submit);
+
+        let mut cmd = app.update(stale(AsyncVerdict::Valid), &mut model, &());
+        assert!(effect_kinds(&mut cmd).is_empty(), "superseded");
+        assert!(app.view(&model).form.username.validating);
+
+        let invalid = AsyncVerdict::Invalid {
+            message: "Taken".to_string(),
+        };
+        let _ = app.update(latest(invalid), &mut model, &());
+        let view = app.view(&model);
+        assert!(!view.form.username.validating);
+        assert_eq!(view.form.username.display_error.as_deref(), Some("Taken"));
+        assert!(!view.form.can_submit);
+
+        let accepted = type_username(&mut model, "alicja");
+        let _ = app.update(accepted(AsyncVerdict::Valid), &mut model, &());
+        assert!(app.view(&model).form.can_submit);
+    }
+
+    #[test]
+    fn test_shell_and_remote_checks_settle_separately() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                async_validated: vec![FieldIdent::Username],
+                remote_checks: vec![RemoteCheck {
+                    ident: FieldIdent::Username,
+                    url: "https://api.example.com/usernames".to_string(),
+                    on_edit: true,
+                    debounce_ms: 0,
+                }],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+        let type_username = |model: &mut Model, value: &str| {
+            let mut cmd = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Username,
+                    value: value.to_string(),
+                },
+                model,
+                &(),
+            );
+            let (mut shell, mut http) = (None, None);
+            for effect in cmd.effects() {
+                match effect {
+                    Effect::AsyncValidate(request) => shell = Some(request),
+                    Effect::Http(request) => http = Some(request),
+                    _ => {}
+                }
+            }
+            let (mut shell, mut http) = (shell.unwrap(), http.unwrap());
+            move |shell_verdict, remote_valid: bool| {
+                shell.resolve(shell_verdict).unwrap();
+                http.resolve(HttpResult::Ok(
+                    HttpResponse::ok()
+                        .json(serde_json::json!({ "valid": remote_valid, "message": null }))
+                        .build(),
+                ))
+                .unwrap();
+                let events: Vec<Event> = cmd.events().collect();
+                assert!(matches!(events[0], Event::AsyncValidationResult { .. }));
+                assert!(matches!(events[1], Event::RemoteValidationReceived { .. }));
+                events
+            }
+        };
+
+        let verdicts = type_username(&mut model, "alice")(AsyncVerdict::Valid, false);
+        let mut verdicts = verdicts.into_iter();
+        let _ = app.update(verdicts.next().unwrap(), &mut model, &());
+        assert!(app.view(&model).form.username.validating, "remote pending");
+        let _ = app.update(verdicts.next().unwrap(), &mut model, &());
+        let username = app.view(&model).form.username;
+        assert!(!username.validating);
+        assert_eq!(
+            username.display_error.as_deref(),
+            Some(REMOTE_CHECK_FAILED_MESSAGE)
+        );
+
+        let invalid = AsyncVerdict::Invalid {
+            message: "Reserved".to_string(),
+        };
+        let verdicts = type_username(&mut model, "alicia")(invalid, true);
+        for verdict in verdicts.into_iter().rev() {
+            let _ = app.update(verdict, &mut model, &());
+        }
+        let username = app.view(&model).form.username;
+        assert!(!username.validating);
+        assert_eq!(username.display_error.as_deref(), Some("Reserved"));
+    }
+
+    #[test]
+    fn test_submit_is_refused_while_a_check_is_pending() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                async_validated: vec![FieldIdent::Username],
+                remote_checks: vec![RemoteCheck {
+                    ident: FieldIdent::Email,
+                    url: "https://api.example.com/emails".to_string(),
+                    on_edit: false,
+                    debounce_ms: 0,
+                }],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+        let mut cmd = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "alice".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let mut request = cmd
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::AsyncValidate(request) => Some(request),
+                _ => None,
+            })
+            .expect("expected an async validation");
+
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(!model.form_handler.get_form().submitted, "username pending");
+
+        request.resolve(AsyncVerdict::Valid).unwrap();
+        let verdict = cmd.events().next().expect("expected the verdict");
+        let _ = app.update(verdict, &mut model, &());
+        let _ = app.update(
+            Event::ValidateFieldRemote {
+                ident: FieldIdent::Email,
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(!model.form_handler.get_form().submitted, "email pending");
+
+        // Only an edit withdraws the remote check
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: "other@example.com".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(model.form_handler.get_form().submitted);
+    }
+
+    #[test]
+    fn test_dynamic_fields_are_configured_edited_and_reset() {
+        let app = App;
+        let mut model = Model::default();
+        let nickname = |validators| crate::config::DynamicFieldSpec {
+            key: "nickname".to_string(),
+            validators,
+        };
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                dynamic_fields: vec![nickname(vec![ValidatorSpec::UsernamePolicy(
+                    UsernamePolicy::default(),
+                )])],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        assert_eq!(
+            app.view(&model).config_error,
+            Some(ConfigError::InvalidDynamicField { index: 0 })
+        );
+
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                dynamic_fields: vec![nickname(vec![ValidatorSpec::MinLength { min: 3 }])],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        let update = |model: &mut Model, value: &str| {
+            let mut cmd = app.update(
+                Event::UpdateDynamicValue {
+                    key: "nickname".to_string(),
+                    value: value.to_string(),
+                },
+                model,
+                &(),
+            );
+            effect_kinds(&mut cmd)
+        };
+        assert_eq!(update(&mut model, "Al"), vec!["render"]);
+        let field = &app.view(&model).dynamic_fields[0];
+        assert_eq!(field.key, "nickname");
+        assert_eq!(
+            field.field.display_error.as_deref(),
+            Some("Must be at least 3 characters")
+        );
+        assert_eq!(update(&mut model, "Alex"), vec!["render"]);
+        assert!(app.view(&model).dynamic_fields[0].field.valid);
+
+        // They count toward the form, and keep their rules through a save
+        fill_valid_form(&app, &mut model);
+        let mut model: Model = serde_json::from_value(serde_json::to_value(&model).unwrap())
+            .expect("model round-trips");
+        update(&mut model, "Al");
+        let view = app.view(&model);
+        assert_eq!(
+            view.dynamic_fields[0].field.display_error.as_deref(),
+            Some("Must be at least 3 characters")
+        );
+        assert!(!view.form.can_submit);
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(!model.form_handler.get_form().submitted);
+        update(&mut model, "Alex");
+        assert!(app.view(&model).form.can_submit);
+        assert_eq!(
+            model.form_handler.get_form().submission().dynamic,
+            [("nickname".to_string(), "Alex".to_string())]
+                .into_iter()
+                .collect::<indexmap::IndexMap<_, _>>()
+        );
+
+        let _ = app.update(Event::ResetForm, &mut model, &());
+        let field = &app.view(&model).dynamic_fields[0].field;
+        assert_eq!(field.value, "");
+        assert!(!field.valid);
+    }
+
+    const SUBMIT_URL: &str = "https://api.example.com/form";
+
+    /// Submits a valid form to `SUBMIT_URL`, checks what was POSTed, answers
+    /// with `answer` and returns the event that produced.
+    fn submit_to_server(app: &App, model: &mut Model, answer: HttpResult) -> Event {
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_url: Some(SUBMIT_URL.to_string()),
+                ..Default::default()
+            }),
+            model,
+            &(),
+        );
+        fill_valid_form(app, model);
+
+        let mut cmd = app.update(Event::Submit, model, &());
+        let mut request = cmd
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::Http(request) => Some(request),
+                _ => None,
+            })
+            .expect("expected the submission");
+        assert_eq!(request.operation.method, "POST");
+        assert_eq!(request.operation.url, SUBMIT_URL);
+        assert_eq!(
+            serde_json::from_slice::<FormSubmission>(&request.operation.body).unwrap(),
+            FormSubmission {
+                username: "TestUser".to_string(),
+                email: "test@example.com".to_string(),
+                age: Some(30),
+                address: "10 Downing Street".to_string(),
+                dynamic: Default::default(),
+            }
+        );
+        // Not submitted until the server says so
+        assert!(!model.form_handler.get_form().submitted);
+        let mut again = app.update(Event::Submit, model, &());
+        assert!(effect_kinds(&mut again).is_empty());
+
+        request.resolve(answer).unwrap();
+        let answer = cmd.events().next().expect("expected the server's answer");
+        answer
+    }
+
+    #[test]
+    fn test_submit_posts_the_form_and_locks_once_accepted() {
+        let app = App;
+        let mut model = Model::default();
+        let answer = submit_to_server(&app, &mut model, HttpResult::Ok(HttpResponse::ok().build()));
+        assert_eq!(
+            answer,
+            Event::SubmitResponseReceived(SubmitResult::Accepted)
+        );
+
+        let mut cmd = app.update(answer, &mut model, &());
+        assert!(effect_kinds(&mut cmd).contains(&"render"));
+        let form = model.form_handler.get_form();
+        assert!(form.submitted);
+        assert!(!form.is_editing);
+        assert_eq!(
+            app.view(&model).form.status_message,
+            FormStatus::Submitted.message()
+        );
+    }
+
+    #[test]
+    fn test_abandoning_during_a_submission_does_not_block_submit() {
+        let app = App;
+        let mut model = Model::default();
+        let answer = submit_to_server(&app, &mut model, HttpResult::Ok(HttpResponse::ok().build()));
+        let _ = app.update(
+            Event::Abandon {
+                discard_draft: false,
+            },
+            &mut model,
+            &(),
+        );
+        let mut cmd = app.update(answer.clone(), &mut model, &());
+        assert!(
+            effect_kinds(&mut cmd).is_empty(),
+            "dropped by the locked form"
+        );
+        let form = model.form_handler.get_form();
+        assert!(!form.submitting);
+        assert!(!form.submitted);
+
+        let _ = app.update(Event::ResetForm, &mut model, &());
+        fill_valid_form(&app, &mut model);
+        let mut cmd = app.update(answer, &mut model, &());
+        assert!(effect_kinds(&mut cmd).is_empty(), "no submission in flight");
+        assert!(!model.form_handler.get_form().submitted);
+        let mut cmd = app.update(Event::Submit, &mut model, &());
+        assert_eq!(effect_kinds(&mut cmd), vec!["http", "render"]);
+    }
+
+    #[test]
+    fn test_unreachable_server_keeps_the_form_editable() {
+        let app = App;
+        let mut model = Model::default();
+        let unreachable = HttpResult::Err(HttpError::Io("connection refused".to_string()));
+        let answer = submit_to_server(&app, &mut model, unreachable);
+        assert_eq!(
+            answer,
+            Event::SubmitResponseReceived(SubmitResult::Failed {
+                message: SUBMIT_UNREACHABLE_MESSAGE.to_string()
+            })
+        );
+
+        let _ = app.update(answer, &mut model, &());
+        let form = model.form_handler.get_form();
+        assert!(!form.submitted);
+        assert!(form.is_editing);
+        assert_eq!(
+            app.view(&model).form.status_message,
+            SUBMIT_UNREACHABLE_MESSAGE
+        );
+
+        // Editing moves on from the failure
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Age,
+                value: "31".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(
+            app.view(&model).form.status_message,
+            FormStatus::UnsavedChanges.message()
+        );
+    }
+
+    #[test]
+    fn test_refused_submission_reports_the_status_and_can_be_retried() {
+        let app = App;
+        // A rejected form and a failing server alike
+        for status in [422, 500] {
+            let mut model = Model::default();
+            let refused = HttpResult::Ok(HttpResponse::status(status).build());
+            let answer = submit_to_server(&app, &mut model, refused);
+            let _ = app.update(answer, &mut model, &());
+
+            let form = model.form_handler.get_form();
+            assert!(form.is_editing);
+            assert!(!form.submitted);
+            assert_eq!(
+                app.view(&model).form.status_message,
+                format!("The server refused the form (HTTP {status}).")
+            );
+            let mut cmd = app.update(Event::Submit, &mut model, &());
+            assert!(effect_kinds(&mut cmd).contains(&"http"));
+        }
+    }
+
+    /// Submits the form and answers its `HttpPost` hook with receipt `id`,
+    /// returning the hook's method and URL.
+    fn submit_and_accept(app: &App, model: &mut Model, id: &str) -> (String, String) {
+        let mut cmd = app.update(Event::Submit, model, &());
+        let mut request = cmd
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::Http(request) => Some(request),
+                _ => None,
+            })
+            .expect("expected the submit hook");
+        let sent = (
+            request.operation.method.clone(),
+            request.operation.url.clone(),
+        );
+        request
+            .resolve(HttpResult::Ok(
+                HttpResponse::ok()
+                    .json(serde_json::json!({ "id": id }))
+                    .build(),
+            ))
+            .unwrap();
+        for event in cmd.events() {
+            let _ = app.update(event, model, &());
+        }
+        sent
+    }
+
+    #[test]
+    fn test_resubmit_after_edit_follows_policy() {
+        const URL: &str = "https://api.example.com/submissions";
+        for (policy, mode, resubmission, receipt) in [
+            (
+                ResubmitPolicy::NewSubmission,
+                SubmissionMode::New,
+                ("POST", URL.to_string()),
+                "r-2",
+            ),
+            (
+                ResubmitPolicy::UpdateExisting,
+                SubmissionMode::Update,
+                ("PUT", format!("{URL}/r-1")),
+                "r-1",
+            ),
+        ] {
+            let app = App;
+            let mut model = Model::default();
+            let _ = app.update(
+                Event::Configure(AppConfig {
+                    submit_hooks: vec![SubmitHook::HttpPost {
+                        url: URL.to_string(),
+                    }],
+                    resubmit_policy: policy,
+                    ..Default::default()
+                }),
+                &mut model,
+                &(),
+            );
+            fill_valid_form(&app, &mut model);
+            assert_eq!(app.view(&model).submission_mode, SubmissionMode::New);
+
+            let sent = submit_and_accept(&app, &mut model, "r-1");
+            assert_eq!(sent, ("POST".to_string(), URL.to_string()));
+
+            let _ = app.update(Event::Edit, &mut model, &());
+            assert_eq!(app.view(&model).submission_mode, mode, "{policy:?}");
+
+            let (method, url) = submit_and_accept(&app, &mut model, receipt);
+            assert_eq!((method.as_str(), url), resubmission, "{policy:?}");
+            assert_eq!(
+                model.receipt,
+                Some(SubmissionReceipt {
+                    id: receipt.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_submit_success_renders_before_hooks() {
+        let hooks = vec![
+            SubmitHook::Navigate {
+                target: "/thanks".to_string(),
+            },
+            SubmitHook::HttpPost {
+                url: "https://api.example.com/submit".to_string(),
+            },
+            SubmitHook::EmitAnalytics {
+                name: "form_submitted".to_string(),
+            },
+        ];
+        let expected = vec!["render", "navigate", "http", "analytics"];
+
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_hooks: hooks.clone(),
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+        let mut cmd = app.update(Event::Submit, &mut model, &());
+        assert_eq!(effect_kinds(&mut cmd), expected);
+
+        // The same order when the submit completes after an email check
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_hooks: hooks,
+                email_check_url: Some("https://api.example.com/email".to_string()),
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(Event::Submit, &mut model, &());
+        let mut cmd = app.update(
+            Event::EmailChecked {
+                email: "test@example.com".to_string(),
+                available: Some(true),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(effect_kinds(&mut cmd), expected);
+    }
+
+    #[test]
+    fn test_submit_hooks_do_not_run_on_invalid_submit() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_hooks: vec![SubmitHook::Navigate {
+                    target: "/thanks".to_string(),
+                }],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+
+        let mut cmd = app.update(Event::Submit, &mut model, &());
+        assert!(cmd
+            .effects()
+            .all(|effect| matches!(effect, Effect::Render(_))));
+    }
+
+    #[test]
+    fn test_configure_rejects_invalid_hooks() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_hooks: vec![
+                    SubmitHook::EmitAnalytics {
+                        name: "form_submitted".to_string(),
+                    },
+                    SubmitHook::HttpPost {
+                        url: "not a url".to_string(),
+                    },
+                ],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(
+            app.view(&model).config_error,
+            Some(ConfigError::InvalidHookUrl {
+                index: 1,
+                url: "not a url".to_string()
+            })
+        );
+        assert!(model.config.submit_hooks.is_empty());
+    }
+
+    #[test]
+    fn test_configure_rejects_unknown_template_placeholder() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                address_providers: vec![
+                    AddressProvider::new("https://api.example.com"),
+                    AddressProvider {
+                        url_template: "/v1/autocomplete?text={query}&lang={language}".to_string(),
+                        ..AddressProvider::new("https://provider.example.com")
+                    },
+                ],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(
+            app.view(&model).config_error,
+            Some(ConfigError::InvalidProviderTemplate {
+                index: 1,
+                placeholder: "{language}".to_string()
+            })
+        );
+        assert!(model.config.address_providers.is_empty());
+    }
+
+    fn effect_kinds(cmd: &mut Command<Effect, Event>) -> Vec<&'static str> {
+        cmd.effects()
+            .map(|effect| match effect {
+                Effect::Render(_) => "render",
+                Effect::Http(_) => "http",
+                Effect::Timer(_) => "timer",
+                Effect::Analytics(_) => "analytics",
+                Effect::Navigate(_) => "navigate",
+                Effect::Storage(_) => "storage",
+                Effect::Diagnostics(_) => "diagnostics",
+                Effect::AsyncValidate(_) => "async_validate",
+                Effect::Random(_) => "random",
+            })
+            .collect()
+    }
+
+    fn sample_suggestion() -> Address {
+        Address {
+            street: "221B Baker Street".to_string(),
+            city: "London".to_string(),
+            postcode: "NW1 6XE".to_string(),
+            country: "UK".to_string(),
+            display: "221B Baker Street, London, NW1 6XE UK".to_string(),
+            lat: None,
+            lon: None,
+        }
+    }
+
+    /// Whether the model changed, and the effects produced.
+    type Outcome = (bool, Vec<&'static str>);
+
+    /// One sample of every event, with the expected outcome when editing
+    /// and when locked.
+    fn editing_guard_table() -> Vec<(Event, Outcome, Outcome)> {
+        let samples = vec![
+            Event::Configure(AppConfig {
+                submit_hooks: vec![SubmitHook::Navigate {
+                    target: "/thanks".to_string(),
+                }],
+                ..Default::default()
+            }),
+            Event::SetStatusOverride(Some("Saved to favorites".to_string())),
+            Event::AcknowledgeSignals {
+                up_to_seq: u64::MAX,
+            },
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "NewUser".to_string(),
+            },
+            Event::SetFieldValue {
+                ident: FieldIdent::Address,
+                value: "10 Downing Street".to_string(),
+                fetch: false,
+            },
+            Event::UpdateDynamicValue {
+                key: "nickname".to_string(),
+                value: "Al".to_string(),
+            },
+            Event::BatchUpdate(vec![(FieldIdent::Age, "40".to_string())]),
+            Event::TouchField {
+                ident: FieldIdent::Email,
+            },
+            Event::SetFieldEditing {
+                ident: FieldIdent::Username,
+                editing: true,
+            },
+            Event::SetFieldEditing {
+                ident: FieldIdent::Username,
+                editing: false,
+            },
+            Event::ToggleFieldMask {
+                ident: FieldIdent::Username,
+            },
+            Event::Submit,
+            Event::EmailChecked {
+                email: "test@example.com".to_string(),
+                available: Some(true),
+            },
+            Event::SubmitResponseReceived(SubmitResult::Accepted),
+            Event::AsyncValidationResult {
+                ident: FieldIdent::Username,
+                value: "Test  User".to_string(),
+                result: AsyncVerdict::Valid,
+            },
+            Event::ValidateFieldRemote {
+                ident: FieldIdent::Username,
+            },
+            Event::RemoteValidationReceived {
+                ident: FieldIdent::Username,
+                generation: 1,
+                valid: Some(false),
+                message: None,
+            },
+            Event::SubmissionAccepted {
+                receipt: SubmissionReceipt {
+                    id: "r-1".to_string(),
+                },
+            },
+            Event::SubmitRejected {
+                errors: vec![ServerFieldError {
+                    ident: FieldIdent::Email,
+                    message: "Email is already registered".to_string(),
+                }],
+            },
+            Event::SetFieldError {
+                ident: FieldIdent::Email,
+                error: Some("Email is already registered".to_string()),
+            },
+            Event::Edit,
+            Event::ResetForm,
+            Event::ResetAll,
+            Event::Abandon {
+                discard_draft: true,
+            },
+            Event::Undo,
+            Event::Redo,
+            Event::UndoField {
+                ident: FieldIdent::Email,
+            },
+            Event::SetErrorDebounce {
+                ident: FieldIdent::Email,
+                millis: Some(300),
+            },
+            Event::AddressDebounceElapsed { generation: 1 },
+            Event::RemoteCheckDebounceElapsed {
+                ident: FieldIdent::Username,
+                generation: 1,
+            },
+            Event::UndoWindowElapsed { edit: 1 },
+            Event::ErrorDebounceElapsed {
+                ident: FieldIdent::Email,
+                generation: 0,
+            },
+            Event::Tick {
+                today: "2030-01-01".to_string(),
+            },
+            Event::SaveDraft,
+            Event::RestoreDraft {
+                payload: r#"{"username":"Restored"}"#.to_string(),
+            },
+            Event::RestoreSnapshot({
+                let mut form = Form::default();
+                form.set_input(FieldIdent::Username, "Restored".to_string());
+                form.snapshot()
+            }),
+            Event::MarkAsPristine,
+            Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query: "Baker".to_string(),
+            },
+            Event::FetchSuggestionsForced {
+                ident: FieldIdent::Address,
+                query: "B".to_string(),
+            },
+            Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 0,
+                result: AddressSuggestionsResult::Success(vec![sample_suggestion()]),
+            },
+            Event::RetrySuggestions {
+                ident: FieldIdent::Address,
+            },
+            Event::SelectCountry {
+                country: Some("UK".to_string()),
+            },
+            Event::SetUserLocation {
+                location: Some(Coordinates {
+                    lat: 51.5,
+                    lon: -0.12,
+                }),
+            },
+            Event::SelectSuggestion {
+                suggestion: sample_suggestion(),
+            },
+            Event::SelectSuggestionAt { index: 0 },
+            Event::ClearSuggestions,
+            Event::ExportDiagnostics,
+            Event::JitterSeeded { seed: 7 },
+            Event::ErrorSaltReceived { salt: 7 },
+        ];
+
+        samples
+            .into_iter()
+            .map(|event| {
+                // No wildcard: a new event must be added to this table
+                let (editing, locked) = match &event {
+                    // No seed given, so the shell's random source is asked
+                    // for one as well as for the salt
+                    Event::Configure(_) => (
+                        (true, vec!["random", "random", "render"]),
+                        (true, vec!["random", "random", "render"]),
+                    ),
+                    Event::SetStatusOverride(_) => ((true, vec!["render"]), (true, vec!["render"])),
+                    // Only the locked form has a caret left to acknowledge
+                    Event::AcknowledgeSignals { .. } => ((false, vec![]), (true, vec!["render"])),
+                    Event::UpdateValue { .. } => ((true, vec!["render", "timer"]), (false, vec![])),
+                    Event::SetFieldValue { .. } => {
+                        ((true, vec!["render", "timer"]), (false, vec![]))
+                    }
+                    Event::BatchUpdate(_) => ((true, vec!["render", "timer"]), (false, vec![])),
+                    // No dynamic fields configured
+                    Event::UpdateDynamicValue { .. } => ((false, vec![]), (false, vec![])),
+                    Event::TouchField { .. } => ((true, vec!["render"]), (false, vec![])),
+                    Event::SetFieldEditing { editing: true, .. } => {
+                        ((true, vec!["render"]), (false, vec![]))
+                    }
+                    Event::SetFieldEditing { editing: false, .. } => {
+                        ((false, vec!["render"]), (false, vec!["render"]))
+                    }
+                    Event::ToggleFieldMask { .. } => {
+                        ((true, vec!["render"]), (true, vec!["render"]))
+                    }
+                    Event::Submit => ((true, vec!["render"]), (false, vec![])),
+                    // Stale: the fresh form's email is not the one checked
+                    Event::EmailChecked { .. } => ((false, vec![]), (false, vec![])),
+                    // Stale: no submission is in flight
+                    Event::SubmitResponseReceived(_) => ((false, vec![]), (false, vec![])),
+                    // No remote checks configured, so none in flight
+                    Event::ValidateFieldRemote { .. }
+                    | Event::RemoteValidationReceived { .. }
+                    | Event::AsyncValidationResult { .. } => ((false, vec![]), (false, vec![])),
+                    Event::SubmissionAccepted { .. } => {
+                        ((true, vec!["render"]), (true, vec!["render"]))
+                    }
+                    Event::SubmitRejected { .. } => {
+                        ((true, vec!["render"]), (true, vec!["render"]))
+                    }
+                    Event::SetFieldError { .. } => ((true, vec!["render"]), (true, vec!["render"])),
+                    Event::Edit => ((true, vec!["render"]), (true, vec!["render"])),
+                    Event::ResetForm => ((false, vec!["render"]), (true, vec!["render"])),
+                    // Applying the default config re-checks the username
+                    Event::ResetAll => ((true, vec!["render"]), (true, vec!["render"])),
+                    Event::Abandon { .. } => (
+                        (true, vec!["analytics", "storage", "render"]),
+                        (true, vec!["analytics", "storage", "render"]),
+                    ),
+                    // Nothing to undo or redo in a fresh model
+                    Event::Undo | Event::Redo => ((false, vec![]), (false, vec![])),
+                    Event::UndoField { .. } => ((false, vec![]), (false, vec![])),
+                    Event::SetErrorDebounce { .. } => {
+                        ((true, vec!["render"]), (true, vec!["render"]))
+                    }
+                    // Superseded: no address edit has been made
+                    Event::AddressDebounceElapsed { .. } => ((false, vec![]), (false, vec![])),
+                    // Superseded: no remote check is configured
+                    Event::RemoteCheckDebounceElapsed { .. } => ((false, vec![]), (false, vec![])),
+                    Event::ErrorDebounceElapsed { .. } => {
+                        ((false, vec!["render"]), (false, vec!["render"]))
+                    }
+                    Event::UndoWindowElapsed { .. } => ((false, vec![]), (false, vec![])),
+                    Event::Tick { .. } => ((true, vec![]), (true, vec![])),
+                    Event::SaveDraft => ((false, vec!["storage"]), (false, vec!["storage"])),
+                    Event::RestoreDraft { .. } | Event::RestoreSnapshot(_) => {
+                        ((true, vec!["render"]), (false, vec![]))
+                    }
+                    // Nothing edited or touched yet
+                    Event::MarkAsPristine => ((false, vec!["render"]), (false, vec![])),
+                    Event::FetchSuggestions { .. } | Event::FetchSuggestionsForced { .. } => {
+                        ((true, vec!["http", "render"]), (true, vec![]))
+                    }
+                    // Logged either way; the locked form's address is not
+                    // the query, so its answer is not shown
+                    Event::SuggestionsReceived { .. } => ((true, vec!["render"]), (true, vec![])),
+                    // Nothing fetched yet to retry
+                    Event::RetrySuggestions { .. } => ((false, vec![]), (false, vec![])),
+                    Event::SelectCountry { .. } => ((true, vec![]), (false, vec![])),
+                    Event::SetUserLocation { .. } => ((true, vec![]), (true, vec![])),
+                    // Remembers the value it sends back, for the caret hint
+                    Event::SelectSuggestion { .. } => ((true, vec!["render"]), (false, vec![])),
+                    // Nothing to select until suggestions arrive
+                    Event::SelectSuggestionAt { .. } => ((false, vec![]), (false, vec![])),
+                    Event::ClearSuggestions => ((false, vec!["render"]), (false, vec!["render"])),
+                    Event::ExportDiagnostics => {
+                        ((false, vec!["diagnostics"]), (false, vec!["diagnostics"]))
+                    }
+                    Event::JitterSeeded { .. } | Event::ErrorSaltReceived { .. } => {
+                        ((true, vec![]), (true, vec![]))
+                    }
+                };
+                (event, editing, locked)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_editing_guard_table() {
+        let app = App;
+
+        for (event, editing, locked) in editing_guard_table() {
+            assert_eq!(
+                requires_editing(&event),
+                locked == (false, vec![]),
+                "requires_editing disagrees with the table for {event:?}"
+            );
+
+            let mut editing_model = suggesting_model();
+            let mut locked_model = suggesting_model();
+            fill_valid_form(&app, &mut locked_model);
+            // Collapsed on leaving the field, which leaves a caret signal
+            for event in [
+                Event::UpdateValue {
+                    ident: FieldIdent::Username,
+                    value: "Test  User".to_string(),
+                },
+                Event::SetFieldEditing {
+                    ident: FieldIdent::Username,
+                    editing: false,
+                },
+            ] {
+                let _ = app.update(event, &mut locked_model, &());
+            }
+            let _ = app.update(Event::Submit, &mut locked_model, &());
+            assert!(!locked_model.form_handler.get_form().is_editing);
+
+            for (model, (expect_changed, expect_effects), mode) in [
+                (&mut editing_model, editing, "editing"),
+                (&mut locked_model, locked, "locked"),
+            ] {
+                let before = serde_json::to_value(&*model).unwrap();
+                let mut cmd = app.update(event.clone(), model, &());
+                let effects = effect_kinds(&mut cmd);
+                let after = serde_json::to_value(&*model).unwrap();
+
+                assert_eq!(
+                    before != after,
+                    expect_changed,
+                    "{event:?} while {mode}: unexpected model change"
+                );
+                assert_eq!(
+                    effects, expect_effects,
+                    "{event:?} while {mode}: unexpected effects"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejected_submit_can_be_corrected_and_resubmitted() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                submit_hooks: vec![SubmitHook::HttpPost {
+                    url: "https://api.example.com/submit".to_string(),
+                }],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+        let mut cmd = app.update(Event::Submit, &mut model, &());
+        assert!(effect_kinds(&mut cmd).contains(&"http"));
+
+        let _ = app.update(
+            Event::SubmitRejected {
+                errors: vec![ServerFieldError {
+                    ident: FieldIdent::Email,
+                    message: "Email is already registered".to_string(),
+                }],
+            },
+            &mut model,
+            &(),
+        );
+        let view = app.view(&model);
+        assert!(view.form.is_editing_form);
+        assert!(!view.form.submitted);
+        assert!(!view.form.can_submit);
+        assert_eq!(
+            view.form.email.display_error.as_deref(),
+            Some("Email is already registered")
+        );
+
+        // Editing another field keeps the server's verdict on the email
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "OtherUser".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert!(!app.view(&model).form.email.valid);
+
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: "other@example.com".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let mut cmd = app.update(Event::Submit, &mut model, &());
+        assert!(effect_kinds(&mut cmd).contains(&"http"));
+        assert!(app.view(&model).form.submitted);
+    }
+
+    fn abandon(app: &App, model: &mut Model, discard_draft: bool) -> Vec<Effect> {
+        let mut cmd = app.update(Event::Abandon { discard_draft }, model, &());
+        cmd.effects().collect()
+    }
+
+    #[test]
+    fn test_abandon_reports_progress_and_handles_draft() {
+        let app = App;
+        let mut model = Model::default();
+        for (ident, value) in [
+            (FieldIdent::Username, "TestUser"),
+            (FieldIdent::Email, "test@example.com"),
+            (FieldIdent::PreferredName, "Tess"),
+        ] {
+            let _ = app.update(
+                Event::UpdateValue {
+                    ident,
+                    value: value.to_string(),
+                },
+                &mut model,
+                &(),
+            );
+        }
+
+        let effects = abandon(&app, &mut model, false);
+        let analytics: Vec<&AnalyticsOperation> = effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::Analytics(request) => Some(&request.operation),
+                _ => None,
+            })
+            .collect();
+        // username and email of the three required fields
+        assert_eq!(
+            analytics,
+            vec![&AnalyticsOperation::FormAbandoned {
+                progress_percent: 66,
+                dirty_field_count: 3,
+            }]
+        );
+        assert!(!effects
+            .iter()
+            .any(|effect| matches!(effect, Effect::Storage(_))));
+        assert!(app.view(&model).form.abandoned);
+
+        let mut discarding = Model::default();
+        let effects = abandon(&app, &mut discarding, true);
+        let deletes: Vec<&StorageOperation> = effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::Storage(request) => Some(&request.operation),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            deletes,
+            vec![&StorageOperation::Delete {
+                key: crate::events::draft::DRAFT_KEY.to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_abandoned_form_ignores_events_until_reset() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query: "Baker".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let _ = abandon(&app, &mut model, false);
+
+        // the outstanding fetch resolves after the user has left
+        for event in [
+            Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 1,
+                result: AddressSuggestionsResult::Success(vec![sample_suggestion()]),
+            },
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "TestUser".to_string(),
+            },
+            Event::Edit,
+            Event::Abandon {
+                discard_draft: true,
+            },
+        ] {
+            let mut cmd = app.update(event, &mut model, &());
+            assert!(effect_kinds(&mut cmd).is_empty());
+        }
+        let view = app.view(&model);
+        assert!(view.address_suggestions.is_empty());
+        assert_eq!(view.form.username.value, "");
+        assert!(!view.form.is_editing_form);
+
+        let _ = app.update(Event::ResetForm, &mut model, &());
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "TestUser".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let view = app.view(&model);
+        assert!(!view.form.abandoned);
+        assert!(view.form.is_editing_form);
+        assert_eq!(view.form.username.value, "TestUser");
+    }
+
+    #[test]
+    fn test_render_hints() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: "test@example.com".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(
+            app.view(&model).render_hints,
+            vec![
+                RenderHint::Fields(vec![FieldIdent::Email]),
+                RenderHint::Status,
+                RenderHint::Signals
+            ]
+        );
+
+        let _ = app.update(
+            Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 0,
+                result: AddressSuggestionsResult::Success(vec![sample_suggestion()]),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(app.view(&model).render_hints, vec![RenderHint::Suggestions]);
+
+        // Submit dispatches ClearSuggestions before the shell renders
+        fill_valid_form(&app, &mut model);
+        let mut cmd = app.update(Event::Submit, &mut model, &());
+        for event in cmd.events() {
+            let _ = app.update(event, &mut model, &());
+        }
+        assert_eq!(app.view(&model).render_hints, vec![RenderHint::Whole]);
+
+        // The next shell event starts over
+        let _ = app.update(Event::Edit, &mut model, &());
+        let _ = app.update(
+            Event::TouchField {
+                ident: FieldIdent::Age,
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(
+            app.view(&model).render_hints,
+            vec![
+                RenderHint::Fields(vec![FieldIdent::Age]),
+                RenderHint::Status
+            ]
+        );
+    }
+
+    #[test]
+    fn test_view_model() {
+        let app = App;
+        let model = Model::default();
+        let view = app.view(&model);
+
+        assert_eq!(view.form.username.value, "");
+        assert!(!view.form.submitted);
+        assert!(view.form.is_editing_form);
+        assert!(view.address_suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_undo_redo_across_fields() {
+        let app = App;
+        let mut model = Model::default();
+        let update = |model: &mut Model, ident: FieldIdent, value: &str| {
+            let _ = app.update(
+                Event::UpdateValue {
+                    ident,
+                    value: value.to_string(),
+                },
+                model,
+                &(),
+            );
+        };
+        update(&mut model, FieldIdent::Username, "al");
+        update(&mut model, FieldIdent::Username, "alice");
+        let _ = app.update(
+            Event::SetFieldEditing {
+                ident: FieldIdent::Username,
+                editing: false,
+            },
+            &mut model,
+            &(),
+        );
+        update(&mut model, FieldIdent::Email, "alice@example.com");
+        update(&mut model, FieldIdent::Address, "221B Baker Street");
+        let _ = app.update(
+            Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 0,
+                result: AddressSuggestionsResult::Success(vec![sample_suggestion()]),
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::SelectSuggestionAt { index: 0 }, &mut model, &());
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Address,
+                value: sample_suggestion().display,
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::TouchField {
+                ident: FieldIdent::Email,
+            },
+            &mut model,
+            &(),
+        );
+
+        // The typed address and the selected one are a single run
+        let mut cmd = app.update(Event::Undo, &mut model, &());
+        assert_eq!(effect_kinds(&mut cmd), vec!["render"]);
+        let form = model.form_handler.get_form();
+        assert_eq!(form.address.value, "");
+        assert!(!form.address.dirty);
+        assert!(form.email.touched);
+        assert!(app.view(&model).address_suggestions.is_empty());
+
+        let _ = app.update(Event::Undo, &mut model, &());
+        let form = model.form_handler.get_form();
+        assert_eq!(form.email.value.0, "");
+        assert!(!form.email.valid);
+        assert_eq!(form.username.value.0, "alice");
+
+        let _ = app.update(Event::Undo, &mut model, &());
+        assert_eq!(model.form_handler.get_form().username.value.0, "");
+        let view = app.view(&model);
+        assert!(!view.can_undo);
+        assert!(view.can_redo);
+
+        let _ = app.update(Event::Redo, &mut model, &());
+        let _ = app.update(Event::Redo, &mut model, &());
+        let form = model.form_handler.get_form();
+        assert_eq!(form.username.value.0, "alice");
+        assert_eq!(form.email.value.0, "alice@example.com");
+        assert!(form.email.valid);
+
+        update(&mut model, FieldIdent::Age, "30");
+        let view = app.view(&model);
+        assert!(view.can_undo);
+        assert!(!view.can_redo);
+
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(Event::Submit, &mut model, &());
+        let submitted = model.form_handler.inputs();
+        assert!(model.form_handler.get_form().submitted);
+        let _ = app.update(Event::Undo, &mut model, &());
+        assert!(model.form_handler.get_form().submitted);
+        assert_eq!(model.form_handler.inputs(), submitted);
+    }
+
+    #[test]
+    fn test_a_pause_in_typing_ends_an_undo_step() {
+        let app = App;
+        let mut model = Model::default();
+        // Returns the event sent once the edit's undo window is over
+        let type_username = |model: &mut Model, value: &str| {
+            let mut cmd = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Username,
+                    value: value.to_string(),
+                },
+                model,
+                &(),
+            );
+            let mut timer = cmd
+                .effects()
+                .find_map(|effect| match effect {
+                    Effect::Timer(request) => Some(request),
+                    _ => None,
+                })
+                .expect("an undo window");
+            let TimerOperation::Start { id, millis } = timer.operation;
+            assert_eq!(TimerKind::of(id), Some(TimerKind::UndoWindow));
+            assert_eq!(millis, UNDO_WINDOW_MS);
+            timer.resolve(TimerOutput::Elapsed { id }).unwrap();
+            let elapsed = cmd.events().next().expect("the window's end");
+            elapsed
+        };
+        let al = type_username(&mut model, "al");
+        let ali = type_username(&mut model, "ali");
+        // Typing went on, so the first window's end is stale
+        let _ = app.update(al, &mut model, &());
+        let _ = app.update(ali, &mut model, &());
+        let _ = type_username(&mut model, "alice");
+
+        let _ = app.update(Event::Undo, &mut model, &());
+        assert_eq!(model.form_handler.get_form().username.value.0, "ali");
+        let _ = app.update(Event::Undo, &mut model, &());
+        assert_eq!(model.form_handler.get_form().username.value.0, "");
+        assert!(!app.view(&model).can_undo);
+    }
+
+    #[test]
+    fn test_over_long_address_is_flagged_but_suggestions_fit() {
+        let app = App;
+        let mut model = Model::default();
+        let address = |model: &mut Model, value: String| {
+            let _ = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Address,
+                    value,
+                },
+                model,
+                &(),
+            );
+            app.view(model).form.address
+        };
+
+        let typed = address(&mut model, "a".repeat(DEFAULT_MAX_ADDRESS_LENGTH + 1));
+        assert_eq!(typed.error.as_deref(), Some(ADDRESS_TOO_LONG_MESSAGE));
+        assert!(!typed.valid);
+
+        let mut cmd = app.update(
+            Event::SelectSuggestion {
+                suggestion: sample_suggestion(),
+            },
+            &mut model,
+            &(),
+        );
+        for event in cmd.events().collect::<Vec<_>>() {
+            let _ = app.update(event, &mut model, &());
+        }
+        let selected = app.view(&model).form.address;
+        assert_eq!(selected.value, sample_suggestion().display);
+        assert!(selected.valid);
+
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                max_address_length: 10,
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        assert_eq!(
+            app.view(&model).form.address.error.as_deref(),
+            Some(ADDRESS_TOO_LONG_MESSAGE)
+        );
+        assert!(address(&mut model, "10 Downing".to_string()).valid);
+    }
+
+    #[test]
+    fn test_batch_update_sets_every_field_with_one_render() {
+        let app = App;
+        let mut model = Model::default();
+        let values = [
+            (FieldIdent::Username, "TestUser"),
+            (FieldIdent::Email, "test@example.com"),
+            (FieldIdent::Age, "30"),
+            (FieldIdent::Address, "10 Downing Street"),
+        ];
+        let mut cmd = app.update(
+            Event::BatchUpdate(
+                values
+                    .iter()
+                    .map(|(ident, value)| (*ident, value.to_string()))
+                    .collect(),
+            ),
+            &mut model,
+            &(),
+        );
+        assert!(cmd.events().next().is_none());
+        // and one undo window
+        assert_eq!(effect_kinds(&mut cmd), vec!["render", "timer"]);
+
+        let view = app.view(&model);
+        for (ident, value) in values {
+            let field = view.form.field(ident);
+            assert_eq!(field.value, value, "{ident:?}");
+            assert!(field.dirty && field.valid, "{ident:?}");
+        }
+        assert!(view.form.can_submit);
+        assert_eq!(
+            view.form.status_message,
+            FormStatus::UnsavedChanges.message()
+        );
+
+        // One undo takes the whole batch back
+        let _ = app.update(Event::Undo, &mut model, &());
+        assert!(!app.view(&model).is_dirty);
+    }
+
+    #[test]
+    fn test_undo_field_reverts_one_field_and_is_a_form_undo_step() {
+        let app = App;
+        let mut model = Model::default();
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Age,
+                value: "200".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert!(!app.view(&model).form.age.valid);
+        assert!(app.view(&model).form.age.undoable);
+
+        let undo_age = Event::UndoField {
+            ident: FieldIdent::Age,
+        };
+        let mut cmd = app.update(undo_age.clone(), &mut model, &());
+        assert_eq!(effect_kinds(&mut cmd), vec!["render", "timer"]);
+        let view = app.view(&model);
+        assert_eq!(view.form.age.value, "30");
+        assert!(view.form.age.valid);
+        assert!(view.form.age.dirty);
+        assert!(!view.form.age.undoable);
+        assert_eq!(view.form.username.value, "TestUser");
+
+        let mut cmd = app.update(undo_age, &mut model, &());
+        assert!(effect_kinds(&mut cmd).is_empty());
+
+        // Form-wide undo takes back the field undo first
+        let _ = app.update(Event::Undo, &mut model, &());
+        assert_eq!(app.view(&model).form.age.value, "200");
+    }
+
+    #[test]
+    fn test_configure_with_deferred_validation_shows_no_errors() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                validate_on_change: false,
+                max_address_length: 20,
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        let address = &model.form_handler.get_form().address;
+        assert_eq!(address.error, None);
+        assert!(address.valid);
+        assert!(!address.validated);
+
+        // Checked by the submit, the address follows later limits at once
+        let _ = app.update(Event::Submit, &mut model, &());
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Address,
+                value: "10 Downing Street, London".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert_eq!(
+            model.form_handler.get_form().address.error.as_deref(),
+            Some(ADDRESS_TOO_LONG_MESSAGE)
+        );
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                validate_on_change: false,
+                max_address_length: 40,
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        assert_eq!(model.form_handler.get_form().address.error, None);
+    }
+
+    #[test]
+    fn test_reset_keeps_configuration() {
+        let app = App;
+        let mut model = Model::default();
+        let config = AppConfig {
+            address_providers: vec![AddressProvider::new("https://custom.example.com/lookup")],
+            username_policy: UsernamePolicy::Handle,
+            username_ignored_characters: "._".to_string(),
+            validate_on_change: false,
+            ..Default::default()
+        };
+        let _ = app.update(Event::Configure(config.clone()), &mut model, &());
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "Alice".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+
+        let mut cmd = app.update(Event::ResetForm, &mut model, &());
+        for event in cmd.events() {
+            let _ = app.update(event, &mut model, &());
+        }
+
+        assert_eq!(model.config, config);
+        let form = model.form_handler.get_form();
+        assert_eq!(form.username.value.0, "");
+        assert_eq!(form.username_policy, UsernamePolicy::Handle);
+        assert_eq!(form.username_ignored_characters, "._");
+        assert!(!form.validate_on_change);
+
+        let mut cmd = app.update(
+            Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query: "Baker".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let Some(Effect::Http(request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        assert_eq!(
+            request.operation.url,
+            "https://custom.example.com/lookup?query=Baker&limit=6"
+        );
+    }
+
+    #[test]
+    fn test_error_display_order() {
+        let app = App;
+        let mut model = Model::default();
+        for (ident, value) in [(FieldIdent::Email, "not an email"), (FieldIdent::Age, "12")] {
+            let _ = app.update(
+                Event::UpdateValue {
+                    ident,
+                    value: value.to_string(),
+                },
+                &mut model,
+                &(),
+            );
+        }
+        let summary = |model: &Model| -> Vec<FieldIdent> {
+            let form = model.form_handler.view();
+            assert_eq!(
+                form.focus_hint,
+                form.error_summary.first().map(|error| error.ident)
+            );
+            form.error_summary.iter().map(|error| error.ident).collect()
+        };
+        assert_eq!(
+            summary(&model),
+            vec![
+                FieldIdent::Username,
+                FieldIdent::Email,
+                FieldIdent::Age,
+                FieldIdent::Address
+            ]
+        );
+
+        // Listed fields lead, repeats are dropped and the rest keep form order
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                error_display_order: vec![FieldIdent::Age, FieldIdent::Email, FieldIdent::Age],
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        assert_eq!(
+            summary(&model),
+            vec![
+                FieldIdent::Age,
+                FieldIdent::Email,
+                FieldIdent::Username,
+                FieldIdent::Address
+            ]
+        );
+        assert_eq!(model.form_handler.view().focus_hint, Some(FieldIdent::Age));
+
+        let _ = app.update(Event::ResetForm, &mut model, &());
+        assert_eq!(
+            model.form_handler.get_form().error_display_order,
+            vec![FieldIdent::Age, FieldIdent::Email, FieldIdent::Age]
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_export_last_failure_until_reset() {
+        let app = App;
+        let mut model = suggesting_model();
+        for (query, result) in [
+            ("Baker", AddressSuggestionsResult::Unreachable),
+            ("Baker Street", AddressSuggestionsResult::Success(vec![])),
+        ] {
+            let _ = app.update(
+                Event::FetchSuggestions {
+                    ident: FieldIdent::Address,
+                    query: query.to_string(),
+                },
+                &mut model,
+                &(),
+            );
+            let _ = app.update(
+                Event::SuggestionsReceived {
+                    ident: FieldIdent::Address,
+                    sequence: 1,
+                    result,
+                },
+                &mut model,
+                &(),
+            );
+        }
+
+        let report = export_diagnostics(&app, &mut model);
+        assert_eq!(report.suggestion_attempts.len(), 2);
+        let failure = report
+            .last_suggestion_failure
+            .expect("a failure was logged");
+        assert_eq!(failure.query, "Baker");
+        assert_eq!(failure.outcome, SuggestionOutcome::Unreachable);
+
+        let _ = app.update(Event::ResetForm, &mut model, &());
+        let report = export_diagnostics(&app, &mut model);
+        assert!(report.suggestion_attempts.is_empty());
+        assert_eq!(report.last_suggestion_failure, None);
+    }
+
+    #[test]
+    fn test_reset_form_keeps_session_state_and_reset_all_does_not() {
+        let app = App;
+        let config = AppConfig {
+            address_api_url: Some(ADDRESS_API_URL.to_string()),
+            username_policy: UsernamePolicy::Handle,
+            suggestion_retry: RetryPolicy {
+                max_retries: 2,
+                base_delay_ms: 100,
+                jitter_ms: 50,
+            },
+            random_seed: Some(9),
+            ..Default::default()
+        };
+        let mut fresh = Model::default();
+        let _ = app.update(Event::Configure(config.clone()), &mut fresh, &());
+
+        let mut model = Model::default();
+        let _ = app.update(Event::Configure(config.clone()), &mut model, &());
+        let _ = app.update(
+            Event::SelectCountry {
+                country: Some("UK".to_string()),
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query: "Baker".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(
+            Event::SetStatusOverride(Some("Saved to favorites".to_string())),
+            &mut model,
+            &(),
+        );
+
+        let _ = app.update(Event::ResetForm, &mut model, &());
+        let state = serde_json::to_value(&model).unwrap();
+        assert_eq!(model.form_handler.get_form().username.value.0, "");
+        assert_eq!(model.status_override, None);
+        assert_eq!(model.config, config);
+        assert_eq!(state["address_handler"]["country"], "UK");
+        assert_ne!(model.jitter, fresh.jitter);
+
+        let _ = app.update(Event::ResetAll, &mut model, &());
+        assert_eq!(model.config, config);
+        assert_eq!(
+            model.form_handler.get_form().username_policy,
+            UsernamePolicy::Handle
+        );
+        assert_eq!(
+            serde_json::to_value(&model).unwrap(),
+            serde_json::to_value(&fresh).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_status_override_shows_until_the_form_changes() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(
+            Event::SetStatusOverride(Some("Saved to favorites".to_string())),
+            &mut model,
+            &(),
+        );
+        assert_eq!(app.view(&model).form.status_message, "Saved to favorites");
+
+        // Events that leave the form as it was keep the override
+        let _ = app.update(Event::ExportDiagnostics, &mut model, &());
+        assert_eq!(app.view(&model).form.status_message, "Saved to favorites");
+
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "TestUser".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(model.status_override, None);
+        assert_ne!(app.view(&model).form.status_message, "Saved to favorites");
+
+        let _ = app.update(
+            Event::SetStatusOverride(Some("Saved to favorites".to_string())),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::SetStatusOverride(None), &mut model, &());
+        assert_eq!(
+            app.view(&model).form.status_message,
+            model.form_handler.view().status_message
+        );
+    }
+
+    /// The caret signals in the view, oldest first.
+    fn carets(app: &App, model: &Model) -> Vec<(FieldIdent, u32)> {
+        app.view(model)
+            .signals
+            .into_iter()
+            .filter_map(|raised| match raised.signal {
+                Signal::Caret { ident, position } => Some((ident, position)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_caret_signal_follows_core_rewrites_until_typing() {
+        let app = App;
+        let mut model = Model::default();
+        let suggestion = Address {
+            street: "12 Rue de l'Église".to_string(),
+            city: "Genève".to_string(),
+            postcode: "1204".to_string(),
+            country: "CH".to_string(),
+            display: "12 Rue de l'Église, Genève".to_string(),
+            lat: None,
+            lon: None,
+        };
+        let mut cmd = app.update(
+            Event::SelectSuggestion {
+                suggestion: suggestion.clone(),
+            },
+            &mut model,
+            &(),
+        );
+        for event in cmd.events() {
+            let _ = app.update(event, &mut model, &());
+        }
+        assert_eq!(app.view(&model).form.address.value, suggestion.display);
+        // Characters, not the 28 bytes
+        assert_eq!(carets(&app, &model), vec![(FieldIdent::Address, 26)]);
+
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "  Zoë   Smith ".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(carets(&app, &model), vec![]);
+
+        let _ = app.update(
+            Event::SetFieldEditing {
+                ident: FieldIdent::Username,
+                editing: false,
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(app.view(&model).form.username.value, "Zoë Smith");
+        assert_eq!(carets(&app, &model), vec![(FieldIdent::Username, 9)]);
+
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "Zoë Smith!".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(carets(&app, &model), vec![]);
+    }
+
+    #[test]
+    fn test_acknowledging_keeps_signals_raised_after_the_view() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                draft_size_limit: Some(64),
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        for event in [
+            Event::SetFieldValue {
+                ident: FieldIdent::Address,
+                value: "x".repeat(100),
+                fetch: false,
+            },
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "Zoë  Smith".to_string(),
+            },
+            Event::SetFieldEditing {
+                ident: FieldIdent::Username,
+                editing: false,
+            },
+        ] {
+            let _ = app.update(event, &mut model, &());
+        }
+        let seen = app.view(&model).signals;
+        assert_eq!(seen.len(), 1);
+
+        // Raised after the shell took its view
+        let _ = app.update(Event::SaveDraft, &mut model, &());
+        let mut cmd = app.update(
+            Event::AcknowledgeSignals {
+                up_to_seq: seen[0].seq,
+            },
+            &mut model,
+            &(),
+        );
+        assert_eq!(effect_kinds(&mut cmd), vec!["render"]);
+        let pending = app.view(&model).signals;
+        assert_eq!(pending.len(), 1);
+        assert!(pending[0].seq > seen[0].seq);
+        assert!(matches!(pending[0].signal, Signal::DraftTruncated(_)));
+
+        // Acknowledging again changes nothing
+        let mut cmd = app.update(
+            Event::AcknowledgeSignals {
+                up_to_seq: seen[0].seq,
+            },
+            &mut model,
+            &(),
+        );
+        assert!(effect_kinds(&mut cmd).is_empty());
+
+        // A full reset keeps numbering, so old acknowledgements stay valid
+        let last_seq = pending[0].seq;
+        let _ = app.update(Event::ResetAll, &mut model, &());
+        assert!(app.view(&model).signals.is_empty());
+        let _ = app.update(
+            Event::SetFieldValue {
+                ident: FieldIdent::Address,
+                value: "x".repeat(100),
+                fetch: false,
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::SaveDraft, &mut model, &());
+        assert!(app.view(&model).signals[0].seq > last_seq);
+    }
+
+    fn export_diagnostics(app: &App, model: &mut Model) -> DiagnosticsReport {
+        let mut cmd = app.update(Event::ExportDiagnostics, model, &());
+        let Some(Effect::Diagnostics(request)) = cmd.effects().next() else {
+            panic!("expected a diagnostics report");
+        };
+        let DiagnosticsOperation::Report(report) = request.operation;
+        report
+    }
+
+    fn fetch_suggestions(app: &App, model: &mut Model, query: &str) -> Vec<&'static str> {
+        let mut cmd = app.update(
+            Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query: query.to_string(),
+            },
+            model,
+            &(),
+        );
+        effect_kinds(&mut cmd)
+    }
+
+    #[test]
+    fn test_forced_fetch_skips_the_pause_and_the_length_check() {
+        let app = App;
+        let mut model = suggesting_model();
+        let forced = |model: &mut Model, query: &str| {
+            app.update(
+                Event::FetchSuggestionsForced {
+                    ident: FieldIdent::Address,
+                    query: query.to_string(),
+                },
+                model,
+                &(),
+            )
+        };
+
+        // Too short to fetch as typed
+        let mut cmd = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Address,
+                value: "B".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert!(!cmd.effects().any(|effect| matches!(
+            effect,
+            Effect::Timer(request)
+                if matches!(request.operation, TimerOperation::Start { id, .. }
+                    if TimerKind::of(id) == Some(TimerKind::SuggestionDebounce))
+        )));
+        let mut cmd = forced(&mut model, "B");
+        assert_eq!(
+            answer_suggestions(&app, &mut model, &mut cmd, Address::london_sample(1)),
+            vec!["render"]
+        );
+        assert_eq!(app.view(&model).address_suggestions.len(), 1);
+
+        // Asked again for the same query, it fetches again, and the answer
+        // replaces the one shown
+        let pause = type_address(&app, &mut model, "Baker");
+        let mut cmd = forced(&mut model, "Baker");
+        assert_eq!(
+            answer_suggestions(&app, &mut model, &mut cmd, Address::london_sample(2)),
+            vec!["render"]
+        );
+        let mut cmd = forced(&mut model, "Baker");
+        assert_eq!(
+            answer_suggestions(&app, &mut model, &mut cmd, Address::london_sample(3)),
+            vec!["render"]
+        );
+        assert_eq!(app.view(&model).address_suggestions.len(), 3);
+        // The pause of the typing before is dropped
+        for event in pause() {
+            let mut cmd = app.update(event, &mut model, &());
+            assert!(cmd.events().next().is_none());
+        }
+
+        // Still not on a locked form
+        fill_valid_form(&app, &mut model);
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(effect_kinds(&mut forced(&mut model, "Baker")).is_empty());
+    }
+
+    #[test]
+    fn test_suggestion_fetch_gating() {
+        let app = App;
+        let editing = |_: &mut Model| {};
+        let submitted = |model: &mut Model| {
+            fill_valid_form(&App, model);
+            let _ = App.update(Event::Submit, model, &());
+        };
+        let checking_email = |model: &mut Model| {
+            let _ = App.update(
+                Event::Configure(AppConfig {
+                    address_api_url: Some(ADDRESS_API_URL.to_string()),
+                    email_check_url: Some("https://api.example.com/email".to_string()),
+                    ..Default::default()
+                }),
+                model,
+                &(),
+            );
+            fill_valid_form(&App, model);
+            let _ = App.update(Event::Submit, model, &());
+        };
+        let unconfigured = |model: &mut Model| {
+            let _ = App.update(Event::Configure(AppConfig::default()), model, &());
+        };
+        type Setup = fn(&mut Model);
+        let table: [(&str, Setup, &str, Option<SkipReason>); 8] = [
+            ("editing", editing, "Baker", None),
+            (
+                "unconfigured",
+                unconfigured,
+                "Baker",
+                Some(SkipReason::NoEndpoint),
+            ),
+            ("editing", editing, " \t", Some(SkipReason::EmptyQuery)),
+            ("editing", editing, " Ba ", Some(SkipReason::ShortQuery)),
+            ("editing", editing, " Bak ", None),
+            ("submitted", submitted, "Baker", Some(SkipReason::Locked)),
+            (
+                "checking email",
+                checking_email,
+                "Baker",
+                Some(SkipReason::Submitting),
+            ),
+            (
+                "checking email",
+                checking_email,
+                "",
+                Some(SkipReason::Submitting),
+            ),
+        ];
+
+        for (state, setup, query, skip) in table {
+            let mut model = suggesting_model();
+            setup(&mut model);
+            assert_eq!(
+                can_fetch_suggestions(&model, query, false),
+                skip.map_or(Ok(()), Err),
+                "{state}, {query:?}"
+            );
+
+            let effects = fetch_suggestions(&app, &mut model, query);
+            let skipped = export_diagnostics(&app, &mut model).skipped_suggestion_fetches;
+            match skip {
+                None => {
+                    assert_eq!(effects, vec!["http", "render"], "{state}, {query:?}");
+                    assert!(skipped.is_empty());
+                }
+                Some(reason) => {
+                    assert!(effects.is_empty(), "{state}, {query:?}");
+                    assert_eq!(skipped, vec![SkippedFetches { reason, count: 1 }]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fetch_queued_before_submit_is_skipped() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(
+            Event::Configure(AppConfig {
+                suggestion_debounce_ms: 0,
+                ..Default::default()
+            }),
+            &mut model,
+            &(),
+        );
+        fill_valid_form(&app, &mut model);
+
+        // The fetch an address edit dispatches is still queued when the
+        // user hits submit
+        let mut cmd = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Address,
+                value: "221B Baker Street".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let queued: Vec<Event> = cmd.events().collect();
+        let _ = app.update(Event::Submit, &mut model, &());
+        assert!(model.form_handler.get_form().submitted);
+
+        for event in queued {
+            let mut cmd = app.update(event, &mut model, &());
+            assert!(effect_kinds(&mut cmd).is_empty());
+        }
+        assert!(model.address_handler.get_suggestions().is_empty());
+        assert_eq!(
+            export_diagnostics(&app, &mut model).skipped_suggestion_fetches,
+            vec![SkippedFetches {
+                reason: SkipReason::Locked,
+                count: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_count_field_errors_without_values() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(Event::Configure(AppConfig::default()), &mut model, &());
+        let _ = app.update(Event::ErrorSaltReceived { salt: 7 }, &mut model, &());
+        for value in ["bob", "bob@", "bob", "bob@", "bob@mail", "bob"] {
+            let _ = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Email,
+                    value: value.to_string(),
+                },
+                &mut model,
+                &(),
+            );
+        }
+        let _ = app.update(Event::Submit, &mut model, &());
+
+        let report = export_diagnostics(&app, &mut model);
+        let email = report
+            .field_errors
+            .iter()
+            .find(|stats| stats.ident == FieldIdent::Email)
+            .expect("email errors were recorded");
+        assert_eq!(
+            email.error,
+            "Please enter a valid email address (e.g. user@example.com)"
+        );
+        // Six edits and the submit, on three distinct values
+        assert_eq!((email.occurrences, email.distinct_values), (7, 3));
+
+        // The salt is not saved with the model; the next error asks again
+        let mut restored: Model = serde_json::from_value(serde_json::to_value(&model).unwrap())
+            .expect("model round-trips");
+        let mut cmd = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: "bob@mail.".to_string(),
+            },
+            &mut restored,
+            &(),
+        );
+        assert_eq!(effect_kinds(&mut cmd), vec!["render", "timer", "random"]);
+        assert!(report
+            .field_errors
+            .iter()
+            .any(|stats| stats.ident == FieldIdent::Username && stats.occurrences == 1));
+
+        let dump = serde_json::to_string(&report).unwrap();
+        assert!(!dump.contains("bob"), "{dump}");
+
+        let _ = app.update(Event::ResetForm, &mut model, &());
+        assert!(export_diagnostics(&app, &mut model).field_errors.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_loads_models_saved_by_older_and_newer_versions() {
+        let field = |value: serde_json::Value| {
+            serde_json::json!({
+                "value": value,
+                "initial_value": value,
+                "touched": true,
+                "dirty": false,
+                "error": null,
+                "valid": true,
+                "editing": true
+            })
+        };
+        // As saved by the first release
+        let first_release = serde_json::json!({
+            "form_handler": {
+                "form": {
+                    "username": field("alice".into()),
+                    "email": field("alice@example.com".into()),
+                    "age": field(30.into()),
+                    "address": field("10 Downing Street".into()),
+                    "submitted": false,
+                    "is_editing": true
+                }
+            },
+            "address_handler": {
+                "suggestions": [{
+                    "street": "10 Downing Street",
+                    "city": "London",
+                    "postcode": "SW1A 2AA",
+                    "country": "UK",
+                    "combined": "10 Downing Street, London, SW1A 2AA, UK"
+                }],
+                "api_url": ADDRESS_API_URL
+            }
+        });
+
+        let mut model = Model::migrate(first_release.to_string().as_bytes()).unwrap();
+        assert_eq!(model.model_version, MODEL_VERSION);
+        let view = App.view(&model);
+        assert_eq!(view.form.username.value, "alice");
+        assert_eq!(view.form.age.value, "30");
+        assert_eq!(view.form.date_of_birth.value, "");
+        assert!(view.address_suggestions.is_empty());
+        assert!(view.form.is_editing_form);
+
+        // Dynamic fields were kept beside the form, without their specs
+        let mut before_dynamic_specs = serde_json::to_value(&model).unwrap();
+        before_dynamic_specs["model_version"] = 1.into();
+        before_dynamic_specs["config"]["dynamic_fields"] = serde_json::json!([{
+            "key": "nickname",
+            "validators": [{ "MinLength": { "min": 3 } }]
+        }]);
+        before_dynamic_specs["dynamic_form"] = serde_json::json!({
+            "fields": { "nickname": field("Alex".into()) }
+        });
+        before_dynamic_specs["form_handler"]["form"]
+            .as_object_mut()
+            .unwrap()
+            .remove("dynamic");
+        model = Model::migrate(before_dynamic_specs.to_string().as_bytes()).unwrap();
+        assert_eq!(App.view(&model).dynamic_fields[0].field.value, "Alex");
+        let _ = App.update(
+            Event::UpdateDynamicValue {
+                key: "nickname".to_string(),
+                value: "Al".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        assert!(!App.view(&model).dynamic_fields[0].field.valid);
+
+        let mut newer = serde_json::to_value(&model).unwrap();
+        newer["model_version"] = (MODEL_VERSION + 1).into();
+        newer["added_later"] = serde_json::json!({ "anything": true });
+        let model = Model::migrate(newer.to_string().as_bytes()).unwrap();
+        assert_eq!(App.view(&model).form.username.value, "alice");
+
+        assert!(Model::migrate(b"not a model").is_err());
     }
 }
@@ -0,0 +1,27 @@
+use crux_core::capability::Operation;
+use serde::{Deserialize, Serialize};
+
+/// Fire-and-forget analytics event for the shell to forward to its tracker.
+/// Payloads never carry field values; the only user-entered text is the
+/// suggestion search query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AnalyticsOperation {
+    Event {
+        name: String,
+    },
+    /// Which rank of the address suggestions the user picked
+    SuggestionSelected {
+        query: String,
+        selected_index: usize,
+        result_count: usize,
+    },
+    /// The user left the form for good without submitting it
+    FormAbandoned {
+        progress_percent: u32,
+        dirty_field_count: usize,
+    },
+}
+
+impl Operation for AnalyticsOperation {
+    type Output = ();
+}
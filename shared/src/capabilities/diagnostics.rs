@@ -0,0 +1,28 @@
+use crux_core::capability::Operation;
+use serde::{Deserialize, Serialize};
+
+use crate::events::diagnostics::{FieldErrorStats, SkippedFetches, SuggestionAttempt};
+
+/// Troubleshooting data for the shell to attach to a support report.
+/// Like analytics, the only user-entered text is the suggestion query;
+/// field values are only ever counted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum DiagnosticsOperation {
+    Report(DiagnosticsReport),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DiagnosticsReport {
+    /// Most recent last
+    pub suggestion_attempts: Vec<SuggestionAttempt>,
+    /// Kept even once it has dropped out of `suggestion_attempts`
+    pub last_suggestion_failure: Option<SuggestionAttempt>,
+    /// Fetches that were not made, by reason
+    pub skipped_suggestion_fetches: Vec<SkippedFetches>,
+    /// Validation errors hit per field, in form order
+    pub field_errors: Vec<FieldErrorStats>,
+}
+
+impl Operation for DiagnosticsOperation {
+    type Output = ();
+}
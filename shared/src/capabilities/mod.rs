@@ -1 +1,8 @@
 // # Form capabilities can be added here in the future
+pub mod analytics;
+pub mod diagnostics;
+pub mod navigate;
+pub mod random;
+pub mod storage;
+pub mod timer;
+pub mod validate;
@@ -0,0 +1,12 @@
+use crux_core::capability::Operation;
+use serde::{Deserialize, Serialize};
+
+/// Asks the shell to navigate to `target` (a route or screen name).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NavigateOperation {
+    pub target: String,
+}
+
+impl Operation for NavigateOperation {
+    type Output = ();
+}
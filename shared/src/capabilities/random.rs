@@ -0,0 +1,15 @@
+use crux_core::capability::Operation;
+use serde::{Deserialize, Serialize};
+
+/// Asks the shell for a value from its platform's random source. The core
+/// has none of its own, and a fixed seed would make every client behave
+/// alike.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RandomOperation;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RandomValue(pub u64);
+
+impl Operation for RandomOperation {
+    type Output = RandomValue;
+}
@@ -0,0 +1,14 @@
+use crux_core::capability::Operation;
+use serde::{Deserialize, Serialize};
+
+/// Asks the shell to persist `value` under `key` in its key-value store
+/// (e.g. NSUserDefaults, SharedPreferences, localStorage).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum StorageOperation {
+    Save { key: String, value: String },
+    Delete { key: String },
+}
+
+impl Operation for StorageOperation {
+    type Output = ();
+}
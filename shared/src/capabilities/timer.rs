@@ -0,0 +1,55 @@
+use crux_core::capability::Operation;
+use serde::{Deserialize, Serialize};
+
+/// What a timer is for, kept in the top bits of its id. Below them each
+/// kind numbers its own timers, so no two timers in flight share an id.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimerKind {
+    /// Numbered by field and the field's edit
+    ErrorDebounce,
+    /// Numbered by fetch; a fetch's retries wait one after another
+    RetryBackoff,
+    /// Numbered by address edit
+    SuggestionDebounce,
+    /// Numbered like the remote checks themselves
+    RemoteCheckDebounce,
+    /// Numbered by recorded edit
+    UndoWindow,
+}
+
+impl TimerKind {
+    const SHIFT: u32 = 61;
+
+    /// The id of timer `number` of this kind. Only the low 61 bits of
+    /// `number` are kept.
+    pub fn id(self, number: u64) -> u64 {
+        (self as u64) << Self::SHIFT | number & ((1 << Self::SHIFT) - 1)
+    }
+
+    pub fn of(id: u64) -> Option<Self> {
+        match id >> Self::SHIFT {
+            0 => Some(Self::ErrorDebounce),
+            1 => Some(Self::RetryBackoff),
+            2 => Some(Self::SuggestionDebounce),
+            3 => Some(Self::RemoteCheckDebounce),
+            4 => Some(Self::UndoWindow),
+            _ => None,
+        }
+    }
+}
+
+/// Asks the shell to wait for `millis` and then resolve the request.
+/// The `id` is echoed back so the core can tell timers apart.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TimerOperation {
+    Start { id: u64, millis: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TimerOutput {
+    Elapsed { id: u64 },
+}
+
+impl Operation for TimerOperation {
+    type Output = TimerOutput;
+}
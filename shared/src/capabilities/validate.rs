@@ -0,0 +1,22 @@
+use crux_core::capability::Operation;
+use serde::{Deserialize, Serialize};
+
+use crate::events::form::FieldIdent;
+
+/// Asks the shell to check `value` of `ident` by means the core does not
+/// have, e.g. a uniqueness lookup.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AsyncValidateOperation {
+    pub ident: FieldIdent,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AsyncVerdict {
+    Valid,
+    Invalid { message: String },
+}
+
+impl Operation for AsyncValidateOperation {
+    type Output = AsyncVerdict;
+}
@@ -0,0 +1,387 @@
+use crux_core::Command;
+use crux_http::command::Http;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{Effect, Event};
+use crate::capabilities::analytics::AnalyticsOperation;
+use crate::capabilities::navigate::NavigateOperation;
+use crate::descriptor::ValidatorSpec;
+use crate::events::address::{template, AddressProvider};
+use crate::events::dynamic::DynamicForm;
+use crate::events::form::{
+    max_address_length_default, min_suggestion_query_len_default, remote_check_debounce_default,
+    suggestion_debounce_default, username_ignored_characters_default, FieldIdent, UsernamePolicy,
+    DEFAULT_MAX_ADDRESS_LENGTH, DEFAULT_MIN_SUGGESTION_QUERY_LEN, DEFAULT_SUGGESTION_DEBOUNCE_MS,
+};
+use crate::events::retry::{retrying, Backoff};
+use crate::pending::{self, OperationKind};
+
+/// Configuration sent by the shell with `Event::Configure`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AppConfig {
+    #[serde(default)]
+    pub submit_hooks: Vec<SubmitHook>,
+    /// Suggestion endpoints in priority order; empty uses `address_api_url`.
+    #[serde(default)]
+    pub address_providers: Vec<AddressProvider>,
+    /// The suggestion endpoint when there are no `address_providers`, e.g.
+    /// `http://10.0.2.2:8000/api/suggestions` from an Android emulator.
+    /// Without either, suggestions are not fetched.
+    #[serde(default)]
+    pub address_api_url: Option<String>,
+    /// Largest draft payload in bytes; `None` uses `DEFAULT_DRAFT_SIZE_LIMIT`.
+    #[serde(default)]
+    pub draft_size_limit: Option<usize>,
+    #[serde(default)]
+    pub username_policy: UsernamePolicy,
+    /// Characters dropped when comparing usernames for uniqueness
+    #[serde(default = "username_ignored_characters_default")]
+    pub username_ignored_characters: String,
+    /// Validate fields as they are edited, or only on submit
+    #[serde(default = "validate_on_change_default")]
+    pub validate_on_change: bool,
+    /// Makes age a required field, for markets that must collect it
+    #[serde(default)]
+    pub age_required: bool,
+    /// Longest address accepted, in characters
+    #[serde(default = "max_address_length_default")]
+    pub max_address_length: usize,
+    /// Order of the error summary, most important first. Unlisted fields
+    /// follow in form order.
+    #[serde(default)]
+    pub error_display_order: Vec<FieldIdent>,
+    /// Endpoint asked `?check=<email>` before a valid form is submitted;
+    /// `None` submits straight away.
+    #[serde(default)]
+    pub email_check_url: Option<String>,
+    /// Endpoint a valid form is POSTed to, as JSON, before it counts as
+    /// submitted; `None` submits locally.
+    #[serde(default)]
+    pub submit_url: Option<String>,
+    /// Fields checked against a server on `Event::ValidateFieldRemote`
+    #[serde(default)]
+    pub remote_checks: Vec<RemoteCheck>,
+    /// Fields the shell checks with an `AsyncValidate` effect after each
+    /// edit that passes the local rules
+    #[serde(default)]
+    pub async_validated: Vec<FieldIdent>,
+    /// Fields declared by the shell, shown after the built-in ones
+    #[serde(default)]
+    pub dynamic_fields: Vec<DynamicFieldSpec>,
+    #[serde(default)]
+    pub resubmit_policy: ResubmitPolicy,
+    /// Pause in address typing, in milliseconds, before suggestions are
+    /// fetched; 0 fetches on every edit
+    #[serde(default = "suggestion_debounce_default")]
+    pub suggestion_debounce_ms: u64,
+    /// Fewest characters, ignoring surrounding whitespace, that an address
+    /// query needs before suggestions are fetched
+    #[serde(default = "min_suggestion_query_len_default")]
+    pub min_suggestion_query_len: usize,
+    #[serde(default)]
+    pub suggestion_retry: RetryPolicy,
+    #[serde(default)]
+    pub submission_retry: RetryPolicy,
+    /// Seeds the jitter of retry delays, for fixed delays in tests. Without
+    /// one the core asks the shell's random source on every configure.
+    #[serde(default)]
+    pub random_seed: Option<u64>,
+}
+
+fn validate_on_change_default() -> bool {
+    true
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            submit_hooks: Vec::new(),
+            address_providers: Vec::new(),
+            address_api_url: None,
+            draft_size_limit: None,
+            username_policy: UsernamePolicy::default(),
+            username_ignored_characters: username_ignored_characters_default(),
+            validate_on_change: true,
+            age_required: false,
+            max_address_length: DEFAULT_MAX_ADDRESS_LENGTH,
+            error_display_order: Vec::new(),
+            email_check_url: None,
+            submit_url: None,
+            remote_checks: Vec::new(),
+            async_validated: Vec::new(),
+            dynamic_fields: Vec::new(),
+            resubmit_policy: ResubmitPolicy::default(),
+            suggestion_debounce_ms: DEFAULT_SUGGESTION_DEBOUNCE_MS,
+            min_suggestion_query_len: DEFAULT_MIN_SUGGESTION_QUERY_LEN,
+            suggestion_retry: RetryPolicy::default(),
+            submission_retry: RetryPolicy::default(),
+            random_seed: None,
+        }
+    }
+}
+
+/// Retries of a request that failed to reach a server or got a 5xx.
+/// Retry `n` (from 0) waits `base_delay_ms * 2^n` plus a random extra of
+/// up to `jitter_ms`. The default never retries.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay_ms: u64,
+    pub jitter_ms: u64,
+}
+
+impl RetryPolicy {
+    /// The delay before retry `retry`, without jitter.
+    pub fn backoff(&self, retry: u32) -> u64 {
+        self.base_delay_ms
+            .saturating_mul(2u64.checked_pow(retry).unwrap_or(u64::MAX))
+    }
+}
+
+/// Follow-up action run, in order, after a successful submit.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum SubmitHook {
+    HttpPost { url: String },
+    EmitAnalytics { name: String },
+    Navigate { target: String },
+}
+
+/// An endpoint asked `?value=<input>` whether the input of `ident` is
+/// acceptable, e.g. whether a username is free. It answers
+/// `{ "valid": bool, "message": string | null }`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RemoteCheck {
+    pub ident: FieldIdent,
+    pub url: String,
+    /// Also ask after every edit that passes the local rules, rather than
+    /// only on `Event::ValidateFieldRemote`
+    #[serde(default)]
+    pub on_edit: bool,
+    /// The pause in typing an edit waits for before it is checked; 0 checks
+    /// every edit
+    #[serde(default = "remote_check_debounce_default")]
+    pub debounce_ms: u64,
+}
+
+/// A text field declared at runtime, edited with
+/// `Event::UpdateDynamicValue`. Only text rules apply: `MinLength`,
+/// `Pattern` and `Range`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DynamicFieldSpec {
+    pub key: String,
+    pub validators: Vec<ValidatorSpec>,
+}
+
+/// What submitting again after `Event::Edit` does once the server has
+/// accepted a submission.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResubmitPolicy {
+    /// POST again; the server issues a new receipt
+    #[default]
+    NewSubmission,
+    /// PUT to `<url>/<receipt id>`, keeping the receipt
+    UpdateExisting,
+}
+
+/// The server's acknowledgement of an `HttpPost` submit hook, read from
+/// an `{ "id": ... }` response body.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SubmissionReceipt {
+    pub id: String,
+}
+
+/// What the next submit will do, so shells can label the button.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmissionMode {
+    New,
+    Update,
+}
+
+impl ResubmitPolicy {
+    /// The receipt the next submission updates, if it updates one.
+    pub fn updates(self, receipt: Option<&SubmissionReceipt>) -> Option<&SubmissionReceipt> {
+        match self {
+            ResubmitPolicy::NewSubmission => None,
+            ResubmitPolicy::UpdateExisting => receipt,
+        }
+    }
+
+    pub fn mode(self, receipt: Option<&SubmissionReceipt>) -> SubmissionMode {
+        match self.updates(receipt) {
+            Some(_) => SubmissionMode::Update,
+            None => SubmissionMode::New,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    InvalidHookUrl {
+        index: usize,
+        url: String,
+    },
+    EmptyAnalyticsName {
+        index: usize,
+    },
+    EmptyNavigationTarget {
+        index: usize,
+    },
+    InvalidProviderUrl {
+        index: usize,
+        url: String,
+    },
+    /// `placeholder` is unknown or never closed, as written in the template
+    InvalidProviderTemplate {
+        index: usize,
+        placeholder: String,
+    },
+    InvalidAddressApiUrl {
+        url: String,
+    },
+    InvalidEmailCheckUrl {
+        url: String,
+    },
+    InvalidSubmitUrl {
+        url: String,
+    },
+    InvalidRemoteCheckUrl {
+        index: usize,
+        url: String,
+    },
+    /// The key is empty or repeated, or a rule does not apply to text
+    InvalidDynamicField {
+        index: usize,
+    },
+}
+
+impl AppConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (index, hook) in self.submit_hooks.iter().enumerate() {
+            hook.validate(index)?;
+        }
+        for (index, provider) in self.address_providers.iter().enumerate() {
+            if !is_http_url(&provider.url) {
+                return Err(ConfigError::InvalidProviderUrl {
+                    index,
+                    url: provider.url.clone(),
+                });
+            }
+            if let Err(placeholder) = template::validate(&provider.url_template) {
+                return Err(ConfigError::InvalidProviderTemplate { index, placeholder });
+            }
+        }
+        if let Some(url) = &self.address_api_url {
+            if !is_http_url(url) {
+                return Err(ConfigError::InvalidAddressApiUrl { url: url.clone() });
+            }
+        }
+        if let Some(url) = &self.email_check_url {
+            if !is_http_url(url) {
+                return Err(ConfigError::InvalidEmailCheckUrl { url: url.clone() });
+            }
+        }
+        if let Some(url) = &self.submit_url {
+            if !is_http_url(url) {
+                return Err(ConfigError::InvalidSubmitUrl { url: url.clone() });
+            }
+        }
+        for (index, check) in self.remote_checks.iter().enumerate() {
+            if !is_http_url(&check.url) {
+                return Err(ConfigError::InvalidRemoteCheckUrl {
+                    index,
+                    url: check.url.clone(),
+                });
+            }
+        }
+        DynamicForm::from_specs(&self.dynamic_fields)
+            .map_err(|index| ConfigError::InvalidDynamicField { index })?;
+        Ok(())
+    }
+
+    /// Runs the hooks for a submission, updating `receipt` if the
+    /// `resubmit_policy` says so. Each hook's first effect is emitted in
+    /// configuration order; none waits for another to finish. Failed HTTP
+    /// hooks are retried after the delays of their own `backoff`, which
+    /// gives each hook its own timers.
+    pub fn submit_hooks_command(
+        &self,
+        receipt: Option<&SubmissionReceipt>,
+        mut backoff: impl FnMut() -> Backoff,
+    ) -> Command<Effect, Event> {
+        let update = self.resubmit_policy.updates(receipt);
+        Command::all(
+            self.submit_hooks
+                .iter()
+                .map(|hook| hook.command(update, backoff())),
+        )
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    matches!(url::Url::parse(url), Ok(parsed) if matches!(parsed.scheme(), "http" | "https"))
+}
+
+impl SubmitHook {
+    fn validate(&self, index: usize) -> Result<(), ConfigError> {
+        match self {
+            SubmitHook::HttpPost { url } if !is_http_url(url) => Err(ConfigError::InvalidHookUrl {
+                index,
+                url: url.clone(),
+            }),
+            SubmitHook::EmitAnalytics { name } if name.trim().is_empty() => {
+                Err(ConfigError::EmptyAnalyticsName { index })
+            }
+            SubmitHook::Navigate { target } if target.trim().is_empty() => {
+                Err(ConfigError::EmptyNavigationTarget { index })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn command(
+        &self,
+        update: Option<&SubmissionReceipt>,
+        backoff: Backoff,
+    ) -> Command<Effect, Event> {
+        match self {
+            SubmitHook::HttpPost { url } => {
+                let target =
+                    update.map(|receipt| format!("{}/{}", url.trim_end_matches('/'), receipt.id));
+                let url = url.clone();
+                // Only a receipt feeds back into the form; failures do not
+                Command::new(|ctx| async move {
+                    let attempt = || {
+                        let (method, endpoint) = match &target {
+                            Some(target) => ("PUT", target),
+                            None => ("POST", &url),
+                        };
+                        let kind = OperationKind::Submission {
+                            url: endpoint.clone(),
+                        };
+                        pending::label(method, endpoint, kind);
+                        match &target {
+                            Some(target) => Http::put(target),
+                            None => Http::post(&url),
+                        }
+                        .expect_json::<SubmissionReceipt>()
+                        .build()
+                        .into_future(ctx.clone())
+                    };
+                    if let Ok(mut response) = retrying(&ctx, &backoff, attempt).await {
+                        if let Some(receipt) = response.take_body() {
+                            ctx.send_event(Event::SubmissionAccepted { receipt });
+                        }
+                    }
+                })
+            }
+            SubmitHook::EmitAnalytics { name } => {
+                Command::notify_shell(AnalyticsOperation::Event { name: name.clone() }).into()
+            }
+            SubmitHook::Navigate { target } => Command::notify_shell(NavigateOperation {
+                target: target.clone(),
+            })
+            .into(),
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Opt-in ViewModel delta protocol for high-frequency render paths.
+//!
+//! Instead of the full ViewModel, `view_delta` returns only the top-level
+//! members that changed since the generation the shell last acknowledged,
+//! each encoded with the same bincode format as the bridge. When the shell's
+//! generation is unknown, in the future, or too old, a full snapshot is sent.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::app::ViewModel;
+
+/// Bumped whenever the delta wire format changes. Shells that see a
+/// different version should fall back to the full `view()`.
+pub const VIEW_DELTA_VERSION: u32 = 1;
+
+/// Acknowledged generations older than this many generations get a snapshot.
+pub const MAX_DELTA_AGE: u32 = 64;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ViewDelta {
+    pub version: u32,
+    pub generation: u32,
+    pub body: ViewDeltaBody,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ViewDeltaBody {
+    Snapshot(Box<ViewModel>),
+    Patch(Vec<ViewPatch>),
+}
+
+/// New value of the top-level ViewModel member at `path`, bincode encoded.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ViewPatch {
+    pub path: String,
+    pub value: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeltaError {
+    VersionMismatch(u32),
+    UnknownPath(String),
+    Decode(String),
+}
+
+fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+    bincode::serialize(value).expect("ViewModel members are serializable")
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DeltaError> {
+    bincode::deserialize(bytes).map_err(|e| DeltaError::Decode(e.to_string()))
+}
+
+macro_rules! view_members {
+    ($($member:ident),* $(,)?) => {
+        fn encode_members(view: &ViewModel) -> Vec<(&'static str, Vec<u8>)> {
+            // Fails to compile when a ViewModel member is missing from the list
+            let ViewModel { $($member: _,)* } = view;
+            vec![$((stringify!($member), encode(&view.$member)),)*]
+        }
+
+        fn apply_patch(view: &mut ViewModel, patch: &ViewPatch) -> Result<(), DeltaError> {
+            match patch.path.as_str() {
+                $(stringify!($member) => view.$member = decode(&patch.value)?,)*
+                path => return Err(DeltaError::UnknownPath(path.to_string())),
+            }
+            Ok(())
+        }
+    };
+}
+
+view_members!(
+    form,
+    address_suggestions,
+    suggestions_loading,
+    config_error,
+    signals,
+    dynamic_fields,
+    can_undo,
+    can_redo,
+    is_dirty,
+    dirty_fields,
+    changed_since_submit,
+    invalid_fields,
+    error_count,
+    first_invalid_field,
+    submission_mode,
+    render_hints,
+);
+
+impl ViewDelta {
+    /// Applies the delta to the shell's cached copy of the ViewModel.
+    pub fn apply(&self, view: &mut ViewModel) -> Result<(), DeltaError> {
+        if self.version != VIEW_DELTA_VERSION {
+            return Err(DeltaError::VersionMismatch(self.version));
+        }
+        match &self.body {
+            ViewDeltaBody::Snapshot(snapshot) => *view = (**snapshot).clone(),
+            ViewDeltaBody::Patch(patches) => {
+                for patch in patches {
+                    apply_patch(view, patch)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct TrackedMember {
+    path: &'static str,
+    value: Vec<u8>,
+    changed_at: u32,
+}
+
+/// Remembers the last emitted value of each member and the generation in
+/// which it last changed.
+#[derive(Default)]
+pub struct ViewDeltaTracker {
+    generation: u32,
+    members: Vec<TrackedMember>,
+}
+
+impl ViewDeltaTracker {
+    /// Builds the delta from `acknowledged` (0 meaning none) to `view`.
+    pub fn delta(&mut self, view: &ViewModel, acknowledged: u32) -> ViewDelta {
+        self.observe(view);
+
+        let too_old = self.generation - acknowledged.min(self.generation) > MAX_DELTA_AGE;
+        let body = if acknowledged == 0 || acknowledged > self.generation || too_old {
+            ViewDeltaBody::Snapshot(Box::new(view.clone()))
+        } else {
+            ViewDeltaBody::Patch(
+                self.members
+                    .iter()
+                    .filter(|member| member.changed_at > acknowledged)
+                    .map(|member| ViewPatch {
+                        path: member.path.to_string(),
+                        value: member.value.clone(),
+                    })
+                    .collect(),
+            )
+        };
+
+        ViewDelta {
+            version: VIEW_DELTA_VERSION,
+            generation: self.generation,
+            body,
+        }
+    }
+
+    fn observe(&mut self, view: &ViewModel) {
+        let current = encode_members(view);
+        let changed = self.members.len() != current.len()
+            || self
+                .members
+                .iter()
+                .zip(&current)
+                .any(|(member, (_, value))| &member.value != value);
+        if !changed {
+            return;
+        }
+
+        self.generation += 1;
+        let generation = self.generation;
+        let previous = std::mem::take(&mut self.members);
+        self.members = current
+            .into_iter()
+            .map(|(path, value)| {
+                let changed_at = previous
+                    .iter()
+                    .find(|member| member.path == path && member.value == value)
+                    .map_or(generation, |member| member.changed_at);
+                TrackedMember {
+                    path,
+                    value,
+                    changed_at,
+                }
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{App, Event, Model};
+    use crate::events::address::{Address, AddressSuggestionsResult};
+    use crate::events::form::FieldIdent;
+    use crux_core::App as _;
+
+    fn scripted_session() -> Vec<Event> {
+        let mut events = Vec::new();
+        let mut typed = String::new();
+        for c in "TestUser".chars() {
+            typed.push(c);
+            events.push(Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: typed.clone(),
+            });
+        }
+        typed.clear();
+        for c in "test@example.com".chars() {
+            typed.push(c);
+            events.push(Event::UpdateValue {
+                ident: FieldIdent::Email,
+                value: typed.clone(),
+            });
+        }
+        let suggestion = Address {
+            street: "221B Baker Street".to_string(),
+            city: "London".to_string(),
+            postcode: "NW1 6XE".to_string(),
+            country: "UK".to_string(),
+            display: "221B Baker Street, London, NW1 6XE UK".to_string(),
+            lat: None,
+            lon: None,
+        };
+        events.extend([
+            Event::TouchField {
+                ident: FieldIdent::Email,
+            },
+            Event::SetFieldEditing {
+                ident: FieldIdent::Age,
+                editing: true,
+            },
+            Event::UpdateValue {
+                ident: FieldIdent::Age,
+                value: "4".to_string(),
+            },
+            Event::UpdateValue {
+                ident: FieldIdent::Age,
+                value: "42".to_string(),
+            },
+            Event::SetFieldEditing {
+                ident: FieldIdent::Age,
+                editing: false,
+            },
+            Event::UpdateValue {
+                ident: FieldIdent::Address,
+                value: "Baker".to_string(),
+            },
+            Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 0,
+                result: AddressSuggestionsResult::Success(vec![suggestion.clone()]),
+            },
+            Event::SelectSuggestion {
+                suggestion: suggestion.clone(),
+            },
+            Event::UpdateValue {
+                ident: FieldIdent::Address,
+                value: suggestion.display.clone(),
+            },
+            Event::Submit,
+            Event::Edit,
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "Other".to_string(),
+            },
+            Event::Submit,
+            Event::ResetForm,
+            Event::ClearSuggestions,
+        ]);
+        events
+    }
+
+    #[test]
+    fn applying_deltas_reproduces_the_full_view() {
+        let app = App;
+        let mut model = Model::default();
+        let mut tracker = ViewDeltaTracker::default();
+
+        let mut shell_view = None;
+        let mut acknowledged: u32 = 0;
+
+        for (step, event) in scripted_session().into_iter().enumerate() {
+            let _ = app.update(event, &mut model, &());
+            let current = app.view(&model);
+
+            // the shell sometimes drops its ack, and sometimes loses its cache
+            let sent_ack = match step % 7 {
+                3 => 0,
+                5 => acknowledged.saturating_sub(1),
+                _ => acknowledged,
+            };
+            let delta: ViewDelta = bincode::deserialize(
+                &bincode::serialize(&tracker.delta(&current, sent_ack)).unwrap(),
+            )
+            .unwrap();
+
+            if sent_ack == 0 {
+                assert!(matches!(delta.body, ViewDeltaBody::Snapshot(_)));
+            }
+            let view = shell_view.get_or_insert_with(|| current.clone());
+            delta.apply(view).unwrap();
+            assert_eq!(view, &current, "diverged at step {step}");
+            acknowledged = delta.generation;
+        }
+    }
+
+    #[test]
+    fn unchanged_view_produces_empty_patch() {
+        let app = App;
+        let model = Model::default();
+        let mut tracker = ViewDeltaTracker::default();
+
+        let first = tracker.delta(&app.view(&model), 0);
+        let second = tracker.delta(&app.view(&model), first.generation);
+
+        assert_eq!(second.generation, first.generation);
+        assert_eq!(second.body, ViewDeltaBody::Patch(vec![]));
+    }
+
+    #[test]
+    fn single_member_change_patches_only_that_member() {
+        let app = App;
+        let mut model = Model::default();
+        let mut tracker = ViewDeltaTracker::default();
+        let first = tracker.delta(&app.view(&model), 0);
+
+        let _ = app.update(
+            Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 0,
+                result: AddressSuggestionsResult::Success(vec![]),
+            },
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: "TestUser".to_string(),
+            },
+            &mut model,
+            &(),
+        );
+        let delta = tracker.delta(&app.view(&model), first.generation);
+
+        let ViewDeltaBody::Patch(patches) = delta.body else {
+            panic!("expected a patch");
+        };
+        let paths: Vec<&str> = patches.iter().map(|p| p.path.as_str()).collect();
+        // The first edit also makes it undoable, dirty and valid
+        assert_eq!(
+            paths,
+            vec![
+                "form",
+                "can_undo",
+                "is_dirty",
+                "dirty_fields",
+                "changed_since_submit",
+                "invalid_fields",
+                "error_count",
+                "first_invalid_field",
+                "render_hints"
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_or_stale_generation_gets_snapshot() {
+        let app = App;
+        let mut model = Model::default();
+        let mut tracker = ViewDeltaTracker::default();
+        let first = tracker.delta(&app.view(&model), 0);
+
+        let future = tracker.delta(&app.view(&model), first.generation + 10);
+        assert!(matches!(future.body, ViewDeltaBody::Snapshot(_)));
+
+        for i in 0..=MAX_DELTA_AGE {
+            let _ = app.update(
+                Event::UpdateValue {
+                    ident: FieldIdent::Username,
+                    value: format!("user{i}"),
+                },
+                &mut model,
+                &(),
+            );
+            let _ = tracker.delta(&app.view(&model), 0);
+        }
+        let stale = tracker.delta(&app.view(&model), first.generation);
+        assert!(matches!(stale.body, ViewDeltaBody::Snapshot(_)));
+    }
+
+    #[test]
+    fn version_mismatch_is_rejected() {
+        let app = App;
+        let model = Model::default();
+        let mut view = app.view(&model);
+        let mut delta = ViewDeltaTracker::default().delta(&view, 0);
+        delta.version += 1;
+
+        assert_eq!(
+            delta.apply(&mut view),
+            Err(DeltaError::VersionMismatch(VIEW_DELTA_VERSION + 1))
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! A description of the form for tooling that inspects it at runtime,
+//! returned by `describe_form`. Kinds and validators come from the same
+//! `Validatable` impls that validate the fields, so they cannot drift.
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::form::{FieldIdent, FormViewModel, UsernamePolicy};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    Email,
+    Number,
+    Date,
+}
+
+/// A rule a field's value must satisfy. Empty optional fields satisfy
+/// every rule.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ValidatorSpec {
+    /// At least `min` bytes of text
+    MinLength { min: usize },
+    /// Matches the regular expression `pattern`
+    Pattern { pattern: String },
+    /// A whole number from `min` to `max`, inclusive
+    Range { min: u32, max: u32 },
+    /// A date at least `years` before the shell's current date
+    MinimumAge { years: u32 },
+    /// The active `UsernamePolicy`, checked on top of the other rules
+    UsernamePolicy(UsernamePolicy),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FieldMeta {
+    pub ident: FieldIdent,
+    pub label: String,
+    pub kind: FieldKind,
+    pub required: bool,
+    pub validators: Vec<ValidatorSpec>,
+}
+
+/// Every field, in form order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FormDescriptor {
+    pub fields: Vec<FieldMeta>,
+}
+
+impl FormDescriptor {
+    /// Describes the form currently shown by `view`.
+    pub fn new(view: &FormViewModel) -> Self {
+        Self {
+            fields: FieldIdent::ALL
+                .into_iter()
+                .map(|ident| FieldMeta {
+                    ident,
+                    label: ident.label().to_string(),
+                    kind: ident.kind(),
+                    required: view.field(ident).required,
+                    validators: ident.validators(view.username_policy),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::form::{Form, FormHandler};
+    use chrono::{Months, NaiveDate};
+    use regex::Regex;
+
+    /// Inputs on either side of each rule's boundary.
+    fn boundary_inputs(spec: &ValidatorSpec, today: NaiveDate) -> Vec<String> {
+        match spec {
+            ValidatorSpec::MinLength { min } => {
+                vec!["a".repeat(min.saturating_sub(1)), "a".repeat(*min)]
+            }
+            ValidatorSpec::Pattern { .. } => vec![
+                "user@example.com".to_string(),
+                "user@example".to_string(),
+                "user example.com".to_string(),
+            ],
+            ValidatorSpec::Range { min, max } => [min - 1, *min, *max, max + 1]
+                .iter()
+                .map(u32::to_string)
+                .collect(),
+            ValidatorSpec::MinimumAge { years } => {
+                let cutoff = today - Months::new(years * 12);
+                [cutoff, cutoff.succ_opt().unwrap()]
+                    .iter()
+                    .map(|date| date.format("%Y-%m-%d").to_string())
+                    .collect()
+            }
+            ValidatorSpec::UsernamePolicy(_) => Vec::new(),
+        }
+    }
+
+    /// Whether `input` satisfies `spec`, judged from the spec alone.
+    fn satisfies(spec: &ValidatorSpec, input: &str, today: NaiveDate) -> bool {
+        match spec {
+            ValidatorSpec::MinLength { min } => input.len() >= *min,
+            ValidatorSpec::Pattern { pattern } => Regex::new(pattern).unwrap().is_match(input),
+            ValidatorSpec::Range { min, max } => input
+                .parse::<u32>()
+                .map_or(false, |n| (*min..=*max).contains(&n)),
+            ValidatorSpec::MinimumAge { years } => {
+                let date = NaiveDate::parse_from_str(input, "%Y-%m-%d").unwrap();
+                date <= today - Months::new(years * 12)
+            }
+            ValidatorSpec::UsernamePolicy(_) => true,
+        }
+    }
+
+    #[test]
+    fn test_descriptor_agrees_with_validation_at_boundaries() {
+        let today = NaiveDate::from_ymd_opt(2030, 6, 15).unwrap();
+        let mut form = Form {
+            today: Some(today),
+            ..Default::default()
+        };
+        let descriptor = FormDescriptor::new(&FormHandler::new().view());
+        assert_eq!(
+            descriptor
+                .fields
+                .iter()
+                .map(|meta| meta.ident)
+                .collect::<Vec<_>>(),
+            FieldIdent::ALL.to_vec()
+        );
+
+        for meta in &descriptor.fields {
+            let inputs = meta
+                .validators
+                .iter()
+                .flat_map(|spec| boundary_inputs(spec, today));
+            for input in inputs.chain([String::new()]) {
+                let expected = if input.is_empty() && !meta.required {
+                    true
+                } else {
+                    meta.validators
+                        .iter()
+                        .all(|spec| satisfies(spec, &input, today))
+                };
+                form.set_input(meta.ident, input.clone());
+                form.validate_all();
+                assert_eq!(
+                    form.field(meta.ident).is_valid(),
+                    expected,
+                    "{:?} with {input:?}",
+                    meta.ident
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,1135 @@
+use crate::{
+    app::{Effect, Event},
+    capabilities::analytics::AnalyticsOperation,
+    events::{
+        form::FieldIdent,
+        retry::{retrying, Backoff},
+    },
+    pending::{self, OperationKind},
+};
+use crux_core::{command::CommandContext, render::render, Command};
+use crux_http::{command::Http, HttpError};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+pub mod model;
+pub mod template;
+pub mod wire;
+
+pub use model::Address;
+use template::{TemplateValues, DEFAULT_URL_TEMPLATE};
+use wire::{AddressSuggestionDto, ProviderResults};
+
+/// A position in degrees.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Coordinates {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum AddressSuggestionsResult {
+    Success(Vec<Address>),
+    Error,
+    /// No provider could be reached at all
+    Unreachable,
+}
+
+/// A suggestion as shown to the user. `offline` marks entries taken from
+/// the bundled list because no provider could be reached.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SuggestionViewModel {
+    pub suggestion: Address,
+    pub offline: bool,
+    /// `Address::primary_line` of the suggestion
+    pub primary_line: String,
+    /// `Address::secondary_line` of the suggestion
+    pub secondary_line: String,
+}
+
+lazy_static! {
+    static ref OFFLINE_SUGGESTIONS: Vec<Address> =
+        serde_json::from_str::<Vec<AddressSuggestionDto>>(include_str!(
+            "../../../assets/offline_suggestions.json"
+        ))
+        .expect("bundled offline suggestions are valid JSON")
+        .into_iter()
+        .map(Into::into)
+        .collect();
+}
+
+/// Suggestions shown at once. One extra is requested so that dropping the
+/// current address still leaves a full list.
+pub const DISPLAYED_SUGGESTIONS: usize = 5;
+
+/// A suggestion endpoint. Providers are tried in configuration order; one
+/// with no `countries` serves every country.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AddressProvider {
+    pub url: String,
+    /// Path and query appended to `url`, with `{query}`, `{limit}` and
+    /// `{country}` placeholders
+    #[serde(default = "url_template_default")]
+    pub url_template: String,
+    #[serde(default)]
+    pub countries: Vec<String>,
+    #[serde(default)]
+    pub response_format: ProviderResponseFormat,
+}
+
+fn url_template_default() -> String {
+    DEFAULT_URL_TEMPLATE.to_string()
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProviderResponseFormat {
+    /// A JSON array of `AddressSuggestionDto`
+    #[default]
+    Suggestions,
+    /// `{ "results": [{ "address_line", "locality", "postal_code", "country" }] }`,
+    /// optionally with `latitude` and `longitude`
+    Results,
+}
+
+impl AddressProvider {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            url_template: url_template_default(),
+            countries: Vec::new(),
+            response_format: ProviderResponseFormat::default(),
+        }
+    }
+
+    fn serves(&self, country: Option<&str>) -> bool {
+        self.countries.is_empty()
+            || country.map_or(false, |country| {
+                self.countries
+                    .iter()
+                    .any(|served| served.eq_ignore_ascii_case(country.trim()))
+            })
+    }
+
+    async fn fetch(
+        &self,
+        query: &str,
+        country: Option<&str>,
+        ctx: CommandContext<Effect, Event>,
+    ) -> Result<Vec<Address>, HttpError> {
+        let values = TemplateValues {
+            query,
+            limit: DISPLAYED_SUGGESTIONS + 1,
+            country,
+        };
+        let url = format!(
+            "{}{}",
+            self.url,
+            template::render(&self.url_template, &values)
+        );
+        let kind = OperationKind::SuggestionsFetch {
+            query: query.to_string(),
+        };
+        pending::label("GET", &url, kind);
+        match self.response_format {
+            ProviderResponseFormat::Suggestions => Http::get(url)
+                .build()
+                .into_future(ctx)
+                .await?
+                .take_body()
+                .ok_or_else(missing_body)
+                .and_then(|body| wire::decode_suggestions(&body))
+                .map(|suggestions| suggestions.into_iter().map(Into::into).collect()),
+            ProviderResponseFormat::Results => Http::get(url)
+                .expect_json::<ProviderResults>()
+                .build()
+                .into_future(ctx)
+                .await?
+                .take_body()
+                .map(|body| body.results.into_iter().map(Into::into).collect())
+                .ok_or_else(missing_body),
+        }
+    }
+}
+
+fn missing_body() -> HttpError {
+    HttpError::Json("response has no body".to_string())
+}
+
+/// Whether the request never reached a server, as opposed to a server
+/// answering with an error or an unreadable body.
+fn is_network_error(error: &HttpError) -> bool {
+    match error {
+        HttpError::Io(_) | HttpError::Timeout => true,
+        HttpError::Http { .. } | HttpError::Json(_) | HttpError::Url(_) => false,
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum AddressEvent {
+    FetchSuggestions {
+        ident: FieldIdent,
+        query: String,
+    },
+    SuggestionsReceived {
+        ident: FieldIdent,
+        sequence: u64,
+        result: AddressSuggestionsResult,
+    },
+    SelectSuggestion {
+        suggestion: Address,
+    },
+    ClearSuggestions,
+}
+
+/// Suggestions for one field.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct FieldSuggestions {
+    suggestions: Vec<Address>,
+    /// Query the suggestions were fetched for
+    query: String,
+    /// Number of the latest fetch; only its answer is shown
+    #[serde(default)]
+    sequence: u64,
+    /// Whether `suggestions` came from the bundled offline list
+    offline: bool,
+    /// Set once the user no longer wants suggestions, e.g. after leaving
+    /// the field; answers arriving since are dropped until the next fetch
+    #[serde(default)]
+    dismissed: bool,
+    /// The latest fetch is still waiting for an answer
+    #[serde(default)]
+    loading: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AddressHandler {
+    suggestions: BTreeMap<FieldIdent, FieldSuggestions>,
+    /// Used when no providers are configured; without either, nothing is
+    /// fetched
+    api_url: Option<String>,
+    #[serde(default)]
+    providers: Vec<AddressProvider>,
+    #[serde(default)]
+    country: Option<String>,
+    /// Where the user is, if the shell has shared it
+    #[serde(default)]
+    user_location: Option<Coordinates>,
+}
+
+impl AddressHandler {
+    pub fn new(api_url: Option<String>) -> Self {
+        Self {
+            suggestions: BTreeMap::new(),
+            api_url,
+            providers: Vec::new(),
+            country: None,
+            user_location: None,
+        }
+    }
+
+    /// Suggestions received from now on are sorted nearest first.
+    pub fn handle_set_user_location(
+        &mut self,
+        location: Option<Coordinates>,
+    ) -> Command<Effect, Event> {
+        self.user_location = location;
+        Command::done()
+    }
+
+    /// Replaces the configured providers; with none, `api_url` is used.
+    pub fn set_providers(&mut self, providers: Vec<AddressProvider>) {
+        self.providers = providers;
+    }
+
+    pub fn set_api_url(&mut self, api_url: Option<String>) {
+        self.api_url = api_url;
+    }
+
+    /// Whether there is anywhere to fetch suggestions from.
+    pub fn has_endpoint(&self) -> bool {
+        self.api_url.is_some() || !self.providers.is_empty()
+    }
+
+    pub fn handle_select_country(&mut self, country: Option<String>) -> Command<Effect, Event> {
+        self.country = country
+            .map(|country| country.trim().to_string())
+            .filter(|country| !country.is_empty());
+        Command::done()
+    }
+
+    /// The first provider serving the selected country, then the next one
+    /// as its fallback.
+    fn candidate_providers(&self) -> Vec<AddressProvider> {
+        if self.providers.is_empty() {
+            return self.api_url.iter().map(AddressProvider::new).collect();
+        }
+        self.providers
+            .iter()
+            .filter(|provider| provider.serves(self.country.as_deref()))
+            .take(2)
+            .cloned()
+            .collect()
+    }
+
+    pub fn handle_fetch_suggestions(
+        &mut self,
+        ident: FieldIdent,
+        query: String,
+    ) -> Command<crate::app::Effect, crate::app::Event> {
+        self.handle_fetch_suggestions_with_retries(ident, query, Backoff::default())
+    }
+
+    /// Like `handle_fetch_suggestions`, retrying each provider after each of
+    /// the `backoff` delays before moving on to the next.
+    pub fn handle_fetch_suggestions_with_retries(
+        &mut self,
+        ident: FieldIdent,
+        query: String,
+        backoff: Backoff,
+    ) -> Command<crate::app::Effect, crate::app::Event> {
+        let providers = self.candidate_providers();
+        let country = self.country.clone();
+        let entry = self.suggestions.entry(ident).or_default();
+        entry.query = query.clone();
+        entry.sequence += 1;
+        entry.dismissed = false;
+        entry.loading = true;
+        let sequence = entry.sequence;
+        Command::new(move |ctx| async move {
+            let mut unreachable = true;
+            for provider in &providers {
+                let attempt = || provider.fetch(query.trim(), country.as_deref(), ctx.clone());
+                match retrying(&ctx, &backoff, attempt).await {
+                    Ok(suggestions) => {
+                        ctx.send_event(Event::SuggestionsReceived {
+                            ident,
+                            sequence,
+                            result: AddressSuggestionsResult::Success(suggestions),
+                        });
+                        return;
+                    }
+                    Err(error) => unreachable &= is_network_error(&error),
+                }
+            }
+            let result = if unreachable {
+                AddressSuggestionsResult::Unreachable
+            } else {
+                AddressSuggestionsResult::Error
+            };
+            ctx.send_event(Event::SuggestionsReceived {
+                ident,
+                sequence,
+                result,
+            });
+        })
+    }
+
+    /// Fetches the last query of `ident` again, if it has one.
+    pub fn handle_retry_suggestions(&self, ident: FieldIdent) -> Command<Effect, Event> {
+        let query = self.query_for(ident);
+        if query.is_empty() {
+            return Command::done();
+        }
+        Command::event(Event::FetchSuggestions {
+            ident,
+            query: query.to_string(),
+        })
+    }
+
+    /// Stores the received suggestions under `ident`, dropping the value
+    /// already in that field and any duplicates, then capping the list. When offline, the
+    /// bundled list is searched for the last query instead. With a user
+    /// location, suggestions with coordinates come first, nearest first.
+    /// Answers to an older fetch than the latest are dropped.
+    pub fn handle_suggestions_received(
+        &mut self,
+        ident: FieldIdent,
+        sequence: u64,
+        result: AddressSuggestionsResult,
+        current_value: &str,
+    ) -> Command<crate::app::Effect, crate::app::Event> {
+        let near = self.user_location;
+        let entry = self.suggestions.entry(ident).or_default();
+        if sequence != entry.sequence || entry.dismissed {
+            return Command::done();
+        }
+        entry.loading = false;
+        match result {
+            AddressSuggestionsResult::Success(suggestions) => {
+                entry.suggestions = filter_suggestions(suggestions, current_value, near);
+                entry.offline = false;
+            }
+            AddressSuggestionsResult::Error => {
+                entry.suggestions.clear();
+                entry.offline = false;
+            }
+            AddressSuggestionsResult::Unreachable => {
+                entry.suggestions =
+                    filter_suggestions(offline_matches(&entry.query), current_value, near);
+                entry.offline = true;
+            }
+        }
+        render()
+    }
+
+    /// Ends the latest fetch of `ident` without showing its answer, e.g.
+    /// because the user has typed on since. Renders if that was it.
+    pub fn handle_answer_dropped(
+        &mut self,
+        ident: FieldIdent,
+        sequence: u64,
+    ) -> Command<Effect, Event> {
+        match self.suggestions.get_mut(&ident) {
+            Some(entry) if entry.loading && entry.sequence == sequence => {
+                entry.loading = false;
+                render()
+            }
+            _ => Command::done(),
+        }
+    }
+
+    pub fn handle_select_suggestion(
+        &mut self,
+        suggestion: Address,
+    ) -> Command<crate::app::Effect, crate::app::Event> {
+        self.dismiss_all();
+        Command::event(Event::UpdateValue {
+            ident: FieldIdent::Address,
+            value: suggestion.display.clone(),
+        })
+        .then(render())
+    }
+
+    pub fn suggestion_at(&self, index: usize) -> Option<&Address> {
+        self.suggestions
+            .get(&FieldIdent::Address)?
+            .suggestions
+            .get(index)
+    }
+
+    /// Selects the address suggestion at `index` and reports the chosen rank.
+    pub fn handle_select_suggestion_at(&mut self, index: usize) -> Command<Effect, Event> {
+        let Some(entry) = self.suggestions.get(&FieldIdent::Address) else {
+            return Command::done();
+        };
+        let Some(suggestion) = entry.suggestions.get(index).cloned() else {
+            return Command::done();
+        };
+        let selected: Command<Effect, Event> =
+            Command::notify_shell(AnalyticsOperation::SuggestionSelected {
+                query: entry.query.clone(),
+                selected_index: index,
+                result_count: entry.suggestions.len(),
+            })
+            .into();
+        selected.and(self.handle_select_suggestion(suggestion))
+    }
+
+    pub fn handle_clear_suggestions(&mut self) -> Command<crate::app::Effect, crate::app::Event> {
+        self.clear();
+        render()
+    }
+
+    /// Drops the current suggestions of every field without rendering.
+    pub fn clear(&mut self) {
+        for entry in self.suggestions.values_mut() {
+            entry.suggestions.clear();
+            entry.offline = false;
+        }
+    }
+
+    /// Drops the suggestions of `ident`, along with the answer to any fetch
+    /// still on its way, e.g. as the user leaves the field.
+    pub fn dismiss(&mut self, ident: FieldIdent) {
+        if let Some(entry) = self.suggestions.get_mut(&ident) {
+            entry.suggestions.clear();
+            entry.offline = false;
+            entry.dismissed = true;
+            entry.loading = false;
+        }
+    }
+
+    /// `dismiss` for every field.
+    pub fn dismiss_all(&mut self) {
+        for ident in FieldIdent::ALL {
+            self.dismiss(ident);
+        }
+    }
+
+    /// Query the field's suggestions were last fetched for.
+    pub fn query_for(&self, ident: FieldIdent) -> &str {
+        self.suggestions
+            .get(&ident)
+            .map_or("", |entry| entry.query.as_str())
+    }
+
+    pub fn suggestions_for(&self, ident: FieldIdent) -> &[Address] {
+        self.suggestions
+            .get(&ident)
+            .map_or(&[], |entry| entry.suggestions.as_slice())
+    }
+
+    /// Whether the answer to the latest fetch for `ident` is still awaited.
+    pub fn is_loading(&self, ident: FieldIdent) -> bool {
+        self.suggestions
+            .get(&ident)
+            .map_or(false, |entry| entry.loading)
+    }
+
+    /// Suggestions for the address field.
+    pub fn get_suggestions(&self) -> &[Address] {
+        self.suggestions_for(FieldIdent::Address)
+    }
+
+    /// The address field's suggestions as shown to the user.
+    pub fn view(&self) -> Vec<SuggestionViewModel> {
+        let Some(entry) = self.suggestions.get(&FieldIdent::Address) else {
+            return Vec::new();
+        };
+        entry
+            .suggestions
+            .iter()
+            .map(|suggestion| SuggestionViewModel {
+                suggestion: suggestion.clone(),
+                offline: entry.offline,
+                primary_line: suggestion.primary_line(),
+                secondary_line: suggestion.secondary_line(),
+            })
+            .collect()
+    }
+}
+
+/// Bundled suggestions containing `query`, ignoring case.
+fn offline_matches(query: &str) -> Vec<Address> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    OFFLINE_SUGGESTIONS
+        .iter()
+        .filter(|suggestion| suggestion.display.to_lowercase().contains(&query))
+        .cloned()
+        .collect()
+}
+
+fn same_address(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+fn filter_suggestions(
+    suggestions: Vec<Address>,
+    current_address: &str,
+    near: Option<Coordinates>,
+) -> Vec<Address> {
+    let mut filtered: Vec<Address> = Vec::with_capacity(suggestions.len());
+    for suggestion in suggestions {
+        if same_address(&suggestion.display, current_address)
+            || filtered
+                .iter()
+                .any(|kept| same_address(&kept.display, &suggestion.display))
+        {
+            continue;
+        }
+        filtered.push(suggestion);
+    }
+    if let Some(near) = near {
+        // Stable, so suggestions without coordinates keep relevance order
+        filtered.sort_by(|a, b| match (a.distance(near), b.distance(near)) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+    }
+    filtered.truncate(DISPLAYED_SUGGESTIONS);
+    filtered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crux_http::protocol::{HttpResponse, HttpResult};
+
+    const API_URL: &str = "http://localhost:8000/api/suggestions";
+
+    #[test]
+    fn test_address_handler_fetch_suggestions() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let mut cmd = handler.handle_fetch_suggestions(FieldIdent::Address, "test".to_string());
+        let effect = cmd.effects().next().unwrap();
+        assert!(matches!(effect, Effect::Http(_)));
+    }
+
+    #[test]
+    fn test_address_handler_suggestions_received() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let suggestions = vec![Address {
+            street: "123 Test St".to_string(),
+            city: "London".to_string(),
+            postcode: "SW1A 1AA".to_string(),
+            country: "UK".to_string(),
+            display: "123 Test St, London, SW1A 1AA, UK".to_string(),
+            lat: None,
+            lon: None,
+        }];
+        let mut cmd = handler.handle_suggestions_received(
+            FieldIdent::Address,
+            0,
+            AddressSuggestionsResult::Success(suggestions.clone()),
+            "",
+        );
+        let effect = cmd.effects().next().unwrap();
+        assert!(matches!(effect, Effect::Render(_)));
+        assert_eq!(handler.get_suggestions(), suggestions);
+    }
+
+    #[test]
+    fn test_address_handler_clear_suggestions() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let _ = handler.handle_suggestions_received(
+            FieldIdent::Address,
+            0,
+            AddressSuggestionsResult::Success(vec![Address {
+                street: "123 Test St".to_string(),
+                city: "London".to_string(),
+                postcode: "SW1A 1AA".to_string(),
+                country: "UK".to_string(),
+                display: "123 Test St, London, SW1A 1AA, UK".to_string(),
+                lat: None,
+                lon: None,
+            }]),
+            "",
+        );
+        let mut cmd = handler.handle_clear_suggestions();
+        let effect = cmd.effects().next().unwrap();
+        assert!(matches!(effect, Effect::Render(_)));
+        assert!(handler.get_suggestions().is_empty());
+    }
+
+    #[test]
+    fn test_address_handler_filters_current_address_and_duplicates() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let suggestion = |street: &str| Address {
+            street: street.to_string(),
+            city: "London".to_string(),
+            postcode: "SW1A 1AA".to_string(),
+            country: "UK".to_string(),
+            display: format!("{street}, London, SW1A 1AA, UK"),
+            lat: None,
+            lon: None,
+        };
+        let _ = handler.handle_suggestions_received(
+            FieldIdent::Address,
+            0,
+            AddressSuggestionsResult::Success(vec![
+                suggestion("123 Test St"),
+                suggestion("124 Test St"),
+                suggestion("124 Test St"),
+                suggestion("125 Test St"),
+            ]),
+            " 123 test st, London, SW1A 1AA, UK",
+        );
+        assert_eq!(
+            handler.get_suggestions(),
+            vec![suggestion("124 Test St"), suggestion("125 Test St")]
+        );
+    }
+
+    fn uk_and_generic_providers() -> Vec<AddressProvider> {
+        vec![
+            AddressProvider {
+                countries: vec!["UK".to_string()],
+                ..AddressProvider::new("https://uk.example.com/lookup")
+            },
+            AddressProvider {
+                response_format: ProviderResponseFormat::Results,
+                ..AddressProvider::new("https://generic.example.com/search")
+            },
+        ]
+    }
+
+    #[test]
+    fn test_address_handler_selects_provider_by_country() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        handler.set_providers(uk_and_generic_providers());
+
+        let mut cmd = handler.handle_fetch_suggestions(FieldIdent::Address, "Baker".to_string());
+        let Some(Effect::Http(request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        assert_eq!(
+            request.operation.url,
+            "https://generic.example.com/search?query=Baker&limit=6"
+        );
+
+        let _ = handler.handle_select_country(Some("uk".to_string()));
+        let mut cmd = handler.handle_fetch_suggestions(FieldIdent::Address, "Baker".to_string());
+        let Some(Effect::Http(request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        assert_eq!(
+            request.operation.url,
+            "https://uk.example.com/lookup?query=Baker&limit=6&country=uk"
+        );
+    }
+
+    #[test]
+    fn test_address_handler_renders_provider_template() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        handler.set_providers(vec![AddressProvider {
+            url_template: "/v1/autocomplete?text={query}&limit={limit}&region={country}"
+                .to_string(),
+            ..AddressProvider::new("https://provider.example.com")
+        }]);
+
+        let mut cmd =
+            handler.handle_fetch_suggestions(FieldIdent::Address, "Baker St #2".to_string());
+        let Some(Effect::Http(request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        assert_eq!(
+            request.operation.url,
+            "https://provider.example.com/v1/autocomplete?text=Baker%20St%20%232&limit=6"
+        );
+    }
+
+    #[test]
+    fn test_address_handler_falls_back_once_and_normalizes_results() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        handler.set_providers(uk_and_generic_providers());
+        let _ = handler.handle_select_country(Some("UK".to_string()));
+
+        let mut cmd = handler.handle_fetch_suggestions(FieldIdent::Address, "Baker".to_string());
+        let Some(Effect::Http(mut request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        request
+            .resolve(HttpResult::Ok(HttpResponse::status(503).build()))
+            .unwrap();
+        assert!(cmd.events().next().is_none());
+
+        let Some(Effect::Http(mut request)) = cmd.effects().next() else {
+            panic!("expected a fallback request");
+        };
+        assert_eq!(
+            request.operation.url,
+            "https://generic.example.com/search?query=Baker&limit=6&country=UK"
+        );
+        request
+            .resolve(HttpResult::Ok(
+                HttpResponse::ok()
+                    .json(serde_json::json!({
+                        "results": [{
+                            "address_line": "221B Baker Street",
+                            "locality": "London",
+                            "postal_code": "NW1 6XE",
+                            "country": "UK",
+                            "latitude": 51.5238,
+                            "longitude": -0.1586,
+                        }]
+                    }))
+                    .build(),
+            ))
+            .unwrap();
+
+        assert_eq!(
+            cmd.events().next(),
+            Some(Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 1,
+                result: AddressSuggestionsResult::Success(vec![Address {
+                    street: "221B Baker Street".to_string(),
+                    city: "London".to_string(),
+                    postcode: "NW1 6XE".to_string(),
+                    country: "UK".to_string(),
+                    display: "221B Baker Street, London, NW1 6XE UK".to_string(),
+                    lat: Some(51.5238),
+                    lon: Some(-0.1586),
+                }]),
+            })
+        );
+    }
+
+    #[test]
+    fn test_address_handler_surfaces_error_after_one_fallback() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let mut providers = uk_and_generic_providers();
+        providers.push(AddressProvider::new("https://third.example.com/search"));
+        handler.set_providers(providers);
+        let _ = handler.handle_select_country(Some("UK".to_string()));
+
+        let mut cmd = handler.handle_fetch_suggestions(FieldIdent::Address, "Baker".to_string());
+        for _ in 0..2 {
+            let Some(Effect::Http(mut request)) = cmd.effects().next() else {
+                panic!("expected an http request");
+            };
+            request
+                .resolve(HttpResult::Ok(HttpResponse::status(500).build()))
+                .unwrap();
+        }
+
+        assert!(cmd.effects().next().is_none());
+        assert_eq!(
+            cmd.events().next(),
+            Some(Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 1,
+                result: AddressSuggestionsResult::Error,
+            })
+        );
+    }
+
+    #[test]
+    fn test_address_handler_select_suggestion_at_reports_rank() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let suggestions: Vec<Address> = (1..=5)
+            .map(|n| Address {
+                street: format!("{n} Test St"),
+                city: "London".to_string(),
+                postcode: "SW1A 1AA".to_string(),
+                country: "UK".to_string(),
+                display: format!("{n} Test St, London, SW1A 1AA, UK"),
+                lat: None,
+                lon: None,
+            })
+            .collect();
+        let _ = handler.handle_fetch_suggestions(FieldIdent::Address, "Test St".to_string());
+        let _ = handler.handle_suggestions_received(
+            FieldIdent::Address,
+            1,
+            AddressSuggestionsResult::Success(suggestions),
+            "",
+        );
+
+        let mut cmd = handler.handle_select_suggestion_at(2);
+
+        let analytics: Vec<AnalyticsOperation> = cmd
+            .effects()
+            .filter_map(|effect| match effect {
+                Effect::Analytics(request) => Some(request.operation),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            analytics,
+            vec![AnalyticsOperation::SuggestionSelected {
+                query: "Test St".to_string(),
+                selected_index: 2,
+                result_count: 5,
+            }]
+        );
+        assert_eq!(
+            cmd.events().next(),
+            Some(Event::UpdateValue {
+                ident: FieldIdent::Address,
+                value: "3 Test St, London, SW1A 1AA, UK".to_string(),
+            })
+        );
+        assert!(handler.get_suggestions().is_empty());
+    }
+
+    #[test]
+    fn test_address_handler_select_suggestion_at_out_of_range() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let mut cmd = handler.handle_select_suggestion_at(0);
+        assert!(cmd.effects().next().is_none());
+        assert!(cmd.events().next().is_none());
+    }
+
+    #[test]
+    fn test_address_handler_backfills_after_filtering_current_address() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let suggestion = |n: usize| Address {
+            street: format!("{n} Test St"),
+            city: "London".to_string(),
+            postcode: "SW1A 1AA".to_string(),
+            country: "UK".to_string(),
+            display: format!("{n} Test St, London, SW1A 1AA, UK"),
+            lat: None,
+            lon: None,
+        };
+        let six: Vec<Address> = (1..=6).map(suggestion).collect();
+
+        let _ = handler.handle_suggestions_received(
+            FieldIdent::Address,
+            0,
+            AddressSuggestionsResult::Success(six.clone()),
+            "3 Test St, London, SW1A 1AA, UK",
+        );
+        let shown: Vec<&str> = handler
+            .get_suggestions()
+            .iter()
+            .map(|s| s.street.as_str())
+            .collect();
+        assert_eq!(
+            shown,
+            vec![
+                "1 Test St",
+                "2 Test St",
+                "4 Test St",
+                "5 Test St",
+                "6 Test St"
+            ]
+        );
+
+        let _ = handler.handle_suggestions_received(
+            FieldIdent::Address,
+            0,
+            AddressSuggestionsResult::Success(six),
+            "",
+        );
+        assert_eq!(handler.get_suggestions().len(), DISPLAYED_SUGGESTIONS);
+    }
+
+    #[test]
+    fn test_address_handler_passes_selected_country() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let _ = handler.handle_select_country(Some("IE".to_string()));
+
+        let mut cmd = handler.handle_fetch_suggestions(FieldIdent::Address, "Grafton".to_string());
+        let Some(Effect::Http(request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        assert_eq!(
+            request.operation.url,
+            format!("{API_URL}?query=Grafton&limit=6&country=IE")
+        );
+    }
+
+    #[test]
+    fn test_offline_suggestions_asset_parses() {
+        assert_eq!(OFFLINE_SUGGESTIONS.len(), 12);
+    }
+
+    #[test]
+    fn test_address_handler_uses_offline_suggestions_when_unreachable() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let mut cmd = handler.handle_fetch_suggestions(FieldIdent::Address, "london".to_string());
+        let Some(Effect::Http(mut request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        request
+            .resolve(HttpResult::Err(HttpError::Io(
+                "connection refused".to_string(),
+            )))
+            .unwrap();
+        let Some(Event::SuggestionsReceived { result, .. }) = cmd.events().next() else {
+            panic!("expected suggestions");
+        };
+        assert_eq!(result, AddressSuggestionsResult::Unreachable);
+
+        let _ = handler.handle_suggestions_received(FieldIdent::Address, 1, result, "london");
+        let view = handler.view();
+        assert_eq!(view.len(), 3);
+        assert!(view.iter().all(|entry| entry.offline));
+        assert!(view.iter().all(|entry| entry.suggestion.city == "London"));
+    }
+
+    #[test]
+    fn test_address_handler_prefers_online_suggestions() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let online = Address {
+            street: "1 Canada Square".to_string(),
+            city: "London".to_string(),
+            postcode: "E14 5AB".to_string(),
+            country: "UK".to_string(),
+            display: "1 Canada Square, London, E14 5AB, UK".to_string(),
+            lat: None,
+            lon: None,
+        };
+        let mut cmd = handler.handle_fetch_suggestions(FieldIdent::Address, "london".to_string());
+        let Some(Effect::Http(mut request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        request
+            .resolve(HttpResult::Ok(
+                HttpResponse::ok().json(vec![online.clone()]).build(),
+            ))
+            .unwrap();
+        let Some(Event::SuggestionsReceived { result, .. }) = cmd.events().next() else {
+            panic!("expected suggestions");
+        };
+
+        let _ = handler.handle_suggestions_received(FieldIdent::Address, 1, result, "london");
+        assert_eq!(
+            handler.view(),
+            vec![SuggestionViewModel {
+                primary_line: online.primary_line(),
+                secondary_line: online.secondary_line(),
+                suggestion: online,
+                offline: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_address_handler_recovers_leading_suggestions_from_malformed_body() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let mut cmd = handler.handle_fetch_suggestions(FieldIdent::Address, "Baker".to_string());
+        let Some(Effect::Http(mut request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        let suggestions: Vec<Address> = (1..=3)
+            .map(|n| Address {
+                street: format!("{n} Baker Street"),
+                city: "London".to_string(),
+                postcode: "NW1 6XE".to_string(),
+                country: "UK".to_string(),
+                display: format!("{n} Baker Street, London, NW1 6XE UK"),
+                lat: None,
+                lon: None,
+            })
+            .collect();
+        let valid = serde_json::to_string(&suggestions).unwrap();
+        let body = format!("{},{{\"street\": \"4 Baker", valid.trim_end_matches(']'));
+        request
+            .resolve(HttpResult::Ok(HttpResponse::ok().body(body).build()))
+            .unwrap();
+
+        assert_eq!(
+            cmd.events().next(),
+            Some(Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 1,
+                result: AddressSuggestionsResult::Success(suggestions),
+            })
+        );
+    }
+
+    #[test]
+    fn test_address_handler_server_error_is_not_offline() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let mut cmd = handler.handle_fetch_suggestions(FieldIdent::Address, "london".to_string());
+        let Some(Effect::Http(mut request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        request
+            .resolve(HttpResult::Ok(HttpResponse::status(500).build()))
+            .unwrap();
+        assert_eq!(
+            cmd.events().next(),
+            Some(Event::SuggestionsReceived {
+                ident: FieldIdent::Address,
+                sequence: 1,
+                result: AddressSuggestionsResult::Error,
+            })
+        );
+    }
+
+    #[test]
+    fn test_address_handler_sorts_by_distance_from_user() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let suggestion = |street: &str, at: Option<(f64, f64)>| Address {
+            street: street.to_string(),
+            city: "London".to_string(),
+            postcode: "SW1A 1AA".to_string(),
+            country: "UK".to_string(),
+            display: format!("{street}, London, SW1A 1AA, UK"),
+            lat: at.map(|(lat, _)| lat),
+            lon: at.map(|(_, lon)| lon),
+        };
+        let received = vec![
+            suggestion("Unknown Lane", None),
+            suggestion("Canary Wharf", Some((51.5054, -0.0235))),
+            suggestion("Piccadilly Circus", Some((51.5100, -0.1345))),
+        ];
+
+        let _ = handler.handle_suggestions_received(
+            FieldIdent::Address,
+            0,
+            AddressSuggestionsResult::Success(received.clone()),
+            "",
+        );
+        let streets = |handler: &AddressHandler| -> Vec<String> {
+            handler
+                .get_suggestions()
+                .iter()
+                .map(|s| s.street.clone())
+                .collect()
+        };
+        assert_eq!(
+            streets(&handler),
+            vec!["Unknown Lane", "Canary Wharf", "Piccadilly Circus"]
+        );
+
+        // Leicester Square
+        let _ = handler.handle_set_user_location(Some(Coordinates {
+            lat: 51.5103,
+            lon: -0.1301,
+        }));
+        let _ = handler.handle_suggestions_received(
+            FieldIdent::Address,
+            0,
+            AddressSuggestionsResult::Success(received),
+            "",
+        );
+        assert_eq!(
+            streets(&handler),
+            vec!["Piccadilly Circus", "Canary Wharf", "Unknown Lane"]
+        );
+    }
+
+    #[test]
+    fn test_address_suggestion_distance() {
+        let from = Coordinates {
+            lat: 51.5034,
+            lon: -0.1276,
+        };
+        let mut suggestion = Address {
+            street: "221B Baker Street".to_string(),
+            city: "London".to_string(),
+            postcode: "NW1 6XE".to_string(),
+            country: "UK".to_string(),
+            display: "221B Baker Street, London, NW1 6XE, UK".to_string(),
+            lat: None,
+            lon: None,
+        };
+        assert_eq!(suggestion.distance(from), None);
+
+        suggestion.lat = Some(51.5238);
+        suggestion.lon = Some(-0.1586);
+        let km = suggestion.distance(from).unwrap();
+        assert!((km - 3.12).abs() < 0.01, "{km}");
+    }
+
+    #[test]
+    fn test_address_handler_stores_suggestions_per_field() {
+        let mut handler = AddressHandler::new(Some(API_URL.to_string()));
+        let mut cmd = handler.handle_fetch_suggestions(FieldIdent::Address, "Baker".to_string());
+        let Some(Effect::Http(mut request)) = cmd.effects().next() else {
+            panic!("expected an http request");
+        };
+        let suggestion = Address {
+            street: "221B Baker Street".to_string(),
+            city: "London".to_string(),
+            postcode: "NW1 6XE".to_string(),
+            country: "UK".to_string(),
+            display: "221B Baker Street, London, NW1 6XE, UK".to_string(),
+            lat: None,
+            lon: None,
+        };
+        request
+            .resolve(HttpResult::Ok(
+                HttpResponse::ok().json(vec![suggestion.clone()]).build(),
+            ))
+            .unwrap();
+        let Some(Event::SuggestionsReceived {
+            ident,
+            sequence,
+            result,
+        }) = cmd.events().next()
+        else {
+            panic!("expected suggestions");
+        };
+        assert_eq!(ident, FieldIdent::Address);
+
+        let _ = handler.handle_suggestions_received(ident, sequence, result, "Baker");
+        assert_eq!(
+            handler.suggestions_for(FieldIdent::Address),
+            vec![suggestion]
+        );
+        assert!(handler.suggestions_for(FieldIdent::Username).is_empty());
+    }
+}
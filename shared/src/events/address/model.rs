@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+use super::Coordinates;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// An address as the core stores and shows it, whatever shape the provider
+/// sent it in. Serialized under its original name so shells are unaffected.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename = "AddressSuggestion")]
+pub struct Address {
+    pub street: String,
+    pub city: String,
+    pub postcode: String,
+    pub country: String,
+    /// The whole address on one line
+    #[serde(rename = "combined")]
+    pub display: String,
+    /// Latitude in degrees, when the provider knows it
+    #[serde(default)]
+    pub lat: Option<f64>,
+    /// Longitude in degrees, when the provider knows it
+    #[serde(default)]
+    pub lon: Option<f64>,
+}
+
+#[cfg(test)]
+impl Address {
+    /// `n` distinct addresses on Baker Street, the same on every call.
+    pub fn london_sample(n: usize) -> Vec<Address> {
+        (1..=n)
+            .map(|number| Address {
+                street: format!("{number} Baker Street"),
+                city: "London".to_string(),
+                postcode: "NW1 6XE".to_string(),
+                country: "UK".to_string(),
+                display: format!("{number} Baker Street, London, NW1 6XE UK"),
+                lat: None,
+                lon: None,
+            })
+            .collect()
+    }
+}
+
+impl Address {
+    /// First line of a two-line display: the street, or the whole address
+    /// when the provider sent no street.
+    pub fn primary_line(&self) -> String {
+        match self.street.trim() {
+            "" => self.display.trim().to_string(),
+            street => street.to_string(),
+        }
+    }
+
+    /// Second line of a two-line display: city, postcode and country, with
+    /// missing parts left out. Empty when `primary_line` shows the whole
+    /// address.
+    pub fn secondary_line(&self) -> String {
+        if self.street.trim().is_empty() {
+            return String::new();
+        }
+        [&self.city, &self.postcode, &self.country]
+            .iter()
+            .map(|part| part.trim())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Great-circle distance in kilometres from `from`, if this address has
+    /// coordinates.
+    pub fn distance(&self, from: Coordinates) -> Option<f64> {
+        let (lat, lon) = (self.lat?, self.lon?);
+        let (lat1, lat2) = (from.lat.to_radians(), lat.to_radians());
+        let half_dlat = (lat - from.lat).to_radians() / 2.0;
+        let half_dlon = (lon - from.lon).to_radians() / 2.0;
+        let a = half_dlat.sin().powi(2) + lat1.cos() * lat2.cos() * half_dlon.sin().powi(2);
+        Some(2.0 * EARTH_RADIUS_KM * a.sqrt().asin())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(street: &str, city: &str, postcode: &str, country: &str) -> Address {
+        Address {
+            street: street.to_string(),
+            city: city.to_string(),
+            postcode: postcode.to_string(),
+            country: country.to_string(),
+            display: "10 Downing Street, London, SW1A 2AA, UK".to_string(),
+            lat: None,
+            lon: None,
+        }
+    }
+
+    #[test]
+    fn test_two_line_display() {
+        let full = address("10 Downing Street", "London", "SW1A 2AA", "UK");
+        assert_eq!(full.primary_line(), "10 Downing Street");
+        assert_eq!(full.secondary_line(), "London SW1A 2AA UK");
+
+        let no_postcode = address("10 Downing Street", "London", " ", "UK");
+        assert_eq!(no_postcode.secondary_line(), "London UK");
+
+        let no_street = address("", "London", "SW1A 2AA", "UK");
+        assert_eq!(
+            no_street.primary_line(),
+            "10 Downing Street, London, SW1A 2AA, UK"
+        );
+        assert_eq!(no_street.secondary_line(), "");
+    }
+}
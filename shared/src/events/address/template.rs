@@ -0,0 +1,170 @@
+//! Request URLs of suggestion providers, rendered from a template appended
+//! to the provider's `url`.
+
+/// The request shape of the built-in suggestions API.
+pub const DEFAULT_URL_TEMPLATE: &str = "?query={query}&limit={limit}&country={country}";
+
+const PLACEHOLDERS: [&str; 3] = ["query", "limit", "country"];
+
+/// Values for the placeholders of a template.
+#[derive(Clone, Copy, Debug)]
+pub struct TemplateValues<'a> {
+    pub query: &'a str,
+    pub limit: usize,
+    pub country: Option<&'a str>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Piece<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Splits `template` into text and placeholders, or returns the first
+/// placeholder that is unknown or never closed, as written.
+fn pieces(template: &str) -> Result<Vec<Piece<'_>>, String> {
+    let mut pieces = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        if open > 0 {
+            pieces.push(Piece::Text(&rest[..open]));
+        }
+        let Some(close) = rest[open..].find('}') else {
+            return Err(rest[open..].to_string());
+        };
+        let name = &rest[open + 1..open + close];
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(rest[open..=open + close].to_string());
+        }
+        pieces.push(Piece::Placeholder(name));
+        rest = &rest[open + close + 1..];
+    }
+    if !rest.is_empty() {
+        pieces.push(Piece::Text(rest));
+    }
+    Ok(pieces)
+}
+
+/// Checks every placeholder in `template`, returning the first bad one.
+pub fn validate(template: &str) -> Result<(), String> {
+    pieces(template).map(|_| ())
+}
+
+/// Percent-encodes `value`, with spaces as `%20` so it is valid in the
+/// path as well as the query.
+fn encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
+
+impl TemplateValues<'_> {
+    fn get(&self, placeholder: &str) -> Option<String> {
+        match placeholder {
+            "query" => Some(encode(self.query)),
+            "limit" => Some(self.limit.to_string()),
+            "country" => self.country.map(encode),
+            _ => None,
+        }
+    }
+
+    fn render_part(&self, part: &str) -> String {
+        match pieces(part) {
+            Ok(pieces) => pieces
+                .iter()
+                .map(|piece| match piece {
+                    Piece::Text(text) => text.to_string(),
+                    Piece::Placeholder(name) => self.get(name).unwrap_or_default(),
+                })
+                .collect(),
+            Err(_) => part.to_string(),
+        }
+    }
+
+    /// Whether `parameter` is just `name={placeholder}` with no value for
+    /// the placeholder.
+    fn is_unset(&self, parameter: &str) -> bool {
+        match pieces(parameter).as_deref() {
+            Ok([Piece::Text(name), Piece::Placeholder(placeholder)]) => {
+                name.ends_with('=') && self.get(placeholder).is_none()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Renders `template`, percent-encoding the values. A query parameter that
+/// is only a placeholder without a value, like `country={country}` with no
+/// country selected, is left out.
+pub fn render(template: &str, values: &TemplateValues) -> String {
+    let (path, query) = match template.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (template, None),
+    };
+    let mut url = values.render_part(path);
+    if let Some(query) = query {
+        let parameters: Vec<String> = query
+            .split('&')
+            .filter(|parameter| !values.is_unset(parameter))
+            .map(|parameter| values.render_part(parameter))
+            .collect();
+        if !parameters.is_empty() {
+            url.push('?');
+            url.push_str(&parameters.join("&"));
+        }
+    }
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_fills_every_placeholder() {
+        let values = TemplateValues {
+            query: "Baker",
+            limit: 6,
+            country: Some("UK"),
+        };
+        assert_eq!(
+            render(DEFAULT_URL_TEMPLATE, &values),
+            "?query=Baker&limit=6&country=UK"
+        );
+        assert_eq!(
+            render(
+                "/v1/{country}/autocomplete?text={query}&max={limit}",
+                &values
+            ),
+            "/v1/UK/autocomplete?text=Baker&max=6"
+        );
+    }
+
+    #[test]
+    fn test_render_encodes_values_and_drops_unset_parameters() {
+        let values = TemplateValues {
+            query: "10 Downing St & Co/1?",
+            limit: 6,
+            country: None,
+        };
+        assert_eq!(
+            render(DEFAULT_URL_TEMPLATE, &values),
+            "?query=10%20Downing%20St%20%26%20Co%2F1%3F&limit=6"
+        );
+        assert_eq!(
+            render("/search/{query}?region={country}", &values),
+            "/search/10%20Downing%20St%20%26%20Co%2F1%3F"
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_the_bad_placeholder() {
+        assert_eq!(validate(DEFAULT_URL_TEMPLATE), Ok(()));
+        assert_eq!(validate("/v1/autocomplete"), Ok(()));
+        assert_eq!(
+            validate("?text={query}&lang={language}"),
+            Err("{language}".to_string())
+        );
+        assert_eq!(validate("?text={query"), Err("{query".to_string()));
+    }
+}
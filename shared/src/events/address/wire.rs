@@ -0,0 +1,177 @@
+//! Provider response shapes, kept apart from `model` so a provider change
+//! never alters what shells see.
+
+use crux_http::HttpError;
+use serde::de::{Deserializer as _, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::{model::Address, DISPLAYED_SUGGESTIONS};
+
+/// One element of a `ProviderResponseFormat::Suggestions` response, exactly
+/// as `address_api` sends it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AddressSuggestionDto {
+    pub street: String,
+    pub city: String,
+    pub postcode: String,
+    pub country: String,
+    pub combined: String,
+    #[serde(default)]
+    pub lat: Option<f64>,
+    #[serde(default)]
+    pub lon: Option<f64>,
+}
+
+impl From<AddressSuggestionDto> for Address {
+    fn from(dto: AddressSuggestionDto) -> Self {
+        Self {
+            street: dto.street,
+            city: dto.city,
+            postcode: dto.postcode,
+            country: dto.country,
+            display: dto.combined,
+            lat: dto.lat,
+            lon: dto.lon,
+        }
+    }
+}
+
+/// A `ProviderResponseFormat::Results` response.
+#[derive(Deserialize)]
+pub(super) struct ProviderResults {
+    pub results: Vec<ProviderResult>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ProviderResult {
+    address_line: String,
+    locality: String,
+    postal_code: String,
+    country: String,
+    #[serde(default)]
+    latitude: Option<f64>,
+    #[serde(default)]
+    longitude: Option<f64>,
+}
+
+impl From<ProviderResult> for Address {
+    fn from(result: ProviderResult) -> Self {
+        Self {
+            display: format!(
+                "{}, {}, {} {}",
+                result.address_line, result.locality, result.postal_code, result.country
+            ),
+            street: result.address_line,
+            city: result.locality,
+            postcode: result.postal_code,
+            country: result.country,
+            lat: result.latitude,
+            lon: result.longitude,
+        }
+    }
+}
+
+/// Suggestions decoded from one response; the rest of a longer array is
+/// never parsed.
+const MAX_DECODED_SUGGESTIONS: usize = DISPLAYED_SUGGESTIONS + 1;
+
+/// Decodes a JSON array of suggestions, keeping the valid leading elements
+/// when the array turns malformed or is cut short further on.
+pub(super) fn decode_suggestions(body: &[u8]) -> Result<Vec<AddressSuggestionDto>, HttpError> {
+    struct Leading<'a>(&'a mut Vec<AddressSuggestionDto>);
+
+    impl<'de> Visitor<'de> for Leading<'_> {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an array of address suggestions")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+            while self.0.len() < MAX_DECODED_SUGGESTIONS {
+                match seq.next_element()? {
+                    Some(suggestion) => self.0.push(suggestion),
+                    None => break,
+                }
+            }
+            Ok(())
+        }
+    }
+
+    // Elements are pushed as they decode, so they survive a later error
+    let mut suggestions = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    match (&mut deserializer).deserialize_seq(Leading(&mut suggestions)) {
+        Ok(()) => Ok(suggestions),
+        Err(_) if !suggestions.is_empty() => Ok(suggestions),
+        Err(error) => Err(HttpError::Json(error.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_partial_payload_converts_without_coordinates() {
+        let dto: AddressSuggestionDto = serde_json::from_value(json!({
+            "street": "221B Baker Street",
+            "city": "London",
+            "postcode": "NW1 6XE",
+            "country": "UK",
+            "combined": "221B Baker Street, London, NW1 6XE UK",
+        }))
+        .unwrap();
+        let address = Address::from(dto);
+        assert_eq!(address.display, "221B Baker Street, London, NW1 6XE UK");
+        assert_eq!((address.lat, address.lon), (None, None));
+    }
+
+    #[test]
+    fn test_extra_wire_fields_are_ignored() {
+        let dto: AddressSuggestionDto = serde_json::from_value(json!({
+            "street": "1 Canada Square",
+            "city": "London",
+            "postcode": "E14 5AB",
+            "country": "UK",
+            "combined": "1 Canada Square, London, E14 5AB UK",
+            "lat": 51.5049,
+            "lon": -0.0195,
+            "uprn": "6198213",
+            "confidence": 0.93,
+        }))
+        .unwrap();
+        let address = Address::from(dto);
+        assert_eq!(address.street, "1 Canada Square");
+        assert_eq!((address.lat, address.lon), (Some(51.5049), Some(-0.0195)));
+    }
+
+    #[test]
+    fn test_model_serializes_like_the_wire_shape() {
+        let dto = AddressSuggestionDto {
+            street: "10 Downing Street".to_string(),
+            city: "London".to_string(),
+            postcode: "SW1A 2AA".to_string(),
+            country: "UK".to_string(),
+            combined: "10 Downing Street, London, SW1A 2AA UK".to_string(),
+            lat: None,
+            lon: None,
+        };
+        assert_eq!(
+            serde_json::to_value(Address::from(dto.clone())).unwrap(),
+            serde_json::to_value(dto).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decode_suggestions_without_a_valid_element_is_an_error() {
+        assert!(matches!(
+            decode_suggestions(b"[{\"street\": 1}]"),
+            Err(HttpError::Json(_))
+        ));
+        assert!(matches!(decode_suggestions(b"{}"), Err(HttpError::Json(_))));
+        assert_eq!(decode_suggestions(b"[]").unwrap(), vec![]);
+    }
+}
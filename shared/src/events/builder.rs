@@ -0,0 +1,187 @@
+//! Declarative construction of a `Field` with extra rules.
+
+use std::sync::Arc;
+
+use crate::events::form::{
+    Email, Field, FieldValidator, FormField, OptionalText, Username, Validatable,
+};
+
+/// Builds a `Field` whose added rules run in the order given, after the
+/// value's own. Messages name the field by `label`, "Field" by default.
+pub struct FieldBuilder<T> {
+    initial: T,
+    label: String,
+    required: bool,
+    validators: Vec<FieldValidator<T>>,
+}
+
+impl<T: Clone + PartialEq + Validatable> Field<T> {
+    pub fn builder(initial: T) -> FieldBuilder<T> {
+        FieldBuilder {
+            initial,
+            label: "Field".to_string(),
+            required: false,
+            validators: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + Validatable + 'static> FieldBuilder<T> {
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    pub fn custom(
+        mut self,
+        rule: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.validators.push(Arc::new(rule));
+        self
+    }
+
+    pub fn build(self) -> Field<T> {
+        let mut field = Field::new(self.initial, self.required);
+        for validator in self.validators {
+            field.add_validator(move |value: &T| validator(value));
+        }
+        field.revalidate();
+        field
+    }
+
+    /// A rule that fails with "<label> `message`" unless `pass` holds.
+    fn rule(self, message: &str, pass: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        let message = format!("{} {message}", self.label);
+        self.custom(move |value| pass(value).then_some(()).ok_or_else(|| message.clone()))
+    }
+}
+
+/// Values the text rules apply to. Lengths count characters.
+pub trait FieldText {
+    fn text(&self) -> &str;
+}
+
+impl FieldText for String {
+    fn text(&self) -> &str {
+        self
+    }
+}
+
+impl FieldText for Username {
+    fn text(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FieldText for Email {
+    fn text(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FieldText for OptionalText {
+    fn text(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<T: Clone + PartialEq + Validatable + FieldText + 'static> FieldBuilder<T> {
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self.rule("cannot be empty", |value| !value.text().trim().is_empty())
+    }
+
+    pub fn min_len(self, min: usize) -> Self {
+        self.rule(
+            &format!("must be at least {min} characters"),
+            move |value| value.text().chars().count() >= min,
+        )
+    }
+
+    pub fn max_len(self, max: usize) -> Self {
+        self.rule(&format!("must be at most {max} characters"), move |value| {
+            value.text().chars().count() <= max
+        })
+    }
+}
+
+impl FieldBuilder<Option<u32>> {
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self.rule("cannot be empty", Option::is_some)
+    }
+
+    /// Bounds the value when present; a missing value is left to `required`.
+    pub fn range(self, min: u32, max: u32) -> Self {
+        self.rule(&format!("must be between {min} and {max}"), move |value| {
+            value.map_or(true, |n| (min..=max).contains(&n))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_rules_run_in_order_after_the_value_own() {
+        let mut name = Field::builder(OptionalText::default())
+            .label("Nickname")
+            .required()
+            .min_len(2)
+            .max_len(4)
+            .build();
+        assert!(name.required);
+        for (input, error) in [
+            ("", Some("Nickname cannot be empty")),
+            ("   ", Some("Nickname cannot be empty")),
+            ("a", Some("Nickname must be at least 2 characters")),
+            ("Jonathan", Some("Nickname must be at most 4 characters")),
+            ("Jon", None),
+        ] {
+            name.set_value(OptionalText(input.to_string()));
+            assert_eq!(name.error.as_deref(), error, "input {input:?}");
+            assert_eq!(name.valid, error.is_none(), "input {input:?}");
+        }
+
+        // The username's own rule reports first
+        let mut username = Field::builder(Username(String::new()))
+            .label("Username")
+            .max_len(5)
+            .build();
+        username.set_value(Username("ab".to_string()));
+        assert_eq!(
+            username.error.as_deref(),
+            Some("Username must be at least 3 characters")
+        );
+        username.set_value(Username("abcdef".to_string()));
+        assert_eq!(
+            username.error.as_deref(),
+            Some("Username must be at most 5 characters")
+        );
+    }
+
+    #[test]
+    fn test_builder_bounds_numbers_and_takes_custom_rules() {
+        let mut age = Field::builder(Some(30))
+            .label("Age")
+            .range(21, 65)
+            .custom(|value: &Option<u32>| match value {
+                Some(42) => Err("Unlucky".to_string()),
+                _ => Ok(()),
+            })
+            .build();
+        assert!(age.valid);
+        age.set_value(Some(70));
+        assert_eq!(age.error.as_deref(), Some("Age must be between 21 and 65"));
+        age.set_value(Some(42));
+        assert_eq!(age.error.as_deref(), Some("Unlucky"));
+        age.set_value(None);
+        assert!(age.valid, "left to required");
+
+        let mut age = Field::builder(None).label("Age").required().build();
+        assert_eq!(age.error.as_deref(), Some("Age cannot be empty"));
+        age.set_value(Some(30));
+        assert!(age.valid);
+    }
+}
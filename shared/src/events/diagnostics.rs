@@ -0,0 +1,393 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::hash::{Hash, Hasher};
+
+use crate::capabilities::diagnostics::DiagnosticsReport;
+use crate::events::form::{error_code, FieldIdent};
+
+/// Suggestion attempts kept; older ones are evicted first.
+pub const SUGGESTION_LOG_SIZE: usize = 5;
+
+/// Distinct errors remembered per field; the least recently hit is evicted
+/// first.
+pub const FIELD_ERROR_MEMORY_SIZE: usize = 3;
+
+/// Distinct values counted per remembered error; further ones are not.
+pub const FIELD_ERROR_VALUES_COUNTED: usize = 64;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum SuggestionOutcome {
+    Ok {
+        count: usize,
+    },
+    /// A provider answered with an error or an unreadable body
+    Error,
+    /// No provider could be reached
+    Unreachable,
+}
+
+/// Why a suggestion fetch was not made.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SkipReason {
+    /// The form is submitted, view-only or abandoned
+    Locked,
+    /// A submit is waiting on the email check
+    Submitting,
+    /// Nothing but whitespace to search for
+    EmptyQuery,
+    /// Fewer characters than `AppConfig::min_suggestion_query_len`
+    ShortQuery,
+    /// No `address_api_url` or `address_providers` configured
+    NoEndpoint,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SkippedFetches {
+    pub reason: SkipReason,
+    pub count: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SuggestionAttempt {
+    /// Increases across the session, including across resets
+    pub sequence: u64,
+    pub query: String,
+    pub outcome: SuggestionOutcome,
+}
+
+/// Recent suggestion attempts, for diagnostics only.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SuggestionLog {
+    attempts: VecDeque<SuggestionAttempt>,
+    last_failure: Option<SuggestionAttempt>,
+    next_sequence: u64,
+    #[serde(default)]
+    skipped: BTreeMap<SkipReason, u32>,
+}
+
+impl SuggestionLog {
+    pub fn record(&mut self, query: String, outcome: SuggestionOutcome) {
+        let attempt = SuggestionAttempt {
+            sequence: self.next_sequence,
+            query,
+            outcome,
+        };
+        self.next_sequence += 1;
+        match attempt.outcome {
+            SuggestionOutcome::Ok { .. } => {}
+            SuggestionOutcome::Error | SuggestionOutcome::Unreachable => {
+                self.last_failure = Some(attempt.clone());
+            }
+        }
+        if self.attempts.len() == SUGGESTION_LOG_SIZE {
+            self.attempts.pop_front();
+        }
+        self.attempts.push_back(attempt);
+    }
+
+    /// Counts a fetch that was not made.
+    pub fn record_skip(&mut self, reason: SkipReason) {
+        let count = self.skipped.entry(reason).or_default();
+        *count = count.saturating_add(1);
+    }
+
+    /// Forgets the attempts and skips; sequence numbers keep counting.
+    pub fn clear(&mut self) {
+        self.attempts.clear();
+        self.last_failure = None;
+        self.skipped.clear();
+    }
+
+    pub fn report(&self) -> DiagnosticsReport {
+        DiagnosticsReport {
+            suggestion_attempts: self.attempts.iter().cloned().collect(),
+            last_suggestion_failure: self.last_failure.clone(),
+            skipped_suggestion_fetches: self
+                .skipped
+                .iter()
+                .map(|(reason, count)| SkippedFetches {
+                    reason: *reason,
+                    count: *count,
+                })
+                .collect(),
+            field_errors: Vec::new(),
+        }
+    }
+}
+
+/// How often a field failed with one error, and on how many different values.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FieldErrorStats {
+    pub ident: FieldIdent,
+    /// Wording of the error when last hit
+    pub error: String,
+    pub code: Option<String>,
+    pub occurrences: u32,
+    pub distinct_values: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct ErrorTally {
+    error: String,
+    #[serde(default)]
+    code: Option<String>,
+    occurrences: u32,
+    value_hashes: BTreeSet<u64>,
+}
+
+impl ErrorTally {
+    /// Errors with a code are told apart by it, so a tally outlives a
+    /// change of wording; others by their text.
+    fn key(&self) -> &str {
+        self.code.as_deref().unwrap_or(&self.error)
+    }
+}
+
+/// The validation errors each field has hit, for diagnostics only. Values
+/// are kept as salted hashes, enough to count them but not to recover them.
+/// Until the session has a salt, values are not hashed and so not counted.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ErrorMemory {
+    /// From the shell's random source. Never serialized, so hashes in a
+    /// dump cannot be matched against guessed values
+    #[serde(skip)]
+    salt: Option<u64>,
+    /// Whether a salt was set, so that one lost to serialization is asked
+    /// for again
+    #[serde(default)]
+    salted: bool,
+    #[serde(skip)]
+    salt_requested: bool,
+    /// Least recently hit first
+    fields: BTreeMap<FieldIdent, VecDeque<ErrorTally>>,
+}
+
+impl ErrorMemory {
+    /// Starts a new session, which waits for a salt of its own.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Hashes values with `salt` from now on, unless the session has one.
+    pub fn set_salt(&mut self, salt: u64) {
+        if self.salt.is_none() {
+            self.salt = Some(salt);
+            self.salted = true;
+        }
+    }
+
+    /// Whether the salt was lost to serialization and should be asked for
+    /// again. True once, until the answer arrives.
+    pub fn wants_salt(&mut self) -> bool {
+        let wants = self.salted && self.salt.is_none() && !self.salt_requested;
+        self.salt_requested |= wants;
+        wants
+    }
+
+    /// Records that `value` failed validation of `ident` with `error`.
+    pub fn record(&mut self, ident: FieldIdent, error: &str, value: &str) {
+        let value_hash = self.salt.map(|salt| {
+            let mut hasher = DefaultHasher::new();
+            salt.hash(&mut hasher);
+            value.hash(&mut hasher);
+            hasher.finish()
+        });
+
+        let code = error_code(error);
+        let key = code.unwrap_or(error);
+        let tallies = self.fields.entry(ident).or_default();
+        let mut tally = match tallies.iter().position(|tally| tally.key() == key) {
+            Some(index) => tallies.remove(index).expect("index is in bounds"),
+            None => ErrorTally {
+                error: error.to_string(),
+                code: code.map(String::from),
+                occurrences: 0,
+                value_hashes: BTreeSet::new(),
+            },
+        };
+        tally.error = error.to_string();
+        tally.occurrences = tally.occurrences.saturating_add(1);
+        if let Some(value_hash) = value_hash {
+            if tally.value_hashes.len() < FIELD_ERROR_VALUES_COUNTED {
+                tally.value_hashes.insert(value_hash);
+            }
+        }
+        if tallies.len() == FIELD_ERROR_MEMORY_SIZE {
+            tallies.pop_front();
+        }
+        tallies.push_back(tally);
+    }
+
+    /// Forgets every error, keeping the salt.
+    pub fn clear(&mut self) {
+        self.fields.clear();
+    }
+
+    pub fn report(&self) -> Vec<FieldErrorStats> {
+        self.fields
+            .iter()
+            .flat_map(|(ident, tallies)| {
+                tallies.iter().map(|tally| FieldErrorStats {
+                    ident: *ident,
+                    error: tally.error.clone(),
+                    code: tally.code.clone(),
+                    occurrences: tally.occurrences,
+                    distinct_values: tally.value_hashes.len(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::form::USERNAME_HAS_SPACES_MESSAGE;
+
+    #[test]
+    fn test_suggestion_log_evicts_oldest_first() {
+        let mut log = SuggestionLog::default();
+        log.record("Bak".to_string(), SuggestionOutcome::Unreachable);
+        for n in 0..SUGGESTION_LOG_SIZE + 1 {
+            log.record(format!("Baker {n}"), SuggestionOutcome::Ok { count: n });
+        }
+
+        let report = log.report();
+        let kept: Vec<(u64, &str)> = report
+            .suggestion_attempts
+            .iter()
+            .map(|attempt| (attempt.sequence, attempt.query.as_str()))
+            .collect();
+        assert_eq!(
+            kept,
+            vec![
+                (2, "Baker 1"),
+                (3, "Baker 2"),
+                (4, "Baker 3"),
+                (5, "Baker 4"),
+                (6, "Baker 5"),
+            ]
+        );
+        assert_eq!(
+            report.suggestion_attempts[4].outcome,
+            SuggestionOutcome::Ok { count: 5 }
+        );
+        // Evicted, but still the most recent failure
+        assert_eq!(
+            report.last_suggestion_failure,
+            Some(SuggestionAttempt {
+                sequence: 0,
+                query: "Bak".to_string(),
+                outcome: SuggestionOutcome::Unreachable,
+            })
+        );
+
+        log.clear();
+        log.record("Grafton".to_string(), SuggestionOutcome::Error);
+        let report = log.report();
+        assert_eq!(report.suggestion_attempts.len(), 1);
+        assert_eq!(report.suggestion_attempts[0].sequence, 7);
+    }
+
+    #[test]
+    fn test_error_memory_keeps_recent_distinct_errors() {
+        let mut memory = ErrorMemory::default();
+        memory.set_salt(42);
+        for error in ["first", "second", "third"] {
+            memory.record(FieldIdent::Username, error, "ab");
+        }
+        // Hitting "first" again makes "second" the least recent
+        memory.record(FieldIdent::Username, "first", "abc");
+        memory.record(FieldIdent::Username, "fourth", "ab");
+        memory.record(FieldIdent::Email, "first", "ab");
+
+        let stats = |report: &[FieldErrorStats]| {
+            report
+                .iter()
+                .map(|stats| {
+                    (
+                        stats.ident,
+                        stats.error.clone(),
+                        stats.occurrences,
+                        stats.distinct_values,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            stats(&memory.report()),
+            vec![
+                (FieldIdent::Username, "third".to_string(), 1, 1),
+                (FieldIdent::Username, "first".to_string(), 2, 2),
+                (FieldIdent::Username, "fourth".to_string(), 1, 1),
+                (FieldIdent::Email, "first".to_string(), 1, 1),
+            ]
+        );
+
+        memory.clear();
+        assert!(memory.report().is_empty());
+    }
+
+    #[test]
+    fn test_error_memory_keys_errors_by_code() {
+        let mut memory = ErrorMemory::default();
+        memory.record(FieldIdent::Username, USERNAME_HAS_SPACES_MESSAGE, "Jane D");
+        // As saved by a version with other wording
+        let json = serde_json::to_string(&memory)
+            .unwrap()
+            .replace(USERNAME_HAS_SPACES_MESSAGE, "Usernames have no spaces");
+        let mut memory: ErrorMemory = serde_json::from_str(&json).unwrap();
+        memory.record(FieldIdent::Username, USERNAME_HAS_SPACES_MESSAGE, "Jane  D");
+        memory.record(FieldIdent::Username, "Username cannot be empty", "");
+
+        let report = memory.report();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].error, USERNAME_HAS_SPACES_MESSAGE);
+        assert_eq!(report[0].code.as_deref(), Some("username.spaces"));
+        assert_eq!(report[0].occurrences, 2);
+        assert_eq!(report[1].code, None);
+    }
+
+    #[test]
+    fn test_error_memory_hashes_depend_on_salt() {
+        let hashes = |salt: u64| {
+            let mut memory = ErrorMemory::default();
+            memory.set_salt(salt);
+            memory.record(FieldIdent::Email, "invalid", "bob@");
+            memory.fields[&FieldIdent::Email][0].value_hashes.clone()
+        };
+        assert_eq!(hashes(1), hashes(1));
+        assert_ne!(hashes(1), hashes(2));
+    }
+
+    #[test]
+    fn test_error_memory_counts_values_once_salted() {
+        let mut memory = ErrorMemory::default();
+        memory.record(FieldIdent::Email, "invalid", "bob@");
+        assert_eq!(memory.report()[0].occurrences, 1);
+        assert_eq!(memory.report()[0].distinct_values, 0, "not hashed unsalted");
+        assert!(
+            !memory.wants_salt(),
+            "a fresh session gets its salt on configure"
+        );
+
+        memory.set_salt(1);
+        for n in 0..FIELD_ERROR_VALUES_COUNTED + 10 {
+            memory.record(FieldIdent::Email, "invalid", &format!("bob{n}@"));
+        }
+        assert_eq!(
+            memory.report()[0].distinct_values,
+            FIELD_ERROR_VALUES_COUNTED
+        );
+
+        // The salt does not survive a round trip, and is asked for once
+        let json = serde_json::to_string(&memory).unwrap();
+        let mut restored: ErrorMemory = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.salt, None);
+        assert!(restored.wants_salt());
+        assert!(!restored.wants_salt());
+        restored.set_salt(2);
+        assert!(!restored.wants_salt());
+    }
+}
@@ -0,0 +1,216 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::events::form::{DateOfBirth, Email, FieldIdent, Form, OptionalText, Username};
+
+/// Key the draft is stored under in the shell's key-value store.
+pub const DRAFT_KEY: &str = "form_draft";
+
+/// Practical ceiling for a single value in mobile key-value stores.
+pub const DEFAULT_DRAFT_SIZE_LIMIT: usize = 8 * 1024;
+
+/// Field values persisted between sessions. A missing field is left
+/// untouched on restore, so a partially saved draft still restores.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Draft {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub age: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_of_birth: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub middle_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferred_name: Option<String>,
+}
+
+/// Fields left out of the last saved draft because it was too large.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DraftWarning {
+    pub skipped_fields: Vec<FieldIdent>,
+}
+
+impl Draft {
+    pub fn from_form(form: &Form) -> Self {
+        let non_empty = |value: String| (!value.is_empty()).then_some(value);
+        Self {
+            username: non_empty(form.username.value.to_string()),
+            email: non_empty(form.email.value.to_string()),
+            age: form.age.value.map(|age| age.to_string()),
+            address: non_empty(form.address.value.clone()),
+            date_of_birth: non_empty(form.date_of_birth.value.to_string()),
+            middle_name: non_empty(form.middle_name.value.to_string()),
+            preferred_name: non_empty(form.preferred_name.value.to_string()),
+        }
+    }
+
+    fn field_mut(&mut self, ident: FieldIdent) -> &mut Option<String> {
+        match ident {
+            FieldIdent::Username => &mut self.username,
+            FieldIdent::Email => &mut self.email,
+            FieldIdent::Age => &mut self.age,
+            FieldIdent::Address => &mut self.address,
+            FieldIdent::DateOfBirth => &mut self.date_of_birth,
+            FieldIdent::MiddleName => &mut self.middle_name,
+            FieldIdent::PreferredName => &mut self.preferred_name,
+        }
+    }
+
+    /// Serializes the draft, dropping the largest fields first until the
+    /// payload fits in `limit` bytes. Returns the payload and the dropped
+    /// fields.
+    pub fn encode_within(mut self, limit: usize) -> (String, Vec<FieldIdent>) {
+        let mut skipped = Vec::new();
+        loop {
+            let payload = serde_json::to_string(&self).expect("drafts are serializable");
+            if payload.len() <= limit {
+                return (payload, skipped);
+            }
+            let largest = FieldIdent::ALL
+                .into_iter()
+                .filter_map(|ident| {
+                    let len = self.field_mut(ident).as_ref()?.len();
+                    Some((ident, len))
+                })
+                .max_by_key(|(_, len)| *len);
+            let Some((ident, _)) = largest else {
+                return (payload, skipped);
+            };
+            *self.field_mut(ident) = None;
+            skipped.push(ident);
+        }
+    }
+
+    /// `None` if the payload is not a draft at all.
+    pub fn decode(payload: &str) -> Option<Self> {
+        serde_json::from_str(payload).ok()
+    }
+
+    pub fn apply_to(self, form: &mut Form) {
+        if let Some(username) = self.username {
+            form.username.set_value(Username(username));
+        }
+        if let Some(email) = self.email {
+            form.email.set_value(Email(email));
+        }
+        if let Some(age) = self.age {
+            form.age.set_value(age.trim().parse().ok());
+        }
+        if let Some(address) = self.address {
+            form.address.set_value(address);
+        }
+        if let Some(date_of_birth) = self.date_of_birth {
+            let date = NaiveDate::parse_from_str(date_of_birth.trim(), "%Y-%m-%d").ok();
+            form.date_of_birth.set_value(DateOfBirth(date));
+        }
+        if let Some(middle_name) = self.middle_name {
+            form.middle_name.set_value(OptionalText(middle_name));
+        }
+        if let Some(preferred_name) = self.preferred_name {
+            form.preferred_name.set_value(OptionalText(preferred_name));
+        }
+        form.validate_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Effect;
+    use crate::capabilities::storage::StorageOperation;
+    use crate::events::form::FormHandler;
+    use crate::events::signals::Signal;
+
+    /// The pending warning about the last saved draft, if any.
+    fn draft_warning(handler: &FormHandler) -> Option<&DraftWarning> {
+        handler
+            .signals()
+            .pending()
+            .iter()
+            .find_map(|raised| match &raised.signal {
+                Signal::DraftTruncated(warning) => Some(warning),
+                _ => None,
+            })
+    }
+
+    fn saved_payload(handler: &mut FormHandler, limit: usize) -> (String, bool) {
+        let mut cmd = handler.handle_save_draft(limit);
+        let mut payload = None;
+        let mut rendered = false;
+        for effect in cmd.effects() {
+            match effect {
+                Effect::Storage(request) => match request.operation {
+                    StorageOperation::Save { key, value } => {
+                        assert_eq!(key, DRAFT_KEY);
+                        payload = Some(value);
+                    }
+                    StorageOperation::Delete { .. } => panic!("unexpected delete"),
+                },
+                Effect::Render(_) => rendered = true,
+                _ => panic!("unexpected effect"),
+            }
+        }
+        (payload.expect("draft should be saved"), rendered)
+    }
+
+    #[test]
+    fn oversized_field_is_skipped_and_the_rest_restores() {
+        let mut handler = FormHandler::new();
+        let _ = handler.handle_update_value(FieldIdent::Username, "TestUser".to_string());
+        let _ = handler.handle_update_value(FieldIdent::Email, "test@example.com".to_string());
+        let _ = handler.handle_update_value(FieldIdent::Age, "42".to_string());
+        let _ = handler.handle_update_value(FieldIdent::Address, "x".repeat(10_000));
+
+        let (payload, rendered) = saved_payload(&mut handler, DEFAULT_DRAFT_SIZE_LIMIT);
+        assert!(rendered);
+        assert!(payload.len() <= DEFAULT_DRAFT_SIZE_LIMIT);
+        assert_eq!(
+            draft_warning(&handler),
+            Some(&DraftWarning {
+                skipped_fields: vec![FieldIdent::Address]
+            })
+        );
+
+        let mut restored = FormHandler::new();
+        let _ = restored.handle_restore_draft(payload);
+        let form = restored.get_form();
+        assert_eq!(form.username.value.0, "TestUser");
+        assert_eq!(form.email.value.0, "test@example.com");
+        assert_eq!(form.age.value, Some(42));
+        assert_eq!(form.address.value, "");
+        assert!(!form.address.valid);
+    }
+
+    #[test]
+    fn warning_clears_once_the_draft_fits() {
+        let mut handler = FormHandler::new();
+        let _ = handler.handle_update_value(FieldIdent::Address, "x".repeat(100));
+        let _ = saved_payload(&mut handler, 50);
+        assert!(draft_warning(&handler).is_some());
+
+        let _ = handler.handle_update_value(FieldIdent::Address, "10 Downing St".to_string());
+        let (payload, rendered) = saved_payload(&mut handler, 50);
+        assert!(rendered);
+        assert!(draft_warning(&handler).is_none());
+        assert_eq!(
+            Draft::decode(&payload).unwrap().address.as_deref(),
+            Some("10 Downing St")
+        );
+
+        let (_, rendered) = saved_payload(&mut handler, 50);
+        assert!(!rendered);
+    }
+
+    #[test]
+    fn unreadable_draft_is_ignored() {
+        let mut handler = FormHandler::new();
+        let mut cmd = handler.handle_restore_draft("not a draft".to_string());
+        assert!(cmd.effects().next().is_none());
+        assert_eq!(handler.get_form().username.value.0, "");
+    }
+}
@@ -0,0 +1,237 @@
+//! Fields declared at runtime, by key, next to the built-in ones. Each one
+//! holds text that must not be empty, plus the rules its spec lists.
+
+use indexmap::IndexMap;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::config::DynamicFieldSpec;
+use crate::descriptor::ValidatorSpec;
+use crate::events::form::{Field, FieldValidator, FieldViewModel, FormField, ToFieldViewModel};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DynamicFieldViewModel {
+    pub key: String,
+    pub field: FieldViewModel,
+}
+
+/// The runtime-declared fields, in declaration order.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(from = "SavedDynamicForm")]
+pub struct DynamicForm {
+    fields: IndexMap<String, Field<String>>,
+    /// What `from_specs` built the fields from. Rules are code and are not
+    /// serialized, so a deserialized form rebuilds them from these.
+    #[serde(default)]
+    specs: Vec<DynamicFieldSpec>,
+}
+
+#[derive(Deserialize)]
+struct SavedDynamicForm {
+    fields: IndexMap<String, Field<String>>,
+    #[serde(default)]
+    specs: Vec<DynamicFieldSpec>,
+}
+
+impl From<SavedDynamicForm> for DynamicForm {
+    fn from(saved: SavedDynamicForm) -> Self {
+        let SavedDynamicForm { mut fields, specs } = saved;
+        let mut built = Self::from_specs(&specs).unwrap_or_default();
+        for (key, field) in &mut fields {
+            if let Some(rules) = built.fields.get_mut(key) {
+                field.take_validators(rules);
+            }
+        }
+        Self { fields, specs }
+    }
+}
+
+impl DynamicForm {
+    /// One empty field per key, checked against its validators in order.
+    pub fn new(fields: impl IntoIterator<Item = (String, Vec<FieldValidator<String>>)>) -> Self {
+        let fields = fields
+            .into_iter()
+            .map(|(key, validators)| {
+                let mut field = Field::new(String::new(), true);
+                for validator in validators {
+                    field.add_validator(move |value: &String| validator(value));
+                }
+                field.revalidate();
+                (key, field)
+            })
+            .collect();
+        Self {
+            fields,
+            specs: Vec::new(),
+        }
+    }
+
+    /// Builds the fields `specs` declare, or returns the index of the first
+    /// spec with an empty or repeated key or a rule text cannot follow.
+    pub fn from_specs(specs: &[DynamicFieldSpec]) -> Result<Self, usize> {
+        let mut fields = Vec::with_capacity(specs.len());
+        for (index, spec) in specs.iter().enumerate() {
+            let repeated = specs[..index].iter().any(|other| other.key == spec.key);
+            if spec.key.is_empty() || repeated {
+                return Err(index);
+            }
+            let validators = spec
+                .validators
+                .iter()
+                .map(text_validator)
+                .collect::<Option<Vec<_>>>()
+                .ok_or(index)?;
+            fields.push((spec.key.clone(), validators));
+        }
+        Ok(Self {
+            specs: specs.to_vec(),
+            ..Self::new(fields)
+        })
+    }
+
+    /// Empties every field, keeping its rules.
+    pub fn reset(&mut self) {
+        for field in self.fields.values_mut() {
+            let mut fresh = Field::new(String::new(), true);
+            fresh.take_validators(field);
+            fresh.revalidate();
+            *field = fresh;
+        }
+    }
+
+    /// Sets and validates the value of `key`. Returns whether there is such
+    /// a field.
+    pub fn update_value(&mut self, key: &str, value: String) -> bool {
+        let Some(field) = self.fields.get_mut(key) else {
+            return false;
+        };
+        field.set_value(value);
+        field.mark_touched();
+        true
+    }
+
+    pub fn validate_all(&mut self) {
+        for field in self.fields.values_mut() {
+            field.revalidate();
+        }
+    }
+
+    pub fn touch_all(&mut self) {
+        for field in self.fields.values_mut() {
+            field.mark_touched();
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.fields.values().all(|field| field.is_valid())
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.fields.values().any(|field| field.is_dirty())
+    }
+
+    /// Each field's value by key, in declaration order.
+    pub fn values(&self) -> IndexMap<String, String> {
+        self.fields
+            .iter()
+            .map(|(key, field)| (key.clone(), field.value.clone()))
+            .collect()
+    }
+
+    pub fn field(&self, key: &str) -> Option<&Field<String>> {
+        self.fields.get(key)
+    }
+
+    pub fn view(&self) -> Vec<DynamicFieldViewModel> {
+        self.fields
+            .iter()
+            .map(|(key, field)| DynamicFieldViewModel {
+                key: key.clone(),
+                field: field.to_field_view_model(),
+            })
+            .collect()
+    }
+}
+
+/// `spec` as a rule on text, or `None` for the rules that only make sense
+/// on a built-in field.
+fn text_validator(spec: &ValidatorSpec) -> Option<FieldValidator<String>> {
+    let validator: FieldValidator<String> = match spec.clone() {
+        ValidatorSpec::MinLength { min } => Arc::new(move |value: &String| {
+            (value.len() >= min)
+                .then_some(())
+                .ok_or_else(|| format!("Must be at least {min} characters"))
+        }),
+        ValidatorSpec::Pattern { pattern } => {
+            let pattern = Regex::new(&pattern).ok()?;
+            Arc::new(move |value: &String| {
+                pattern
+                    .is_match(value)
+                    .then_some(())
+                    .ok_or_else(|| "Please use the expected format".to_string())
+            })
+        }
+        ValidatorSpec::Range { min, max } => Arc::new(move |value: &String| {
+            value
+                .parse::<u32>()
+                .ok()
+                .filter(|number| (min..=max).contains(number))
+                .map(|_| ())
+                .ok_or_else(|| format!("Must be a whole number from {min} to {max}"))
+        }),
+        ValidatorSpec::MinimumAge { .. } | ValidatorSpec::UsernamePolicy(_) => return None,
+    };
+    Some(validator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(key: &str, validators: Vec<ValidatorSpec>) -> DynamicFieldSpec {
+        DynamicFieldSpec {
+            key: key.to_string(),
+            validators,
+        }
+    }
+
+    #[test]
+    fn test_fields_follow_their_specs_in_order() {
+        let mut form = DynamicForm::from_specs(&[
+            spec("nickname", vec![ValidatorSpec::MinLength { min: 3 }]),
+            spec("floor", vec![ValidatorSpec::Range { min: 0, max: 99 }]),
+        ])
+        .unwrap();
+        assert!(!form.is_valid());
+        let keys: Vec<String> = form.view().into_iter().map(|field| field.key).collect();
+        assert_eq!(keys, vec!["nickname", "floor"]);
+
+        assert!(form.update_value("nickname", "Al".to_string()));
+        assert_eq!(
+            form.field("nickname").unwrap().error.as_deref(),
+            Some("Must be at least 3 characters")
+        );
+        assert!(form.update_value("nickname", "Alex".to_string()));
+        assert!(form.update_value("floor", "12".to_string()));
+        assert!(form.is_valid());
+
+        assert!(!form.update_value("unknown", "x".to_string()));
+    }
+
+    #[test]
+    fn test_bad_specs_are_reported_by_index() {
+        let minimum_age = ValidatorSpec::MinimumAge { years: 18 };
+        let bad_pattern = ValidatorSpec::Pattern {
+            pattern: "(".to_string(),
+        };
+        for (specs, index) in [
+            (vec![spec("a", vec![]), spec("", vec![])], 1),
+            (vec![spec("a", vec![]), spec("a", vec![])], 1),
+            (vec![spec("a", vec![minimum_age])], 0),
+            (vec![spec("a", vec![]), spec("b", vec![bad_pattern])], 1),
+        ] {
+            assert_eq!(DynamicForm::from_specs(&specs).err(), Some(index));
+        }
+    }
+}
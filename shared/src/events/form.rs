@@ -1,21 +1,205 @@
+use chrono::NaiveDate;
 use crux_core::{render::render, Command};
+use crux_http::command::Http;
+use crux_http::HttpError;
+use indexmap::IndexMap;
+use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::Arc;
 
 use crate::app::{Effect, Event};
+use crate::capabilities::analytics::AnalyticsOperation;
+use crate::capabilities::storage::StorageOperation;
+use crate::capabilities::timer::{TimerKind, TimerOperation};
+use crate::capabilities::validate::{AsyncValidateOperation, AsyncVerdict};
+use crate::config::RemoteCheck;
+use crate::descriptor::{FieldKind, ValidatorSpec};
+use crate::events::draft::{Draft, DraftWarning, DRAFT_KEY};
+use crate::events::dynamic::DynamicForm;
+use crate::events::history::FormInputs;
+use crate::events::signals::{Signal, SignalQueue};
+use crate::events::snapshot::FormSnapshot;
+use crate::events::validators;
+use crate::pending::{self, OperationKind};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Copy)]
-pub enum FieldIdent {
+/// Declares `FieldIdent` and `FieldIdent::ALL` from one list, so a new
+/// field cannot be left out of the loops over `ALL`.
+macro_rules! field_idents {
+    ($($variant:ident),+ $(,)?) => {
+        /// Matches on a field must name every variant rather than use a
+        /// wildcard, so that adding a field fails to compile until each
+        /// per-field dispatch handles it.
+        #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Copy)]
+        pub enum FieldIdent {
+            $($variant),+
+        }
+
+        impl FieldIdent {
+            pub const ALL: [FieldIdent; [$(stringify!($variant)),+].len()] =
+                [$(FieldIdent::$variant),+];
+        }
+    };
+}
+
+field_idents!(
     Username,
     Email,
     Age,
     Address,
+    DateOfBirth,
+    MiddleName,
+    PreferredName,
+);
+
+impl FieldIdent {
+    pub fn label(self) -> &'static str {
+        match self {
+            FieldIdent::Username => "Username",
+            FieldIdent::Email => "Email",
+            FieldIdent::Age => "Age",
+            FieldIdent::Address => "Address",
+            FieldIdent::DateOfBirth => "Date of birth",
+            FieldIdent::MiddleName => "Middle name",
+            FieldIdent::PreferredName => "Preferred name",
+        }
+    }
+
+    pub fn kind(self) -> FieldKind {
+        match self {
+            FieldIdent::Username => Username::kind(),
+            FieldIdent::Email => Email::kind(),
+            FieldIdent::Age => <Option<u32>>::kind(),
+            FieldIdent::Address => String::kind(),
+            FieldIdent::DateOfBirth => DateOfBirth::kind(),
+            FieldIdent::MiddleName | FieldIdent::PreferredName => OptionalText::kind(),
+        }
+    }
+
+    /// The field's own rules plus those `Form` applies to it in context.
+    pub fn validators(self, policy: UsernamePolicy) -> Vec<ValidatorSpec> {
+        match self {
+            FieldIdent::Username => {
+                let mut validators = Username::validators();
+                validators.push(ValidatorSpec::UsernamePolicy(policy));
+                validators
+            }
+            FieldIdent::Email => Email::validators(),
+            FieldIdent::Age => <Option<u32>>::validators(),
+            FieldIdent::Address => String::validators(),
+            FieldIdent::DateOfBirth => {
+                let mut validators = DateOfBirth::validators();
+                validators.push(ValidatorSpec::MinimumAge { years: MINIMUM_AGE });
+                validators
+            }
+            FieldIdent::MiddleName | FieldIdent::PreferredName => OptionalText::validators(),
+        }
+    }
+}
+
+/// How usernames are checked and canonicalized; chosen through `AppConfig`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UsernamePolicy {
+    /// Display names such as "Jane D"; runs of whitespace collapse on commit
+    #[default]
+    Display,
+    /// Handles: letters, digits and underscores, lowercased on commit
+    Handle,
+}
+
+impl UsernamePolicy {
+    /// The value a username is normalized to when the user leaves the field.
+    pub fn canonicalize(self, username: &str) -> String {
+        match self {
+            UsernamePolicy::Display => username.split_whitespace().collect::<Vec<_>>().join(" "),
+            UsernamePolicy::Handle => username.trim().to_lowercase(),
+        }
+    }
+
+    fn check(self, username: &str) -> Option<String> {
+        match self {
+            UsernamePolicy::Display => None,
+            UsernamePolicy::Handle if username.chars().any(char::is_whitespace) => {
+                Some(USERNAME_HAS_SPACES_MESSAGE.to_string())
+            }
+            UsernamePolicy::Handle
+                if !username
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+            {
+                Some(USERNAME_HAS_SYMBOLS_MESSAGE.to_string())
+            }
+            UsernamePolicy::Handle => None,
+        }
+    }
+}
+
+pub const USERNAME_HAS_SPACES_MESSAGE: &str = "Username cannot contain spaces";
+pub const USERNAME_HAS_SYMBOLS_MESSAGE: &str =
+    "Username may only contain letters, numbers and underscores";
+
+/// Minimum age enforced by both the age and the date-of-birth fields.
+const MINIMUM_AGE: u32 = 18;
+const MAXIMUM_AGE: u32 = 120;
+const MIN_USERNAME_LENGTH: usize = 3;
+const EMAIL_PATTERN: &str = r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$";
+const MIN_PASSWORD_LENGTH: usize = 8;
+/// Characters a password must contain, each with the error for lacking them.
+const PASSWORD_RULES: [(&str, &str); 3] = [
+    ("[0-9]", "Password must contain a number"),
+    ("[A-Z]", "Password must contain an uppercase letter"),
+    ("[a-z]", "Password must contain a lowercase letter"),
+];
+
+lazy_static! {
+    // Validation runs on every keystroke, so the patterns are compiled once
+    static ref EMAIL_REGEX: Regex = Regex::new(EMAIL_PATTERN).expect("EMAIL_PATTERN is valid");
+    static ref PASSWORD_REGEXES: Vec<Regex> = PASSWORD_RULES
+        .iter()
+        .map(|(pattern, _)| Regex::new(pattern).expect("PASSWORD_RULES are valid"))
+        .collect();
+    static ref USERNAME_RULE: FieldValidator<String> = validators::and(
+        Arc::new(|name: &String| {
+            (!name.is_empty())
+                .then_some(())
+                .ok_or_else(|| "Username cannot be empty".to_string())
+        }),
+        Arc::new(|name: &String| {
+            (name.len() >= MIN_USERNAME_LENGTH)
+                .then_some(())
+                .ok_or_else(|| format!("Username must be at least {MIN_USERNAME_LENGTH} characters"))
+        }),
+    );
 }
 
 pub trait Validatable {
     fn is_valid(&self) -> bool;
     fn error_message(&self) -> Option<String>;
+    /// Every rule the value breaks, for values that report them all at
+    /// once. The first is `error_message`.
+    fn error_messages(&self) -> Vec<String> {
+        self.error_message().into_iter().collect()
+    }
+    fn kind() -> FieldKind
+    where
+        Self: Sized;
+    /// The rules `is_valid` applies, for `FormDescriptor`.
+    fn validators() -> Vec<ValidatorSpec>
+    where
+        Self: Sized;
+}
+
+/// A check a field's value waits on. A field may wait on both kinds at
+/// once, and each verdict only settles its own.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PendingCheck {
+    /// `AsyncValidateOperation`, answered by the shell
+    Shell,
+    /// A `RemoteCheck`, answered over HTTP
+    Remote,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -27,6 +211,69 @@ pub struct Field<T: Clone + PartialEq + Validatable> {
     pub error: Option<String>,
     pub valid: bool,
     pub editing: bool,
+    /// Whether the form expects a value; shells mark these fields.
+    #[serde(default)]
+    pub required: bool,
+    /// Exact text the user typed for fields whose value is parsed (e.g. age),
+    /// echoed back while the field is being edited and dropped on blur.
+    #[serde(default)]
+    pub raw_input: Option<String>,
+    /// Error text currently shown to the user. Lags behind `error` while
+    /// `error_debounce_ms` is set and the user is still typing.
+    #[serde(default)]
+    pub display_error: Option<String>,
+    #[serde(default)]
+    pub error_debounce_ms: Option<u64>,
+    #[serde(default)]
+    pub error_generation: u64,
+    /// Error reported by the server for the current value; cleared as soon
+    /// as the value changes.
+    #[serde(default)]
+    pub server_error: Option<String>,
+    /// Whether `valid` and `error` reflect the current value; cleared
+    /// whenever the value changes.
+    #[serde(default)]
+    pub validated: bool,
+    /// Shown masked, like a password, until the user reveals it
+    #[serde(default)]
+    pub masked: bool,
+    /// Value before the last change, restored by `undo`
+    #[serde(default = "Option::default")]
+    pub previous_value: Option<T>,
+    /// Checks of the current value in flight
+    #[serde(default)]
+    pub pending_checks: BTreeSet<PendingCheck>,
+    /// Every rule the value broke when last validated: its own, then those
+    /// added with `add_validator`. `error` is the first, unless a server or
+    /// form-level error replaced it.
+    #[serde(default)]
+    pub errors: Vec<String>,
+    /// Rules added with `add_validator`; code, so not serialized
+    #[serde(skip, default = "FieldValidators::default")]
+    validators: FieldValidators<T>,
+}
+
+/// A rule added to a single field; `Err` holds its message.
+pub type FieldValidator<T> = Arc<dyn Fn(&T) -> Result<(), String> + Send + Sync>;
+
+struct FieldValidators<T>(Vec<FieldValidator<T>>);
+
+impl<T> Default for FieldValidators<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T> Clone for FieldValidators<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for FieldValidators<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FieldValidators({})", self.0.len())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -35,6 +282,19 @@ pub struct Username(pub String);
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Email(pub String);
 
+/// Optional date of birth. Whether it satisfies the minimum age depends on
+/// the current date, so that rule lives on `Form` rather than here.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DateOfBirth(pub Option<NaiveDate>);
+
+/// Free text with no rules: never invalid, never blocks submit.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct OptionalText(pub String);
+
+/// A new password. Reports every requirement it misses, not just the first.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PasswordStrength(pub String);
+
 impl From<&str> for Username {
     fn from(s: &str) -> Self {
         Username(s.to_string())
@@ -49,24 +309,27 @@ impl From<&str> for Email {
 
 impl Validatable for Username {
     fn is_valid(&self) -> bool {
-        self.0.len() >= 3
+        USERNAME_RULE(&self.0).is_ok()
     }
 
     fn error_message(&self) -> Option<String> {
-        if self.0.is_empty() {
-            Some("Username cannot be empty".to_string())
-        } else if self.0.len() < 3 {
-            Some("Username must be at least 3 characters".to_string())
-        } else {
-            None
-        }
+        USERNAME_RULE(&self.0).err()
+    }
+
+    fn kind() -> FieldKind {
+        FieldKind::Text
+    }
+
+    fn validators() -> Vec<ValidatorSpec> {
+        vec![ValidatorSpec::MinLength {
+            min: MIN_USERNAME_LENGTH,
+        }]
     }
 }
 
 impl Validatable for Email {
     fn is_valid(&self) -> bool {
-        let email_regex = Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap();
-        !self.0.is_empty() && email_regex.is_match(&self.0)
+        !self.0.is_empty() && EMAIL_REGEX.is_match(&self.0)
     }
 
     fn error_message(&self) -> Option<String> {
@@ -78,6 +341,16 @@ impl Validatable for Email {
             None
         }
     }
+
+    fn kind() -> FieldKind {
+        FieldKind::Email
+    }
+
+    fn validators() -> Vec<ValidatorSpec> {
+        vec![ValidatorSpec::Pattern {
+            pattern: EMAIL_PATTERN.to_string(),
+        }]
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -86,10 +359,123 @@ pub struct Form {
     pub email: Field<Email>,
     pub age: Field<Option<u32>>,
     pub address: Field<String>,
+    #[serde(default = "date_of_birth_default")]
+    pub date_of_birth: Field<DateOfBirth>,
+    #[serde(default = "optional_text_default")]
+    pub middle_name: Field<OptionalText>,
+    #[serde(default = "optional_text_default")]
+    pub preferred_name: Field<OptionalText>,
     pub submitted: bool,
     pub is_editing: bool,
+    /// A submit is waiting on the email check; further submits are ignored
+    #[serde(default)]
+    pub submitting: bool,
+    /// Set by `Event::Abandon`; everything but a reset is ignored after it.
+    #[serde(default)]
+    pub abandoned: bool,
+    /// Today's date as last reported by the shell via `Event::Tick`.
+    #[serde(default)]
+    pub today: Option<NaiveDate>,
+    #[serde(default)]
+    pub username_policy: UsernamePolicy,
+    /// When false, edits are only validated on submit.
+    #[serde(default = "validate_on_change_default")]
+    pub validate_on_change: bool,
+    /// Fields whose errors are listed first, as configured by the shell
+    #[serde(default)]
+    pub error_display_order: Vec<FieldIdent>,
+    /// Characters that do not count when comparing usernames
+    #[serde(default = "username_ignored_characters_default")]
+    pub username_ignored_characters: String,
+    /// Longest address accepted, in characters
+    #[serde(default = "max_address_length_default")]
+    pub max_address_length: usize,
+    /// Rules that compare fields; code, so not serialized
+    #[serde(skip)]
+    pub(crate) cross_validators: CrossValidators,
+    /// Fields whose error came from a cross validator
+    #[serde(default)]
+    pub(crate) cross_failed: Vec<FieldIdent>,
+    /// The values of the last accepted submit, as `input` gives them; empty
+    /// until the first
+    #[serde(default)]
+    pub(crate) submitted_inputs: Vec<(FieldIdent, String)>,
+    /// The fields declared by `AppConfig::dynamic_fields`, which count
+    /// toward validity, status and the submission like the others
+    #[serde(default)]
+    pub dynamic: DynamicForm,
+}
+
+/// A rule over several fields: the field to mark invalid and why, or `None`
+/// if the form passes. It should only fire once the fields it compares are
+/// valid on their own.
+pub type FormValidator = Arc<dyn Fn(&Form) -> Option<(FieldIdent, String)> + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub(crate) struct CrossValidators(Vec<FormValidator>);
+
+impl fmt::Debug for CrossValidators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CrossValidators({})", self.0.len())
+    }
+}
+
+pub const ADDRESS_CONTAINS_USERNAME_MESSAGE: &str = "Address must not contain your username";
+
+/// An example cross validator: the address must not contain the username.
+pub fn address_without_username() -> FormValidator {
+    Arc::new(|form: &Form| {
+        let username = form.username.value.0.trim().to_lowercase();
+        let contains =
+            !username.is_empty() && form.address.value.to_lowercase().contains(&username);
+        (form.username.valid && form.address.valid && contains).then(|| {
+            (
+                FieldIdent::Address,
+                ADDRESS_CONTAINS_USERNAME_MESSAGE.to_string(),
+            )
+        })
+    })
+}
+
+fn validate_on_change_default() -> bool {
+    true
+}
+
+/// The leading digits of `input`, so "25a" reads as 25; the raw text is
+/// echoed until the field is left, and then shows as the parsed age.
+fn parse_age(input: &str) -> Option<u32> {
+    let input = input.trim_start();
+    let digits = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    input[..digits].parse().ok()
+}
+
+fn date_of_birth_default() -> Field<DateOfBirth> {
+    Form::default().date_of_birth
+}
+
+fn optional_text_default() -> Field<OptionalText> {
+    Field::new(OptionalText::default(), false)
+}
+
+/// Ignored when comparing usernames unless configured otherwise, so that
+/// "john.doe" and "johndoe" are the same user.
+pub const DEFAULT_USERNAME_IGNORED_CHARACTERS: &str = ".";
+
+pub fn username_ignored_characters_default() -> String {
+    DEFAULT_USERNAME_IGNORED_CHARACTERS.to_string()
 }
 
+/// Room for any real postal address, suggestions included.
+pub const DEFAULT_MAX_ADDRESS_LENGTH: usize = 250;
+
+pub fn max_address_length_default() -> usize {
+    DEFAULT_MAX_ADDRESS_LENGTH
+}
+
+pub const ADDRESS_TOO_LONG_MESSAGE: &str = "Address is too long";
+
 impl Default for Form {
     fn default() -> Self {
         Self {
@@ -101,6 +487,18 @@ impl Default for Form {
                 error: Some("Username cannot be empty".to_string()),
                 valid: false,
                 editing: false,
+                required: true,
+                raw_input: None,
+                display_error: Some("Username cannot be empty".to_string()),
+                error_debounce_ms: None,
+                error_generation: 0,
+                server_error: None,
+                validated: false,
+                masked: false,
+                previous_value: None,
+                pending_checks: BTreeSet::new(),
+                errors: Vec::new(),
+                validators: FieldValidators::default(),
             },
             email: Field {
                 value: Email(String::new()),
@@ -110,6 +508,18 @@ impl Default for Form {
                 error: Some("Email cannot be empty".to_string()),
                 valid: false,
                 editing: false,
+                required: true,
+                raw_input: None,
+                display_error: Some("Email cannot be empty".to_string()),
+                error_debounce_ms: None,
+                error_generation: 0,
+                server_error: None,
+                validated: false,
+                masked: false,
+                previous_value: None,
+                pending_checks: BTreeSet::new(),
+                errors: Vec::new(),
+                validators: FieldValidators::default(),
             },
             age: Field {
                 value: None,
@@ -119,6 +529,18 @@ impl Default for Form {
                 error: None,
                 valid: true,
                 editing: false,
+                required: false,
+                raw_input: None,
+                display_error: None,
+                error_debounce_ms: None,
+                error_generation: 0,
+                server_error: None,
+                validated: false,
+                masked: false,
+                previous_value: None,
+                pending_checks: BTreeSet::new(),
+                errors: Vec::new(),
+                validators: FieldValidators::default(),
             },
             address: Field {
                 value: String::new(),
@@ -128,34 +550,172 @@ impl Default for Form {
                 error: Some("Address cannot be empty".to_string()),
                 valid: false,
                 editing: false,
+                required: true,
+                raw_input: None,
+                display_error: Some("Address cannot be empty".to_string()),
+                error_debounce_ms: None,
+                error_generation: 0,
+                server_error: None,
+                validated: false,
+                masked: false,
+                previous_value: None,
+                pending_checks: BTreeSet::new(),
+                errors: Vec::new(),
+                validators: FieldValidators::default(),
+            },
+            date_of_birth: Field {
+                value: DateOfBirth(None),
+                initial_value: DateOfBirth(None),
+                touched: false,
+                dirty: false,
+                error: None,
+                valid: true,
+                editing: false,
+                required: false,
+                raw_input: None,
+                display_error: None,
+                error_debounce_ms: None,
+                error_generation: 0,
+                server_error: None,
+                validated: false,
+                masked: false,
+                previous_value: None,
+                pending_checks: BTreeSet::new(),
+                errors: Vec::new(),
+                validators: FieldValidators::default(),
             },
+            middle_name: optional_text_default(),
+            preferred_name: optional_text_default(),
             submitted: false,
             is_editing: true,
+            submitting: false,
+            abandoned: false,
+            today: None,
+            username_policy: UsernamePolicy::default(),
+            validate_on_change: true,
+            error_display_order: Vec::new(),
+            username_ignored_characters: username_ignored_characters_default(),
+            max_address_length: DEFAULT_MAX_ADDRESS_LENGTH,
+            cross_validators: CrossValidators::default(),
+            cross_failed: Vec::new(),
+            submitted_inputs: Vec::new(),
+            dynamic: DynamicForm::default(),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// A subset of canonical field values, e.g. from a server enrichment
+/// response. `None` leaves the corresponding field untouched.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PartialSubmission {
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub age: Option<Option<u32>>,
+    pub address: Option<String>,
+    #[serde(default)]
+    pub middle_name: Option<String>,
+    #[serde(default)]
+    pub preferred_name: Option<String>,
+}
+
+/// A field rejected by the server, e.g. an email address already in use.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ServerFieldError {
+    pub ident: FieldIdent,
+    pub message: String,
+}
+
+/// An error the user can currently see, for the summary above the form.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FieldError {
+    pub ident: FieldIdent,
+    pub message: String,
+    /// From `error_code`
+    pub code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct FieldViewModel {
     pub value: String,
     pub initial_value: String,
     pub touched: bool,
     pub dirty: bool,
     pub error: Option<String>,
+    /// Code of `error`, for shells that match on it rather than the text
+    pub error_code: Option<String>,
+    /// Every rule the value breaks when `error` is one of them; otherwise
+    /// just `error`
+    pub errors: Vec<String>,
+    pub display_error: Option<String>,
     pub valid: bool,
     pub editing: bool,
+    pub required: bool,
+    pub masked: bool,
+    /// Whether `UndoField` would restore an earlier value
+    pub undoable: bool,
+    /// Waiting on a remote check, e.g. to show a spinner
+    pub validating: bool,
+    /// An error set with `SetFieldError` stands until the value changes
+    pub has_server_error: bool,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct FormViewModel {
     pub username: FieldViewModel,
     pub email: FieldViewModel,
     pub age: FieldViewModel,
     pub address: FieldViewModel,
+    pub date_of_birth: FieldViewModel,
+    pub middle_name: FieldViewModel,
+    pub preferred_name: FieldViewModel,
     pub submitted: bool,
     pub is_editing_form: bool,
+    pub abandoned: bool,
+    pub status: FormStatus,
     pub status_message: String,
     pub can_submit: bool,
+    pub username_policy: UsernamePolicy,
+    /// Displayed errors in `Form::error_order`
+    pub error_summary: Vec<FieldError>,
+    /// Field to focus: the first one in the summary
+    pub focus_hint: Option<FieldIdent>,
+}
+
+impl FormViewModel {
+    pub fn field(&self, ident: FieldIdent) -> &FieldViewModel {
+        match ident {
+            FieldIdent::Username => &self.username,
+            FieldIdent::Email => &self.email,
+            FieldIdent::Age => &self.age,
+            FieldIdent::Address => &self.address,
+            FieldIdent::DateOfBirth => &self.date_of_birth,
+            FieldIdent::MiddleName => &self.middle_name,
+            FieldIdent::PreferredName => &self.preferred_name,
+        }
+    }
+}
+
+/// What the status banner reports, from `Form::status`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormStatus {
+    Submitted,
+    /// Locked without a successful submission, e.g. once abandoned
+    ViewOnly,
+    UnsavedChanges,
+    Invalid,
+    Ready,
+}
+
+impl FormStatus {
+    pub fn message(self) -> &'static str {
+        match self {
+            FormStatus::Submitted => "Form Submitted Successfully!",
+            FormStatus::ViewOnly => "Form data (View only)",
+            FormStatus::UnsavedChanges => "Form has unsaved changes",
+            FormStatus::Invalid => "Please correct the errors.",
+            FormStatus::Ready => "Please fill out the form.",
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -171,12 +731,170 @@ pub enum FormEvent {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct FormHandler {
     form: Form,
+    /// One-shot instructions for the shell, until it acknowledges them
+    #[serde(default)]
+    signals: SignalQueue,
+    /// Where to confirm the email is free before submitting, if anywhere
+    #[serde(default)]
+    email_check_url: Option<String>,
+    /// A value the core chose that is on its way back as an `UpdateValue`
+    #[serde(default)]
+    core_value: Option<(FieldIdent, String)>,
+    /// Pause in address typing before suggestions are fetched; 0 fetches
+    /// on every edit
+    #[serde(default = "suggestion_debounce_default")]
+    suggestion_debounce_ms: u64,
+    /// Fewest characters, ignoring surrounding whitespace, worth searching
+    /// suggestions for
+    #[serde(default = "min_suggestion_query_len_default")]
+    min_suggestion_query_len: usize,
+    /// Counts address edits, so only the last one's pause fetches
+    #[serde(default)]
+    address_generation: u64,
+    /// Where a valid form is POSTed before it counts as submitted, if
+    /// anywhere
+    #[serde(default)]
+    submit_url: Option<String>,
+    /// Why the server did not take the last submission; shown instead of
+    /// the status until the next edit or submit
+    #[serde(default)]
+    submit_error: Option<String>,
+    #[serde(default)]
+    remote_checks: Vec<RemoteCheck>,
+    /// Numbers the remote checks, so only a field's latest one is applied
+    #[serde(default)]
+    remote_generation: u64,
+    /// The latest remote check of each field, waiting for a pause in
+    /// typing or in flight
+    #[serde(default)]
+    remote_pending: BTreeMap<FieldIdent, u64>,
+    /// Fields the shell checks after each edit
+    #[serde(default)]
+    async_validated: Vec<FieldIdent>,
+}
+
+/// Long enough to skip most keystrokes of a fast typist.
+pub const DEFAULT_SUGGESTION_DEBOUNCE_MS: u64 = 250;
+
+pub fn suggestion_debounce_default() -> u64 {
+    DEFAULT_SUGGESTION_DEBOUNCE_MS
+}
+
+/// A remote check costs a round trip, so it waits a little longer than a
+/// suggestion fetch.
+pub const DEFAULT_REMOTE_CHECK_DEBOUNCE_MS: u64 = 400;
+
+pub fn remote_check_debounce_default() -> u64 {
+    DEFAULT_REMOTE_CHECK_DEBOUNCE_MS
+}
+
+/// Shorter queries match too many addresses to be useful.
+pub const DEFAULT_MIN_SUGGESTION_QUERY_LEN: usize = 3;
+
+pub fn min_suggestion_query_len_default() -> usize {
+    DEFAULT_MIN_SUGGESTION_QUERY_LEN
+}
+
+/// Whether `query` is long enough to search suggestions for.
+pub fn is_suggestion_query(query: &str, min_len: usize) -> bool {
+    let query = query.trim();
+    !query.is_empty() && query.chars().count() >= min_len
+}
+
+/// Query of an email availability check.
+#[derive(Serialize)]
+struct EmailCheckQuery {
+    check: String,
+}
+
+#[derive(Deserialize)]
+struct EmailAvailability {
+    available: bool,
+}
+
+/// The form as POSTed, as JSON, to the configured `submit_url`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FormSubmission {
+    pub username: String,
+    pub email: String,
+    pub age: Option<u32>,
+    pub address: String,
+    /// Values of the dynamic fields by key, in declaration order; left out
+    /// when none are configured
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub dynamic: IndexMap<String, String>,
+}
+
+/// How the server answered a submission.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum SubmitResult {
+    Accepted,
+    /// `message` is shown as the form's status
+    Failed {
+        message: String,
+    },
+}
+
+/// Query of a remote check.
+#[derive(Serialize)]
+struct RemoteCheckQuery {
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct RemoteVerdict {
+    valid: bool,
+    message: Option<String>,
+}
+
+/// Shown when a remote check rejects a value without saying why.
+pub const REMOTE_CHECK_FAILED_MESSAGE: &str = "This value is not available";
+
+pub const SUBMIT_UNREACHABLE_MESSAGE: &str = "Could not reach the server. Please try again.";
+
+pub const EMAIL_TAKEN_MESSAGE: &str = "Email is already registered";
+pub const AGE_REQUIRED_MESSAGE: &str = "Age is required";
+pub const AGE_NOT_A_NUMBER_MESSAGE: &str = "Age must be a number";
+pub const DATE_OF_BIRTH_NOT_A_DATE_MESSAGE: &str = "Date of birth must be a date (YYYY-MM-DD)";
+
+/// Codes of the errors shells may match on, which keep their meaning when
+/// the wording changes.
+const ERROR_CODES: [(&str, &str); 3] = [
+    (USERNAME_HAS_SPACES_MESSAGE, "username.spaces"),
+    (USERNAME_HAS_SYMBOLS_MESSAGE, "username.symbols"),
+    (AGE_REQUIRED_MESSAGE, "age.required"),
+];
+
+/// The code of an error `message`, if it has one.
+pub fn error_code(message: &str) -> Option<&'static str> {
+    ERROR_CODES
+        .iter()
+        .find(|(known, _)| *known == message)
+        .map(|(_, code)| *code)
+}
+
+impl Default for FormHandler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FormHandler {
     pub fn new() -> Self {
         Self {
             form: Form::default(),
+            signals: SignalQueue::default(),
+            email_check_url: None,
+            core_value: None,
+            suggestion_debounce_ms: DEFAULT_SUGGESTION_DEBOUNCE_MS,
+            min_suggestion_query_len: DEFAULT_MIN_SUGGESTION_QUERY_LEN,
+            address_generation: 0,
+            submit_url: None,
+            submit_error: None,
+            remote_checks: Vec::new(),
+            remote_generation: 0,
+            remote_pending: BTreeMap::new(),
+            async_validated: Vec::new(),
         }
     }
 
@@ -184,45 +902,151 @@ impl FormHandler {
         &mut self,
         ident: FieldIdent,
         value: String,
+    ) -> Command<Effect, Event> {
+        self.handle_set_value(ident, value, true)
+    }
+
+    /// Updates a field as typing does. Suggestions for the address are only
+    /// fetched if `fetch` is set.
+    pub fn handle_set_value(
+        &mut self,
+        ident: FieldIdent,
+        value: String,
+        fetch: bool,
     ) -> Command<Effect, Event> {
         if !self.form.is_editing {
             return Command::done();
         }
 
-        match ident {
-            FieldIdent::Username => {
-                self.form.username.set_value(value.as_str().into());
-            }
-            FieldIdent::Email => {
-                self.form.email.set_value(value.as_str().into());
-            }
-            FieldIdent::Age => {
-                let age = value.parse::<u32>().ok();
-                self.form.age.set_value(age);
+        self.submit_error = None;
+        let query = (fetch && ident == FieldIdent::Address).then(|| value.clone());
+        let from_core = self.core_value.take() == Some((ident, value.clone()));
+        self.withdraw_carets();
+        self.form.set_input(ident, value);
+        if from_core {
+            self.raise_caret_at_end(ident);
+        }
+
+        // With validation deferred, errors stay as the last submit left them
+        let debounce = if self.form.validate_on_change {
+            self.form.validate_all();
+            self.debounce_error(ident)
+                .and(self.validate_async(ident))
+                .and(self.check_remote_on_edit(ident))
+        } else {
+            Command::done()
+        };
+        match query {
+            Some(query) if is_suggestion_query(&query, self.min_suggestion_query_len) => {
+                self.debounce_suggestions(query).and(render()).and(debounce)
             }
-            FieldIdent::Address => {
-                self.form.address.set_value(value.clone());
-                return Command::event(Event::FetchSuggestions { query: value }).then(render());
+            // Too short to search: a pause still pending would fetch nothing,
+            // and suggestions for a longer query no longer apply
+            Some(_) => {
+                self.cancel_address_pause();
+                Command::event(Event::ClearSuggestions).and(debounce)
             }
+            None => render().and(debounce),
         }
+    }
 
-        self.form.validate_all();
+    /// Keeps a typing pause still pending from fetching suggestions.
+    pub fn cancel_address_pause(&mut self) {
+        self.address_generation += 1;
+    }
+
+    /// Fetches suggestions for `query` once the user has paused typing.
+    fn debounce_suggestions(&mut self, query: String) -> Command<Effect, Event> {
+        if self.suggestion_debounce_ms == 0 {
+            return Command::event(Event::FetchSuggestions {
+                ident: FieldIdent::Address,
+                query,
+            });
+        }
+        self.address_generation += 1;
+        let generation = self.address_generation;
+        Command::request_from_shell(TimerOperation::Start {
+            id: TimerKind::SuggestionDebounce.id(generation),
+            millis: self.suggestion_debounce_ms,
+        })
+        .then_send(move |_| Event::AddressDebounceElapsed { generation })
+    }
+
+    /// Fetches suggestions for the address as it is now, unless it was
+    /// edited again after edit `generation`.
+    pub fn handle_address_debounce_elapsed(&mut self, generation: u64) -> Command<Effect, Event> {
+        if generation != self.address_generation {
+            return Command::done();
+        }
+        Command::event(Event::FetchSuggestions {
+            ident: FieldIdent::Address,
+            query: self.form.address.value.clone(),
+        })
+    }
+
+    /// Marks the next `UpdateValue` of `ident` to `value` as the core's own
+    /// rewrite, such as a selected suggestion, so the caret goes to its end.
+    pub fn expect_core_value(&mut self, ident: FieldIdent, value: String) {
+        self.core_value = Some((ident, value));
+    }
+
+    pub fn handle_set_error_debounce(
+        &mut self,
+        ident: FieldIdent,
+        millis: Option<u64>,
+    ) -> Command<Effect, Event> {
+        self.form.field_mut(ident).set_error_debounce(millis);
         render()
     }
 
+    pub fn handle_error_debounce_elapsed(
+        &mut self,
+        ident: FieldIdent,
+        generation: u64,
+    ) -> Command<Effect, Event> {
+        if self.form.field_mut(ident).show_error(generation) {
+            render()
+        } else {
+            Command::done()
+        }
+    }
+
+    /// Starts a timer after which the field's latest error is shown, if the
+    /// field debounces its error display.
+    fn debounce_error(&self, ident: FieldIdent) -> Command<Effect, Event> {
+        let (millis, generation) = self.form.field(ident).debounce_state();
+        // Generations count per field, so the field is numbered in too
+        let fields = FieldIdent::ALL.len() as u64;
+        match millis {
+            Some(millis) => Command::request_from_shell(TimerOperation::Start {
+                id: TimerKind::ErrorDebounce.id(generation * fields + ident as u64),
+                millis,
+            })
+            .then_send(move |_| Event::ErrorDebounceElapsed { ident, generation }),
+            None => Command::done(),
+        }
+    }
+
     pub fn handle_touch_field(&mut self, ident: FieldIdent) -> Command<Effect, Event> {
         if !self.form.is_editing {
             return Command::done();
         }
-        match ident {
-            FieldIdent::Username => self.form.username.mark_touched(),
-            FieldIdent::Email => self.form.email.mark_touched(),
-            FieldIdent::Age => self.form.age.mark_touched(),
-            FieldIdent::Address => self.form.address.mark_touched(),
+        if self.form.validate_on_change {
+            self.form.field_mut(ident).mark_touched();
+            self.form.validate_contextual();
+        } else {
+            self.form.field_mut(ident).touch();
         }
         render()
     }
 
+    /// Masking is presentation only: the value and its validity stay as
+    /// they are, and a locked form can still be revealed.
+    pub fn handle_toggle_field_mask(&mut self, ident: FieldIdent) -> Command<Effect, Event> {
+        self.form.field_mut(ident).toggle_mask();
+        render()
+    }
+
     pub fn handle_set_field_editing(
         &mut self,
         ident: FieldIdent,
@@ -231,207 +1055,1754 @@ impl FormHandler {
         if !self.form.is_editing && editing {
             return Command::done();
         }
-        match ident {
-            FieldIdent::Username => self.form.username.set_editing(editing),
-            FieldIdent::Email => self.form.email.set_editing(editing),
-            FieldIdent::Age => self.form.age.set_editing(editing),
-            FieldIdent::Address => self.form.address.set_editing(editing),
+        if ident == FieldIdent::Username && !editing && self.form.is_editing {
+            self.commit_username();
         }
+        self.form.set_field_editing(ident, editing);
         render()
     }
 
     pub fn handle_submit(&mut self) -> Command<Effect, Event> {
+        // Repeated taps while the first submit is in flight
+        if !self.form.is_editing || self.form.submitting {
+            return Command::done();
+        }
+        self.submit_error = None;
+        self.commit_username();
         self.form.touch_all();
-        self.form.validate_all();
+        self.form.revalidate_all();
 
-        if self.form.is_valid() {
-            self.form.submitted = true;
-            self.form.set_editing(false);
-            return Command::event(Event::ClearSuggestions).then(render());
+        // Refused until every remote verdict is in, as the view shows
+        if self.form.can_submit() {
+            if let Some(url) = &self.email_check_url {
+                self.form.submitting = true;
+                return self.check_email(url.clone()).and(render());
+            }
+            return self.send_submission();
         } else {
             self.form.submitted = false;
         }
         render()
     }
 
-    pub fn handle_edit(&mut self) -> Command<Effect, Event> {
-        self.form.submitted = false;
-        self.form.set_editing(true);
-        render()
+    /// POSTs the form to `submit_url`, answering with
+    /// `Event::SubmitResponseReceived`, or submits it locally if there is
+    /// no such endpoint.
+    fn send_submission(&mut self) -> Command<Effect, Event> {
+        let Some(url) = self.submit_url.clone() else {
+            return self.complete_submit();
+        };
+        self.form.submitting = true;
+        let request = Http::post(&url).body_json(&self.form.submission());
+        Command::new(|ctx| async move {
+            pending::label("POST", &url, OperationKind::Submission { url: url.clone() });
+            let result = match request {
+                Ok(request) => request.build().into_future(ctx.clone()).await.map(|_| ()),
+                Err(error) => Err(error),
+            };
+            let result = match result {
+                Ok(()) => SubmitResult::Accepted,
+                Err(HttpError::Http { code, .. }) => SubmitResult::Failed {
+                    message: format!("The server refused the form (HTTP {code})."),
+                },
+                Err(_) => SubmitResult::Failed {
+                    message: SUBMIT_UNREACHABLE_MESSAGE.to_string(),
+                },
+            };
+            ctx.send_event(Event::SubmitResponseReceived(result));
+        })
+        .and(render())
     }
 
-    pub fn handle_reset(&mut self) -> Command<Effect, Event> {
-        self.form.reset();
+    /// Finishes a submit held for the server. A refused form stays editable,
+    /// with the reason as its status.
+    pub fn handle_submit_response(&mut self, result: SubmitResult) -> Command<Effect, Event> {
+        // Stale: the form was reset while the submission was in flight
+        if !self.form.submitting {
+            return Command::done();
+        }
+        self.form.submitting = false;
+        match result {
+            SubmitResult::Accepted => self.complete_submit(),
+            SubmitResult::Failed { message } => {
+                self.submit_error = Some(message);
+                render()
+            }
+        }
+    }
+
+    fn complete_submit(&mut self) -> Command<Effect, Event> {
+        self.form.submitted = true;
+        self.form.record_submit();
+        self.form.commit_baseline();
+        self.form.set_editing(false);
         Command::event(Event::ClearSuggestions).then(render())
     }
 
-    pub fn get_form(&self) -> &Form {
-        &self.form
+    /// Asks `url` whether the current email is free, answering with
+    /// `Event::EmailChecked`.
+    fn check_email(&self, url: String) -> Command<Effect, Event> {
+        let email = self.form.email.value.0.clone();
+        let request = Http::get(&url).query(&EmailCheckQuery {
+            check: email.clone(),
+        });
+        Command::new(|ctx| async move {
+            let kind = OperationKind::EmailCheck {
+                email: email.clone(),
+            };
+            pending::label("GET", &url, kind);
+            let available = match request {
+                Ok(request) => match request
+                    .expect_json::<EmailAvailability>()
+                    .build()
+                    .into_future(ctx.clone())
+                    .await
+                {
+                    Ok(mut response) => response.take_body().map(|body| body.available),
+                    Err(_) => None,
+                },
+                Err(_) => None,
+            };
+            ctx.send_event(Event::EmailChecked { email, available });
+        })
     }
 
-    pub fn view(&self) -> FormViewModel {
-        let username_vm = FieldViewModel {
-            value: self.form.username.value.0.clone(),
-            initial_value: self.form.username.initial_value.0.clone(),
-            touched: self.form.username.touched,
-            dirty: self.form.username.dirty,
-            error: self.form.username.error.clone(),
-            valid: self.form.username.valid,
-            editing: self.form.username.editing,
-        };
+    /// Finishes a submit held for the email check. Without an answer the
+    /// form stays unsubmitted so the user can try again.
+    pub fn handle_email_checked(
+        &mut self,
+        email: String,
+        available: Option<bool>,
+    ) -> Command<Effect, Event> {
+        // Stale: the submit was abandoned while the check was in flight
+        if !self.form.submitting {
+            return Command::done();
+        }
+        self.form.submitting = false;
+        // The user may have changed the form while the check was in flight
+        if !self.form.is_editing || self.form.email.value.0 != email {
+            return Command::done();
+        }
+        match available {
+            Some(true) if self.form.is_valid() => self.send_submission(),
+            // No longer submitting, and nothing to send
+            Some(true) => render(),
+            None => {
+                self.submit_error = Some(SUBMIT_UNREACHABLE_MESSAGE.to_string());
+                render()
+            }
+            Some(false) => {
+                self.form
+                    .field_mut(FieldIdent::Email)
+                    .reject(EMAIL_TAKEN_MESSAGE.to_string());
+                render()
+            }
+        }
+    }
 
-        let email_vm = FieldViewModel {
-            value: self.form.email.value.0.clone(),
-            initial_value: self.form.email.initial_value.0.clone(),
-            touched: self.form.email.touched,
-            dirty: self.form.email.dirty,
-            error: self.form.email.error.clone(),
-            valid: self.form.email.valid,
-            editing: self.form.email.editing,
-        };
+    /// Reopens a submitted form the server refused, showing its errors on
+    /// the offending fields so the user can correct them and submit again.
+    pub fn handle_submit_rejected(
+        &mut self,
+        errors: Vec<ServerFieldError>,
+    ) -> Command<Effect, Event> {
+        self.form.submitted = false;
+        self.form.set_editing(true);
+        for error in errors {
+            self.form.field_mut(error.ident).reject(error.message);
+        }
+        render()
+    }
 
-        let age_vm = FieldViewModel {
-            value: self
-                .form
-                .age
-                .value
-                .map_or_else(String::new, |v| v.to_string()),
-            initial_value: self
-                .form
-                .age
-                .initial_value
-                .map_or_else(String::new, |v| v.to_string()),
-            touched: self.form.age.touched,
-            dirty: self.form.age.dirty,
-            error: self.form.age.error.clone(),
-            valid: self.form.age.valid,
-            editing: self.form.age.editing,
+    /// Asks the remote check of `ident` about its current input, answering
+    /// with `Event::RemoteValidationReceived`. Supersedes any check of the
+    /// field still in flight.
+    pub fn handle_validate_field_remote(&mut self, ident: FieldIdent) -> Command<Effect, Event> {
+        if !self.form.is_editing {
+            return Command::done();
+        }
+        let Some(check) = self.remote_checks.iter().find(|check| check.ident == ident) else {
+            return Command::done();
         };
+        let url = check.url.clone();
+        self.check_remote(ident, &url).and(render())
+    }
 
-        let address_vm = FieldViewModel {
-            value: self.form.address.value.clone(),
-            initial_value: self.form.address.initial_value.clone(),
-            touched: self.form.address.touched,
-            dirty: self.form.address.dirty,
-            error: self.form.address.error.clone(),
-            valid: self.form.address.valid,
-            editing: self.form.address.editing,
+    /// Asks the remote check of `ident`, if it runs on edit, once the user
+    /// has paused typing and if the input passes the local rules. The edit
+    /// supersedes any check of the field waiting or in flight.
+    fn check_remote_on_edit(&mut self, ident: FieldIdent) -> Command<Effect, Event> {
+        let Some(check) = self
+            .remote_checks
+            .iter()
+            .find(|check| check.ident == ident && check.on_edit)
+        else {
+            return Command::done();
         };
-
-        FormViewModel {
-            username: username_vm,
-            email: email_vm,
-            age: age_vm,
-            address: address_vm,
-            submitted: self.form.submitted,
-            is_editing_form: self.form.is_editing,
-            status_message: if self.form.submitted {
-                "Form Submitted Successfully!".to_string()
-            } else if !self.form.is_editing {
-                "Form data (View only)".to_string()
-            } else if self.form.username.dirty
-                || self.form.email.dirty
-                || self.form.age.dirty
-                || self.form.address.dirty
-            {
-                "Form has unsaved changes".to_string()
-            } else if !self.form.is_valid() {
-                "Please correct the errors.".to_string()
-            } else {
-                "Please fill out the form.".to_string()
+        self.remote_pending.remove(&ident);
+        if !self.form.field(ident).is_valid() {
+            return Command::done();
+        }
+        if check.debounce_ms == 0 {
+            let url = check.url.clone();
+            return self.check_remote(ident, &url);
+        }
+        let millis = check.debounce_ms;
+        self.remote_generation += 1;
+        let generation = self.remote_generation;
+        self.remote_pending.insert(ident, generation);
+        // Pending already, so a submit waits for the check
+        self.form.field_mut(ident).start_check(PendingCheck::Remote);
+        Command::request_from_shell(TimerOperation::Start {
+            id: TimerKind::RemoteCheckDebounce.id(generation),
+            millis,
+        })
+        .then_send(move |_| Event::RemoteCheckDebounceElapsed { ident, generation })
+    }
+
+    /// Asks the remote check of `ident` for its input as it is now, unless
+    /// it was edited again after the edit numbered `generation`.
+    pub fn handle_remote_check_debounce_elapsed(
+        &mut self,
+        ident: FieldIdent,
+        generation: u64,
+    ) -> Command<Effect, Event> {
+        if self.remote_pending.get(&ident) != Some(&generation) {
+            return Command::done();
+        }
+        match self.remote_checks.iter().find(|check| check.ident == ident) {
+            Some(check) => {
+                let url = check.url.clone();
+                self.check_remote(ident, &url)
+            }
+            None => Command::done(),
+        }
+    }
+
+    fn check_remote(&mut self, ident: FieldIdent, url: &str) -> Command<Effect, Event> {
+        let value = match ident {
+            // Availability is a question of the name as compared for
+            // uniqueness, not as typed
+            FieldIdent::Username => self.form.username_normalized(),
+            _ => self.form.input(ident),
+        };
+        let request = Http::get(url).query(&RemoteCheckQuery { value });
+        let url = url.to_string();
+        self.remote_generation += 1;
+        let generation = self.remote_generation;
+        self.remote_pending.insert(ident, generation);
+        self.form.field_mut(ident).start_check(PendingCheck::Remote);
+
+        Command::new(move |ctx| async move {
+            pending::label("GET", &url, OperationKind::RemoteValidation { ident });
+            let verdict = match request {
+                Ok(request) => match request
+                    .expect_json::<RemoteVerdict>()
+                    .build()
+                    .into_future(ctx.clone())
+                    .await
+                {
+                    Ok(mut response) => response.take_body(),
+                    Err(_) => None,
+                },
+                Err(_) => None,
+            };
+            ctx.send_event(Event::RemoteValidationReceived {
+                ident,
+                generation,
+                valid: verdict.as_ref().map(|verdict| verdict.valid),
+                message: verdict.and_then(|verdict| verdict.message),
+            });
+        })
+    }
+
+    /// Asks the shell to check `ident` if it is async-validated and passes
+    /// the local rules, answering with `Event::AsyncValidationResult`.
+    fn validate_async(&mut self, ident: FieldIdent) -> Command<Effect, Event> {
+        if !self.async_validated.contains(&ident) || !self.form.field(ident).is_valid() {
+            return Command::done();
+        }
+        self.form.field_mut(ident).start_check(PendingCheck::Shell);
+        let value = self.form.input(ident);
+        Command::request_from_shell(AsyncValidateOperation {
+            ident,
+            value: value.clone(),
+        })
+        .then_send(move |result| Event::AsyncValidationResult {
+            ident,
+            value,
+            result,
+        })
+    }
+
+    /// Applies the shell's verdict, unless the field was edited since it
+    /// was asked. A rejection stands until the value changes, whichever
+    /// check made it, so acceptance leaves the field's errors alone.
+    pub fn handle_async_validation_result(
+        &mut self,
+        ident: FieldIdent,
+        value: String,
+        result: AsyncVerdict,
+    ) -> Command<Effect, Event> {
+        if self.form.input(ident) != value {
+            return Command::done();
+        }
+        let field = self.form.field_mut(ident);
+        if !field.finish_check(PendingCheck::Shell) {
+            return Command::done();
+        }
+        if let AsyncVerdict::Invalid { message } = result {
+            field.set_server_error(Some(message));
+        }
+        render()
+    }
+
+    /// Applies the answer to the latest remote check of `ident`. Answers to
+    /// superseded checks, or about a value edited since, are dropped.
+    pub fn handle_remote_validation_received(
+        &mut self,
+        ident: FieldIdent,
+        generation: u64,
+        valid: Option<bool>,
+        message: Option<String>,
+    ) -> Command<Effect, Event> {
+        if self.remote_pending.get(&ident) != Some(&generation) {
+            return Command::done();
+        }
+        self.remote_pending.remove(&ident);
+        let field = self.form.field_mut(ident);
+        if !field.finish_check(PendingCheck::Remote) {
+            return Command::done();
+        }
+        // Without an answer the field stays as it was
+        if valid == Some(false) {
+            field.set_server_error(Some(
+                message.unwrap_or_else(|| REMOTE_CHECK_FAILED_MESSAGE.to_string()),
+            ));
+        }
+        render()
+    }
+
+    pub fn handle_set_field_error(
+        &mut self,
+        ident: FieldIdent,
+        error: Option<String>,
+    ) -> Command<Effect, Event> {
+        self.form.field_mut(ident).set_server_error(error);
+        render()
+    }
+
+    /// Reports how far the user got, optionally deletes the saved draft, and
+    /// locks the form until it is reset.
+    pub fn handle_abandon(&mut self, discard_draft: bool) -> Command<Effect, Event> {
+        self.form.abandoned = true;
+        self.form.set_editing(false);
+        // The locked form drops any answer still in flight, so nothing else
+        // would clear this
+        self.form.submitting = false;
+
+        let abandoned: Command<Effect, Event> =
+            Command::notify_shell(AnalyticsOperation::FormAbandoned {
+                progress_percent: self.form.progress_percent(),
+                dirty_field_count: self.form.dirty_field_count(),
+            })
+            .into();
+        let discard = if discard_draft {
+            Command::notify_shell(StorageOperation::Delete {
+                key: DRAFT_KEY.to_string(),
+            })
+            .into()
+        } else {
+            Command::done()
+        };
+        abandoned.and(discard).and(render())
+    }
+
+    pub fn handle_edit(&mut self) -> Command<Effect, Event> {
+        self.form.submitted = false;
+        self.form.set_editing(true);
+        render()
+    }
+
+    /// Re-runs only the rules that depend on the current date. Renders only
+    /// if that changed a field's validity or error.
+    pub fn handle_tick(&mut self, today: NaiveDate) -> Command<Effect, Event> {
+        let date_of_birth = &self.form.date_of_birth;
+        let before = (date_of_birth.valid, date_of_birth.error.clone());
+        self.form.set_today(today);
+        let date_of_birth = &self.form.date_of_birth;
+        if (date_of_birth.valid, date_of_birth.error.clone()) != before {
+            render()
+        } else {
+            Command::done()
+        }
+    }
+
+    pub fn set_validate_on_change(&mut self, validate_on_change: bool) {
+        self.form.set_validate_on_change(validate_on_change);
+    }
+
+    pub fn set_email_check_url(&mut self, url: Option<String>) {
+        self.email_check_url = url;
+    }
+
+    pub fn set_submit_url(&mut self, url: Option<String>) {
+        self.submit_url = url;
+    }
+
+    pub fn set_remote_checks(&mut self, checks: Vec<RemoteCheck>) {
+        self.remote_checks = checks;
+    }
+
+    pub fn set_async_validated(&mut self, idents: Vec<FieldIdent>) {
+        self.async_validated = idents;
+    }
+
+    pub fn set_dynamic_form(&mut self, dynamic: DynamicForm) {
+        self.form.dynamic = dynamic;
+    }
+
+    pub fn handle_update_dynamic_value(
+        &mut self,
+        key: &str,
+        value: String,
+    ) -> Command<Effect, Event> {
+        if !self.form.dynamic.update_value(key, value) {
+            return Command::done();
+        }
+        self.submit_error = None;
+        render()
+    }
+
+    pub fn set_error_display_order(&mut self, order: Vec<FieldIdent>) {
+        self.form.error_display_order = order;
+    }
+
+    pub fn set_username_ignored_characters(&mut self, characters: String) {
+        self.form.username_ignored_characters = characters;
+    }
+
+    pub fn set_age_required(&mut self, required: bool) {
+        self.form.set_age_required(required);
+    }
+
+    pub fn set_max_address_length(&mut self, max: usize) {
+        self.form.set_max_address_length(max);
+    }
+
+    pub fn add_cross_validator(&mut self, validator: FormValidator) {
+        self.form.add_cross_validator(validator);
+    }
+
+    pub fn set_suggestion_debounce(&mut self, millis: u64) {
+        self.suggestion_debounce_ms = millis;
+    }
+
+    pub fn set_min_suggestion_query_len(&mut self, min_len: usize) {
+        self.min_suggestion_query_len = min_len;
+    }
+
+    pub fn set_username_policy(&mut self, policy: UsernamePolicy) {
+        self.form.set_username_policy(policy);
+    }
+
+    pub fn handle_reset(&mut self) -> Command<Effect, Event> {
+        self.form.reset();
+        self.signals.clear();
+        self.submit_error = None;
+        self.remote_pending.clear();
+        Command::event(Event::ClearSuggestions).then(render())
+    }
+
+    /// Asks the shell to persist the current values, leaving out the largest
+    /// fields if the payload would exceed `size_limit` bytes.
+    pub fn handle_save_draft(&mut self, size_limit: usize) -> Command<Effect, Event> {
+        let (payload, skipped) = Draft::from_form(&self.form).encode_within(size_limit);
+        let warning_changed = if skipped.is_empty() {
+            self.signals
+                .withdraw(|signal| matches!(signal, Signal::DraftTruncated(_)))
+        } else {
+            self.signals.raise(Signal::DraftTruncated(DraftWarning {
+                skipped_fields: skipped,
+            }));
+            true
+        };
+
+        let save: Command<Effect, Event> = Command::notify_shell(StorageOperation::Save {
+            key: DRAFT_KEY.to_string(),
+            value: payload,
+        })
+        .into();
+        if warning_changed {
+            save.and(render())
+        } else {
+            save
+        }
+    }
+
+    pub fn inputs(&self) -> FormInputs {
+        FormInputs::capture(&self.form)
+    }
+
+    /// Puts back earlier field text, leaving touched and submission state
+    /// alone. Suggestions are not fetched for a restored address.
+    pub fn handle_restore_inputs(&mut self, inputs: FormInputs) -> Command<Effect, Event> {
+        if !self.form.is_editing {
+            return Command::done();
+        }
+        self.withdraw_carets();
+        for (ident, value) in inputs.into_fields() {
+            if self.form.input(ident) != value {
+                self.form.set_input(ident, value);
+                self.raise_caret_at_end(ident);
+            }
+        }
+        if self.form.validate_on_change {
+            self.form.validate_all();
+        }
+        render()
+    }
+
+    /// Sets several fields as `SetFieldValue` would one after another,
+    /// without fetching suggestions, but renders once.
+    pub fn handle_batch_update(
+        &mut self,
+        values: Vec<(FieldIdent, String)>,
+    ) -> Command<Effect, Event> {
+        if !self.form.is_editing {
+            return Command::done();
+        }
+        self.submit_error = None;
+        self.withdraw_carets();
+        let idents: Vec<FieldIdent> = values.iter().map(|(ident, _)| *ident).collect();
+        for (ident, value) in values {
+            self.form.set_input(ident, value);
+        }
+        if !self.form.validate_on_change {
+            return render();
+        }
+        self.form.validate_all();
+        let follow_ups: Vec<_> = idents
+            .into_iter()
+            .map(|ident| {
+                self.debounce_error(ident)
+                    .and(self.validate_async(ident))
+                    .and(self.check_remote_on_edit(ident))
+            })
+            .collect();
+        render().and(Command::all(follow_ups))
+    }
+
+    /// Puts `ident` back to its value before the last change, like the
+    /// shell had sent it.
+    pub fn handle_undo_field(&mut self, ident: FieldIdent) -> Command<Effect, Event> {
+        if !self.form.is_editing || !self.form.field_mut(ident).undo() {
+            return Command::done();
+        }
+        self.withdraw_carets();
+        self.raise_caret_at_end(ident);
+        let debounce = if self.form.validate_on_change {
+            self.form.validate_all();
+            self.debounce_error(ident)
+        } else {
+            Command::done()
+        };
+        render().and(debounce)
+    }
+
+    pub fn handle_restore_draft(&mut self, payload: String) -> Command<Effect, Event> {
+        if !self.form.is_editing {
+            return Command::done();
+        }
+        let Some(draft) = Draft::decode(&payload) else {
+            return Command::done();
+        };
+        draft.apply_to(&mut self.form);
+        render()
+    }
+
+    pub fn handle_mark_as_pristine(&mut self) -> Command<Effect, Event> {
+        if !self.form.is_editing {
+            return Command::done();
+        }
+        self.form.mark_as_pristine();
+        render()
+    }
+
+    pub fn handle_restore_snapshot(&mut self, snapshot: FormSnapshot) -> Command<Effect, Event> {
+        if !self.form.is_editing {
+            return Command::done();
+        }
+        self.form.restore_snapshot(snapshot);
+        render()
+    }
+
+    pub fn signals(&self) -> &SignalQueue {
+        &self.signals
+    }
+
+    /// Numbers new signals after `last_seq`, for a handler replacing one
+    /// whose signals the shell has seen.
+    pub fn resume_signals_after(&mut self, last_seq: u64) {
+        self.signals.resume_after(last_seq);
+    }
+
+    pub fn handle_acknowledge_signals(&mut self, up_to_seq: u64) -> Command<Effect, Event> {
+        if self.signals.acknowledge(up_to_seq) {
+            render()
+        } else {
+            Command::done()
+        }
+    }
+
+    /// Points the caret of `ident` after the last character of its text.
+    fn raise_caret_at_end(&mut self, ident: FieldIdent) {
+        let position = u32::try_from(self.form.input(ident).chars().count()).unwrap_or(u32::MAX);
+        self.signals.raise(Signal::Caret { ident, position });
+    }
+
+    /// Carets placed for an earlier rewrite; editing moves on from them.
+    fn withdraw_carets(&mut self) {
+        self.signals
+            .withdraw(|signal| matches!(signal, Signal::Caret { .. }));
+    }
+
+    fn commit_username(&mut self) {
+        if self.form.commit_username() {
+            self.raise_caret_at_end(FieldIdent::Username);
+        }
+    }
+
+    pub fn get_form(&self) -> &Form {
+        &self.form
+    }
+
+    pub fn view(&self) -> FormViewModel {
+        let form = &self.form;
+        let error_summary = form.error_summary();
+        FormViewModel {
+            username: form.username.view_model(|v| v.0.clone()),
+            email: form.email.view_model(|v| v.0.clone()),
+            age: form
+                .age
+                .view_model(|v| v.map_or_else(String::new, |v| v.to_string())),
+            address: form.address.view_model(String::clone),
+            date_of_birth: form.date_of_birth.view_model(DateOfBirth::to_string),
+            middle_name: form.middle_name.view_model(|v| v.0.clone()),
+            preferred_name: form.preferred_name.view_model(|v| v.0.clone()),
+            submitted: form.submitted,
+            is_editing_form: form.is_editing,
+            abandoned: form.abandoned,
+            status: form.status(),
+            status_message: match &self.submit_error {
+                Some(error) => error.clone(),
+                None => form.status().message().to_string(),
             },
-            can_submit: self.form.can_submit(),
+            can_submit: form.can_submit(),
+            username_policy: form.username_policy,
+            focus_hint: error_summary.first().map(|error| error.ident),
+            error_summary,
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + Validatable> Field<T> {
+    /// A pristine field holding `value`, validated up front.
+    pub fn new(value: T, required: bool) -> Self {
+        let mut field = Self {
+            value: value.clone(),
+            initial_value: value,
+            touched: false,
+            dirty: false,
+            error: None,
+            valid: true,
+            editing: false,
+            required,
+            raw_input: None,
+            display_error: None,
+            error_debounce_ms: None,
+            error_generation: 0,
+            server_error: None,
+            validated: false,
+            masked: false,
+            previous_value: None,
+            pending_checks: BTreeSet::new(),
+            errors: Vec::new(),
+            validators: FieldValidators::default(),
+        };
+        field.validate();
+        field
+    }
+
+    /// While editing, and while what was typed is invalid, parsed fields
+    /// echo the raw input instead of `format`.
+    fn view_model(&self, format: impl Fn(&T) -> String) -> FieldViewModel {
+        FieldViewModel {
+            value: match &self.raw_input {
+                Some(raw) if self.editing || !self.valid => raw.clone(),
+                _ => format(&self.value),
+            },
+            initial_value: format(&self.initial_value),
+            touched: self.touched,
+            dirty: self.dirty,
+            error: self.error.clone(),
+            error_code: self.error.as_deref().and_then(error_code).map(String::from),
+            errors: self.shown_errors(),
+            display_error: self.display_error.clone(),
+            valid: self.valid,
+            editing: self.editing,
+            required: self.required,
+            masked: self.masked,
+            undoable: self.previous_value.is_some(),
+            validating: !self.pending_checks.is_empty(),
+            has_server_error: self.server_error.is_some(),
+        }
+    }
+
+    pub fn set_value(&mut self, value: T) {
+        self.assign(value);
+        self.validate();
+    }
+
+    /// Updates the value and dirty state without validating it.
+    pub fn assign(&mut self, value: T) {
+        if value != self.value {
+            self.previous_value = Some(std::mem::replace(&mut self.value, value));
+        }
+        self.value_changed();
+    }
+
+    /// Restores the value before the last change and validates it. Only one
+    /// change is kept, so undoing twice in a row does nothing the second
+    /// time. Returns whether there was a value to restore.
+    pub fn undo(&mut self) -> bool {
+        let Some(previous) = self.previous_value.take() else {
+            return false;
+        };
+        self.value = previous;
+        // What was typed belongs to the value being undone
+        self.raw_input = None;
+        self.value_changed();
+        self.validate();
+        true
+    }
+
+    fn value_changed(&mut self) {
+        // An answer in flight is about the old value
+        self.pending_checks.clear();
+        self.server_error = None;
+        self.validated = false;
+        self.dirty = self.value != self.initial_value;
+        if self.error_debounce_ms.is_some() {
+            self.error_generation += 1;
+        }
+    }
+
+    /// Marks the value invalid with `error` from outside the validator, such
+    /// as the server, until the value changes; `None` withdraws it. Shown at
+    /// once, like a touched field's.
+    pub fn set_server_error(&mut self, error: Option<String>) {
+        self.server_error = error;
+        self.touched = true;
+        self.validate();
+        self.display_error = self.error.clone();
+    }
+
+    pub fn mark_touched(&mut self) {
+        self.touched = true;
+        self.validate();
+        self.display_error = self.error.clone();
+    }
+
+    pub fn set_error_debounce(&mut self, millis: Option<u64>) {
+        self.error_debounce_ms = millis;
+        self.display_error = self.error.clone();
+    }
+
+    /// Shows the current error if `generation` is the latest edit.
+    /// Returns whether the displayed error was updated.
+    pub fn show_error(&mut self, generation: u64) -> bool {
+        if generation != self.error_generation {
+            return false;
+        }
+        self.display_error = self.error.clone();
+        true
+    }
+
+    fn debounce_state(&self) -> (Option<u64>, u64) {
+        (self.error_debounce_ms, self.error_generation)
+    }
+
+    pub fn set_editing(&mut self, editing: bool) {
+        self.editing = editing;
+        if !editing {
+            // Canonicalize on blur
+            self.raw_input = None;
+        }
+    }
+
+    /// Adds a rule checked after the value's own, in the order added.
+    pub fn add_validator(
+        &mut self,
+        validator: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        self.validators.0.push(Arc::new(validator));
+        self.validated = false;
+    }
+
+    /// `add_validator`, for chaining onto `Field::new`.
+    pub fn with_validator(
+        mut self,
+        validator: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.add_validator(validator);
+        self
+    }
+
+    /// Keeps the rules added to `other`, e.g. the field a reset replaces.
+    pub(crate) fn take_validators(&mut self, other: &mut Field<T>) {
+        self.validators = std::mem::take(&mut other.validators);
+        if !self.validators.0.is_empty() {
+            self.validated = false;
+        }
+    }
+
+    fn validate(&mut self) {
+        self.validated = true;
+        let added: Vec<String> = self
+            .validators
+            .0
+            .iter()
+            .filter_map(|validator| validator(&self.value).err())
+            .collect();
+        self.valid = self.value.is_valid() && added.is_empty();
+        self.errors = self.value.error_messages();
+        self.errors.extend(added);
+        self.error = self.errors.first().cloned();
+        if self.valid && self.server_error.is_some() {
+            self.valid = false;
+            self.error = self.server_error.clone();
+        }
+        if self.error_debounce_ms.is_none() {
+            self.display_error = self.error.clone();
+        }
+    }
+
+    /// `errors` if `error` is the first of them, else `error` alone:
+    /// server and form-level errors replace the value's.
+    fn shown_errors(&self) -> Vec<String> {
+        let Some(error) = &self.error else {
+            return Vec::new();
+        };
+        if self.errors.first() == Some(error) {
+            self.errors.clone()
+        } else {
+            vec![error.clone()]
+        }
+    }
+
+    /// Marks the field invalid with an error from a rule outside the value.
+    fn fail(&mut self, error: String) {
+        self.valid = false;
+        self.error = Some(error);
+        if self.error_debounce_ms.is_none() {
+            self.display_error = self.error.clone();
+        }
+    }
+}
+
+/// Type-erased view of a field, for code that treats every field alike.
+pub trait FormField {
+    fn is_valid(&self) -> bool;
+    fn is_dirty(&self) -> bool;
+    fn is_required(&self) -> bool;
+    fn is_touched(&self) -> bool;
+    fn display_error(&self) -> Option<&str>;
+    /// The current validation error, shown yet or not.
+    fn error(&self) -> Option<&str>;
+    fn mark_touched(&mut self);
+    /// Marks the field touched without validating it.
+    fn touch(&mut self);
+    /// Sets whether the field counts as touched, without validating it.
+    fn set_touched(&mut self, touched: bool);
+    fn revalidate(&mut self);
+    /// Validates the field only if its value changed since it was last
+    /// validated.
+    fn validate_if_changed(&mut self);
+    /// Forgets any validation result, as if the field had never been checked.
+    fn clear_validation(&mut self);
+    fn set_editing(&mut self, editing: bool);
+    fn toggle_mask(&mut self);
+    /// Makes the current value the one `dirty` compares against.
+    fn commit_baseline(&mut self);
+    /// Applies a server error to the current value and shows it at once.
+    fn reject(&mut self, message: String) {
+        self.set_server_error(Some(message))
+    }
+    /// Marks the field invalid with an error from a rule outside the value.
+    fn fail(&mut self, error: String);
+    /// Sets or, with `None`, withdraws the server error.
+    fn set_server_error(&mut self, error: Option<String>);
+    fn is_validating(&self) -> bool;
+    fn start_check(&mut self, check: PendingCheck);
+    /// Settles `check`, returning whether it was still awaited.
+    fn finish_check(&mut self, check: PendingCheck) -> bool;
+    fn error_debounce_ms(&self) -> Option<u64>;
+    fn set_error_debounce(&mut self, millis: Option<u64>);
+    fn show_error(&mut self, generation: u64) -> bool;
+    fn debounce_state(&self) -> (Option<u64>, u64);
+    fn undo(&mut self) -> bool;
+}
+
+impl<T: Clone + PartialEq + Validatable> FormField for Field<T> {
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn is_required(&self) -> bool {
+        self.required
+    }
+
+    fn is_touched(&self) -> bool {
+        self.touched
+    }
+
+    fn display_error(&self) -> Option<&str> {
+        self.display_error.as_deref()
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn mark_touched(&mut self) {
+        Field::mark_touched(self)
+    }
+
+    fn touch(&mut self) {
+        self.touched = true;
+    }
+
+    fn set_touched(&mut self, touched: bool) {
+        self.touched = touched;
+    }
+
+    fn revalidate(&mut self) {
+        self.validate()
+    }
+
+    fn validate_if_changed(&mut self) {
+        if !self.validated {
+            self.validate()
+        }
+    }
+
+    fn clear_validation(&mut self) {
+        self.validated = false;
+        self.valid = true;
+        self.error = None;
+        self.display_error = None;
+    }
+
+    fn set_editing(&mut self, editing: bool) {
+        Field::set_editing(self, editing)
+    }
+
+    fn toggle_mask(&mut self) {
+        self.masked = !self.masked;
+    }
+
+    fn commit_baseline(&mut self) {
+        self.initial_value = self.value.clone();
+        self.dirty = false;
+    }
+
+    fn undo(&mut self) -> bool {
+        Field::undo(self)
+    }
+
+    fn set_server_error(&mut self, error: Option<String>) {
+        Field::set_server_error(self, error)
+    }
+
+    fn fail(&mut self, error: String) {
+        Field::fail(self, error)
+    }
+
+    fn is_validating(&self) -> bool {
+        !self.pending_checks.is_empty()
+    }
+
+    fn start_check(&mut self, check: PendingCheck) {
+        self.pending_checks.insert(check);
+    }
+
+    fn finish_check(&mut self, check: PendingCheck) -> bool {
+        self.pending_checks.remove(&check)
+    }
+
+    fn error_debounce_ms(&self) -> Option<u64> {
+        self.error_debounce_ms
+    }
+
+    fn set_error_debounce(&mut self, millis: Option<u64>) {
+        Field::set_error_debounce(self, millis)
+    }
+
+    fn show_error(&mut self, generation: u64) -> bool {
+        Field::show_error(self, generation)
+    }
+
+    fn debounce_state(&self) -> (Option<u64>, u64) {
+        Field::debounce_state(self)
+    }
+}
+
+impl Validatable for String {
+    fn is_valid(&self) -> bool {
+        !self.is_empty()
+    }
+
+    fn error_message(&self) -> Option<String> {
+        if self.is_empty() {
+            Some("Field cannot be empty".to_string())
+        } else {
+            None
+        }
+    }
+
+    fn kind() -> FieldKind {
+        FieldKind::Text
+    }
+
+    fn validators() -> Vec<ValidatorSpec> {
+        vec![ValidatorSpec::MinLength { min: 1 }]
+    }
+}
+
+impl Validatable for Option<u32> {
+    fn is_valid(&self) -> bool {
+        match self {
+            Some(age) => (MINIMUM_AGE..=MAXIMUM_AGE).contains(age),
+            None => true,
+        }
+    }
+
+    fn error_message(&self) -> Option<String> {
+        match self {
+            Some(age) if !(MINIMUM_AGE..=MAXIMUM_AGE).contains(age) => Some(format!(
+                "Age must be between {MINIMUM_AGE} and {MAXIMUM_AGE}"
+            )),
+            _ => None,
+        }
+    }
+
+    fn kind() -> FieldKind {
+        FieldKind::Number
+    }
+
+    fn validators() -> Vec<ValidatorSpec> {
+        vec![ValidatorSpec::Range {
+            min: MINIMUM_AGE,
+            max: MAXIMUM_AGE,
+        }]
+    }
+}
+
+impl Validatable for DateOfBirth {
+    fn is_valid(&self) -> bool {
+        true
+    }
+
+    fn error_message(&self) -> Option<String> {
+        None
+    }
+
+    fn kind() -> FieldKind {
+        FieldKind::Date
+    }
+
+    fn validators() -> Vec<ValidatorSpec> {
+        Vec::new()
+    }
+}
+
+impl Validatable for OptionalText {
+    fn is_valid(&self) -> bool {
+        true
+    }
+
+    fn error_message(&self) -> Option<String> {
+        None
+    }
+
+    fn kind() -> FieldKind {
+        FieldKind::Text
+    }
+
+    fn validators() -> Vec<ValidatorSpec> {
+        Vec::new()
+    }
+}
+
+impl Validatable for PasswordStrength {
+    fn is_valid(&self) -> bool {
+        self.error_messages().is_empty()
+    }
+
+    fn error_message(&self) -> Option<String> {
+        self.error_messages().into_iter().next()
+    }
+
+    fn error_messages(&self) -> Vec<String> {
+        if self.0.is_empty() {
+            return vec!["Password cannot be empty".to_string()];
+        }
+        let mut errors = Vec::new();
+        if self.0.len() < MIN_PASSWORD_LENGTH {
+            errors.push(format!(
+                "Password must be at least {MIN_PASSWORD_LENGTH} characters"
+            ));
+        }
+        for (regex, (_, error)) in PASSWORD_REGEXES.iter().zip(PASSWORD_RULES) {
+            if !regex.is_match(&self.0) {
+                errors.push(error.to_string());
+            }
+        }
+        errors
+    }
+
+    fn kind() -> FieldKind {
+        FieldKind::Text
+    }
+
+    fn validators() -> Vec<ValidatorSpec> {
+        let mut validators = vec![ValidatorSpec::MinLength {
+            min: MIN_PASSWORD_LENGTH,
+        }];
+        validators.extend(
+            PASSWORD_RULES
+                .iter()
+                .map(|(pattern, _)| ValidatorSpec::Pattern {
+                    pattern: pattern.to_string(),
+                }),
+        );
+        validators
+    }
+}
+
+impl fmt::Display for DateOfBirth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Form {
+    pub fn field(&self, ident: FieldIdent) -> &dyn FormField {
+        match ident {
+            FieldIdent::Username => &self.username,
+            FieldIdent::Email => &self.email,
+            FieldIdent::Age => &self.age,
+            FieldIdent::Address => &self.address,
+            FieldIdent::DateOfBirth => &self.date_of_birth,
+            FieldIdent::MiddleName => &self.middle_name,
+            FieldIdent::PreferredName => &self.preferred_name,
+        }
+    }
+
+    pub fn field_mut(&mut self, ident: FieldIdent) -> &mut dyn FormField {
+        match ident {
+            FieldIdent::Username => &mut self.username,
+            FieldIdent::Email => &mut self.email,
+            FieldIdent::Age => &mut self.age,
+            FieldIdent::Address => &mut self.address,
+            FieldIdent::DateOfBirth => &mut self.date_of_birth,
+            FieldIdent::MiddleName => &mut self.middle_name,
+            FieldIdent::PreferredName => &mut self.preferred_name,
+        }
+    }
+
+    /// The field's text as the user entered it.
+    pub fn input(&self, ident: FieldIdent) -> String {
+        match ident {
+            FieldIdent::Username => self.username.value.0.clone(),
+            FieldIdent::Email => self.email.value.0.clone(),
+            FieldIdent::Age => self.age.raw_input.clone().unwrap_or_else(|| {
+                self.age
+                    .value
+                    .map_or_else(String::new, |age| age.to_string())
+            }),
+            FieldIdent::Address => self.address.value.clone(),
+            FieldIdent::DateOfBirth => self
+                .date_of_birth
+                .raw_input
+                .clone()
+                .unwrap_or_else(|| self.date_of_birth.value.to_string()),
+            FieldIdent::MiddleName => self.middle_name.value.0.clone(),
+            FieldIdent::PreferredName => self.preferred_name.value.0.clone(),
+        }
+    }
+
+    /// The field's baseline as text, in the form `input` gives.
+    pub fn baseline_input(&self, ident: FieldIdent) -> String {
+        match ident {
+            FieldIdent::Username => self.username.initial_value.0.clone(),
+            FieldIdent::Email => self.email.initial_value.0.clone(),
+            FieldIdent::Age => self
+                .age
+                .initial_value
+                .map_or_else(String::new, |age| age.to_string()),
+            FieldIdent::Address => self.address.initial_value.clone(),
+            FieldIdent::DateOfBirth => self.date_of_birth.initial_value.to_string(),
+            FieldIdent::MiddleName => self.middle_name.initial_value.0.clone(),
+            FieldIdent::PreferredName => self.preferred_name.initial_value.0.clone(),
+        }
+    }
+
+    /// Makes the submitted values the baseline later edits are compared to.
+    pub fn commit_baseline(&mut self) {
+        for ident in FieldIdent::ALL {
+            self.field_mut(ident).commit_baseline();
+        }
+    }
+
+    /// Makes the current values the baseline and forgets which fields were
+    /// touched, e.g. once they have been saved elsewhere. Values and errors
+    /// are kept.
+    pub fn mark_as_pristine(&mut self) {
+        self.commit_baseline();
+        for ident in FieldIdent::ALL {
+            self.field_mut(ident).set_touched(false);
+        }
+    }
+
+    /// Parses `value` into the field without validating it.
+    pub fn set_input(&mut self, ident: FieldIdent, value: String) {
+        match ident {
+            FieldIdent::Username => {
+                self.username.assign(value.as_str().into());
+            }
+            FieldIdent::Email => {
+                self.email.assign(value.as_str().into());
+            }
+            FieldIdent::Age => {
+                let age = parse_age(&value);
+                self.age.assign(age);
+                self.age.raw_input = Some(value);
+            }
+            FieldIdent::DateOfBirth => {
+                let date = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok();
+                self.date_of_birth.assign(DateOfBirth(date));
+                self.date_of_birth.raw_input = Some(value);
+            }
+            FieldIdent::MiddleName => {
+                self.middle_name.assign(OptionalText(value));
+            }
+            FieldIdent::PreferredName => {
+                self.preferred_name.assign(OptionalText(value));
+            }
+            FieldIdent::Address => {
+                self.address.assign(value);
+            }
+        }
+    }
+
+    pub fn touch_all(&mut self) {
+        for ident in FieldIdent::ALL {
+            self.field_mut(ident).mark_touched();
+        }
+        self.dynamic.touch_all();
+        self.validate_contextual();
+    }
+
+    /// Validates the fields changed since their last validation, then the
+    /// contextual rules.
+    pub fn validate_all(&mut self) {
+        for ident in FieldIdent::ALL {
+            self.field_mut(ident).validate_if_changed();
+        }
+        self.validate_contextual();
+    }
+
+    /// Validates every field, changed or not.
+    pub fn revalidate_all(&mut self) {
+        for ident in FieldIdent::ALL {
+            self.field_mut(ident).revalidate();
+        }
+        self.dynamic.validate_all();
+        self.validate_contextual();
+    }
+
+    /// Runs the rules that depend on form state beyond a field's own value,
+    /// cross validators last.
+    pub fn validate_contextual(&mut self) {
+        // Cross errors are derived afresh, so a change to either field
+        // compared clears them
+        for ident in std::mem::take(&mut self.cross_failed) {
+            self.field_mut(ident).revalidate();
+        }
+        self.validate_username_policy();
+        self.validate_age_input();
+        self.validate_address_length();
+        self.validate_time_dependent();
+        self.run_cross_validators();
+    }
+
+    /// Adds a rule over several fields, checked from the next validation on.
+    pub fn add_cross_validator(&mut self, validator: FormValidator) {
+        self.cross_validators.0.push(validator);
+    }
+
+    /// Re-derives the errors of the cross validators. These depend on the
+    /// other form-level rules, so those run again too.
+    pub fn cross_validate(&mut self) {
+        self.validate_contextual();
+    }
+
+    /// A field's own error takes precedence over a cross error.
+    fn run_cross_validators(&mut self) {
+        let failures: Vec<(FieldIdent, String)> = self
+            .cross_validators
+            .0
+            .iter()
+            .filter_map(|validator| validator(self))
+            .collect();
+        for (ident, error) in failures {
+            let field = self.field_mut(ident);
+            if field.is_valid() {
+                field.fail(error);
+                self.cross_failed.push(ident);
+            }
+        }
+    }
+
+    /// Makes an empty age invalid or valid again, re-checking it at once.
+    /// Like `set_username_policy`, for whether an age is required.
+    pub fn set_age_required(&mut self, required: bool) {
+        if self.age.required == required {
+            return;
+        }
+        self.age.required = required;
+        self.age.validated = false;
+        if self.validate_on_change {
+            self.validate_all();
+        }
+    }
+
+    /// Like `Field::set_editing`, except that an age that is not a number,
+    /// or a date of birth that is not a date, keeps its text for the user
+    /// to correct.
+    pub fn set_field_editing(&mut self, ident: FieldIdent, editing: bool) {
+        let unparsed = match ident {
+            FieldIdent::Age => self.unparsed_age(),
+            FieldIdent::DateOfBirth => self.unparsed_date_of_birth(),
+            _ => None,
+        }
+        .map(str::to_string);
+        self.field_mut(ident).set_editing(editing);
+        match ident {
+            FieldIdent::Age if unparsed.is_some() => self.age.raw_input = unparsed,
+            FieldIdent::DateOfBirth if unparsed.is_some() => {
+                self.date_of_birth.raw_input = unparsed
+            }
+            _ => {}
+        }
+    }
+
+    fn unparsed_date_of_birth(&self) -> Option<&str> {
+        self.date_of_birth
+            .raw_input
+            .as_deref()
+            .filter(|raw| !raw.trim().is_empty() && self.date_of_birth.value.0.is_none())
+    }
+
+    /// Text that does not read as an age, including digits too many for
+    /// one.
+    fn unparsed_age(&self) -> Option<&str> {
+        self.age
+            .raw_input
+            .as_deref()
+            .filter(|raw| !raw.trim().is_empty() && parse_age(raw).is_none())
+    }
+
+    /// Text that is not a number leaves the age empty, which is valid when
+    /// it is optional, so it is failed here instead.
+    fn validate_age_input(&mut self) {
+        if !(self.age.validated && self.age.valid) {
+            return;
+        }
+        if self.unparsed_age().is_some() {
+            self.age.fail(AGE_NOT_A_NUMBER_MESSAGE.to_string());
+        } else if self.age.required && self.age.value.is_none() {
+            self.age.fail(AGE_REQUIRED_MESSAGE.to_string());
+        }
+    }
+
+    /// Switches the username policy. The username was checked under the old
+    /// one, so it is checked again: at once, or with the next validation
+    /// while validation is deferred.
+    pub fn set_username_policy(&mut self, policy: UsernamePolicy) {
+        if self.username_policy == policy {
+            return;
+        }
+        self.username_policy = policy;
+        self.username.validated = false;
+        if self.validate_on_change {
+            self.validate_all();
+        }
+    }
+
+    /// Changes the longest address accepted, re-checking the address at
+    /// once unless validation is deferred and it has not been checked yet.
+    pub fn set_max_address_length(&mut self, max: usize) {
+        self.max_address_length = max;
+        if !self.validate_on_change && !self.address.validated {
+            return;
+        }
+        self.address.validate();
+        self.validate_address_length();
+    }
+
+    /// Fails an address that is valid otherwise but too long. The failure
+    /// lasts until the value or the limit changes.
+    fn validate_address_length(&mut self) {
+        if self.address.validated
+            && self.address.valid
+            && self.address.value.chars().count() > self.max_address_length
+        {
+            self.address.fail(ADDRESS_TOO_LONG_MESSAGE.to_string());
         }
     }
-}
 
-impl<T: Clone + PartialEq + Validatable> Field<T> {
-    pub fn set_value(&mut self, value: T) {
-        self.value = value;
-        self.dirty = self.value != self.initial_value;
-        self.validate();
+    /// Fails a username that is valid otherwise but breaks the policy. Like
+    /// the other contextual rules it builds on the field's own validation,
+    /// so an unchanged username is not validated again.
+    fn validate_username_policy(&mut self) {
+        if !(self.username.validated && self.username.valid) {
+            return;
+        }
+        if let Some(error) = self.username_policy.check(&self.username.value.0) {
+            self.username.fail(error);
+        }
     }
 
-    pub fn mark_touched(&mut self) {
-        self.touched = true;
-        self.validate();
+    /// Turning live validation off hides every error until the next submit;
+    /// turning it back on validates straight away.
+    pub fn set_validate_on_change(&mut self, validate_on_change: bool) {
+        if self.validate_on_change == validate_on_change {
+            return;
+        }
+        self.validate_on_change = validate_on_change;
+        if validate_on_change {
+            self.validate_all();
+        } else {
+            for ident in FieldIdent::ALL {
+                self.field_mut(ident).clear_validation();
+            }
+        }
     }
 
-    pub fn set_editing(&mut self, editing: bool) {
-        self.editing = editing;
+    /// The username as compared for uniqueness: lowercased, without the
+    /// ignored characters. The shown value is unaffected.
+    pub fn username_normalized(&self) -> String {
+        self.username
+            .value
+            .0
+            .chars()
+            .filter(|c| !self.username_ignored_characters.contains(*c))
+            .flat_map(char::to_lowercase)
+            .collect()
     }
 
-    fn validate(&mut self) {
-        self.valid = self.value.is_valid();
-        self.error = self.value.error_message();
+    /// Normalizes the username per the active policy, as on leaving the
+    /// field. Returns whether that changed it.
+    pub fn commit_username(&mut self) -> bool {
+        let canonical = self.username_policy.canonicalize(&self.username.value.0);
+        if canonical == self.username.value.0 {
+            return false;
+        }
+        self.username.set_value(Username(canonical));
+        self.validate_username_policy();
+        true
     }
-}
 
-impl Validatable for String {
-    fn is_valid(&self) -> bool {
-        !self.is_empty()
+    /// Moves the date the time-dependent rules compare against. A new date
+    /// re-checks the date of birth, unless validation is deferred and it has
+    /// not been checked yet.
+    pub fn set_today(&mut self, today: NaiveDate) {
+        if self.today == Some(today) {
+            return;
+        }
+        self.today = Some(today);
+        if !self.validate_on_change && !self.date_of_birth.validated {
+            return;
+        }
+        self.date_of_birth.validate();
+        self.validate_time_dependent();
     }
 
-    fn error_message(&self) -> Option<String> {
-        if self.is_empty() {
-            Some("Field cannot be empty".to_string())
-        } else {
-            None
+    /// Fails a date of birth that is valid otherwise but is not a date, or
+    /// too recent. Until the shell reports a date the age rules cannot be
+    /// evaluated and are skipped.
+    fn validate_time_dependent(&mut self) {
+        if !(self.date_of_birth.validated && self.date_of_birth.valid) {
+            return;
+        }
+        // Text that is not a date leaves the date empty, which is valid
+        if self.unparsed_date_of_birth().is_some() {
+            self.date_of_birth
+                .fail(DATE_OF_BIRTH_NOT_A_DATE_MESSAGE.to_string());
+            return;
+        }
+        let (Some(date_of_birth), Some(today)) = (self.date_of_birth.value.0, self.today) else {
+            return;
+        };
+        match today.years_since(date_of_birth) {
+            None => self
+                .date_of_birth
+                .fail("Date of birth cannot be in the future".to_string()),
+            Some(years) if years < MINIMUM_AGE => self
+                .date_of_birth
+                .fail(format!("You must be at least {MINIMUM_AGE} years old")),
+            Some(_) => {}
         }
     }
-}
 
-impl Validatable for Option<u32> {
-    fn is_valid(&self) -> bool {
-        match self {
-            Some(age) => *age >= 18 && *age <= 120,
-            None => true,
+    pub fn submission(&self) -> FormSubmission {
+        FormSubmission {
+            username: self.username.value.0.clone(),
+            email: self.email.value.0.clone(),
+            age: self.age.value,
+            address: self.address.value.clone(),
+            dynamic: self.dynamic.values(),
         }
     }
 
-    fn error_message(&self) -> Option<String> {
-        match self {
-            Some(age) if *age < 18 || *age > 120 => {
-                Some("Age must be between 18 and 120".to_string())
-            }
-            _ => None,
+    pub fn is_valid(&self) -> bool {
+        FieldIdent::ALL
+            .into_iter()
+            .all(|ident| self.field(ident).is_valid())
+            && self.dynamic.is_valid()
+    }
+
+    /// `is_valid` as a submit would find it. With validation deferred the
+    /// results shown were cleared, so the values are checked on a copy.
+    fn would_be_valid(&self) -> bool {
+        if self.validate_on_change {
+            return self.is_valid();
         }
+        let mut form = self.clone();
+        form.revalidate_all();
+        form.is_valid()
     }
-}
 
-impl Form {
-    pub fn touch_all(&mut self) {
-        self.username.mark_touched();
-        self.email.mark_touched();
-        self.age.mark_touched();
-        self.address.mark_touched();
+    pub fn dirty_field_count(&self) -> usize {
+        self.dirty_fields().len()
     }
 
-    pub fn validate_all(&mut self) {
-        self.username.validate();
-        self.email.validate();
-        self.age.validate();
-        self.address.validate();
+    /// Fields whose value differs from their baseline, in form order.
+    pub fn dirty_fields(&self) -> Vec<FieldIdent> {
+        FieldIdent::ALL
+            .into_iter()
+            .filter(|ident| self.field(*ident).is_dirty())
+            .collect()
     }
 
-    pub fn is_valid(&self) -> bool {
-        self.username.valid && self.email.valid && self.age.valid && self.address.valid
+    /// Fields changed since the last accepted submit, in form order. Unlike
+    /// `dirty_fields`, marking the form pristine does not clear them; only
+    /// another submit does. Before the first submit, the dirty fields.
+    pub fn changed_since_submit(&self) -> Vec<FieldIdent> {
+        if self.submitted_inputs.is_empty() {
+            return self.dirty_fields();
+        }
+        self.submitted_inputs
+            .iter()
+            .filter(|(ident, input)| *input != self.input(*ident))
+            .map(|(ident, _)| *ident)
+            .collect()
+    }
+
+    fn record_submit(&mut self) {
+        self.submitted_inputs = FieldIdent::ALL
+            .into_iter()
+            .map(|ident| (ident, self.input(ident)))
+            .collect();
+    }
+
+    /// Fields whose value is invalid, whether or not the error is shown
+    /// yet, in form order.
+    pub fn invalid_fields(&self) -> Vec<FieldIdent> {
+        FieldIdent::ALL
+            .into_iter()
+            .filter(|ident| !self.field(*ident).is_valid())
+            .collect()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.invalid_fields().len()
+    }
+
+    /// Share of required fields holding a valid value, from 0 to 100.
+    pub fn progress_percent(&self) -> u32 {
+        let required = self.required_fields();
+        if required.is_empty() {
+            return 100;
+        }
+        let complete = required
+            .iter()
+            .filter(|ident| self.field(**ident).is_valid())
+            .count();
+        (complete * 100 / required.len()) as u32
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        FieldIdent::ALL
+            .into_iter()
+            .any(|ident| self.field(ident).is_dirty())
+            || self.dynamic.is_dirty()
+    }
+
+    /// While editing, the banner describes the values even if an earlier
+    /// submission went through; unsaved changes take precedence over errors
+    /// so the banner does not flicker while typing.
+    pub fn status(&self) -> FormStatus {
+        match (
+            self.submitted,
+            self.is_editing,
+            self.is_dirty(),
+            self.is_valid(),
+        ) {
+            (true, false, _, _) => FormStatus::Submitted,
+            (false, false, _, _) => FormStatus::ViewOnly,
+            (_, true, true, _) => FormStatus::UnsavedChanges,
+            (_, true, false, false) => FormStatus::Invalid,
+            (_, true, false, true) => FormStatus::Ready,
+        }
     }
 
     pub fn set_editing(&mut self, editing: bool) {
         self.is_editing = editing;
-        self.username.set_editing(editing);
-        self.email.set_editing(editing);
-        self.age.set_editing(editing);
-        self.address.set_editing(editing);
+        for ident in FieldIdent::ALL {
+            self.set_field_editing(ident, editing);
+        }
     }
 
     pub fn reset(&mut self) {
-        *self = Form::default();
+        let mut fresh = Form::default();
+        // Display policy is configuration, not form data
+        for ident in FieldIdent::ALL {
+            fresh
+                .field_mut(ident)
+                .set_error_debounce(self.field(ident).error_debounce_ms());
+        }
+        // The shell's clock is not form data either
+        fresh.today = self.today;
+        fresh.username_policy = self.username_policy;
+        fresh.set_validate_on_change(self.validate_on_change);
+        fresh.error_display_order = std::mem::take(&mut self.error_display_order);
+        fresh.username_ignored_characters = std::mem::take(&mut self.username_ignored_characters);
+        fresh.set_age_required(self.age.required);
+        fresh.max_address_length = self.max_address_length;
+        fresh.cross_validators = std::mem::take(&mut self.cross_validators);
+        fresh.username.take_validators(&mut self.username);
+        fresh.email.take_validators(&mut self.email);
+        fresh.age.take_validators(&mut self.age);
+        fresh.address.take_validators(&mut self.address);
+        fresh.date_of_birth.take_validators(&mut self.date_of_birth);
+        fresh.middle_name.take_validators(&mut self.middle_name);
+        fresh
+            .preferred_name
+            .take_validators(&mut self.preferred_name);
+        fresh.dynamic = std::mem::take(&mut self.dynamic);
+        fresh.dynamic.reset();
+        // What was last submitted stays submitted
+        fresh.submitted_inputs = std::mem::take(&mut self.submitted_inputs);
+        *self = fresh;
+    }
+
+    /// Every field, configured `error_display_order` first (repeats
+    /// ignored), then the rest in form order.
+    pub fn error_order(&self) -> Vec<FieldIdent> {
+        let mut order: Vec<FieldIdent> = Vec::with_capacity(FieldIdent::ALL.len());
+        for ident in self.error_display_order.iter().chain(&FieldIdent::ALL) {
+            if !order.contains(ident) {
+                order.push(*ident);
+            }
+        }
+        order
+    }
+
+    pub fn error_summary(&self) -> Vec<FieldError> {
+        self.error_order()
+            .into_iter()
+            .filter_map(|ident| {
+                self.field(ident).display_error().map(|message| FieldError {
+                    ident,
+                    message: message.to_string(),
+                    code: error_code(message).map(String::from),
+                })
+            })
+            .collect()
     }
 
+    /// Not while a field waits on a remote verdict.
     pub fn can_submit(&self) -> bool {
-        self.is_editing && self.is_valid()
+        self.is_editing
+            && self.would_be_valid()
+            && !FieldIdent::ALL
+                .into_iter()
+                .any(|ident| self.field(ident).is_validating())
+    }
+
+    pub fn is_required(&self, ident: FieldIdent) -> bool {
+        self.field(ident).is_required()
+    }
+
+    pub fn required_fields(&self) -> Vec<FieldIdent> {
+        FieldIdent::ALL
+            .into_iter()
+            .filter(|ident| self.is_required(*ident))
+            .collect()
+    }
+
+    pub fn optional_fields(&self) -> Vec<FieldIdent> {
+        FieldIdent::ALL
+            .into_iter()
+            .filter(|ident| !self.is_required(*ident))
+            .collect()
+    }
+
+    pub fn merge_partial(&mut self, partial: PartialSubmission) {
+        if let Some(username) = partial.username {
+            self.username.assign(Username(username));
+        }
+        if let Some(email) = partial.email {
+            self.email.assign(Email(email));
+        }
+        if let Some(age) = partial.age {
+            self.age.assign(age);
+            self.age.raw_input = None;
+        }
+        if let Some(address) = partial.address {
+            self.address.assign(address);
+        }
+        if let Some(middle_name) = partial.middle_name {
+            self.middle_name.assign(OptionalText(middle_name));
+        }
+        if let Some(preferred_name) = partial.preferred_name {
+            self.preferred_name.assign(OptionalText(preferred_name));
+        }
+        // Checked like the user's own edits: now, or at the next submit
+        if self.validate_on_change {
+            self.validate_all();
+        }
     }
 }
 
@@ -447,21 +2818,41 @@ impl<T: ToString + Clone + PartialEq + Validatable> ToFieldViewModel for Field<T
             touched: self.touched,
             dirty: self.dirty,
             error: self.error.clone(),
+            error_code: self.error.as_deref().and_then(error_code).map(String::from),
+            errors: self.shown_errors(),
+            display_error: self.display_error.clone(),
             valid: self.valid,
             editing: self.editing,
+            required: self.required,
+            masked: self.masked,
+            undoable: self.previous_value.is_some(),
+            validating: !self.pending_checks.is_empty(),
+            has_server_error: self.server_error.is_some(),
         }
     }
 }
 
-impl ToString for Username {
-    fn to_string(&self) -> String {
-        self.0.clone()
+impl fmt::Display for Username {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Display for OptionalText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Display for PasswordStrength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
     }
 }
 
-impl ToString for Email {
-    fn to_string(&self) -> String {
-        self.0.clone()
+impl fmt::Display for Email {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
     }
 }
 
@@ -474,21 +2865,24 @@ pub struct EmailString(pub Email);
 #[derive(Clone, Debug)]
 pub struct AgeString(pub Option<u32>);
 
-impl ToString for UsernameString {
-    fn to_string(&self) -> String {
-        self.0 .0.clone()
+impl fmt::Display for UsernameString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0 .0)
     }
 }
 
-impl ToString for EmailString {
-    fn to_string(&self) -> String {
-        self.0 .0.clone()
+impl fmt::Display for EmailString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0 .0)
     }
 }
 
-impl ToString for AgeString {
-    fn to_string(&self) -> String {
-        self.0.map_or_else(String::new, |v| v.to_string())
+impl fmt::Display for AgeString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(age) => write!(f, "{age}"),
+            None => Ok(()),
+        }
     }
 }
 
@@ -515,13 +2909,701 @@ mod tests {
         assert!(!handler.get_form().submitted);
     }
 
+    #[test]
+    fn test_submit_commits_the_dirty_baseline() {
+        let mut handler = FormHandler::new();
+        for (ident, value) in [
+            (FieldIdent::Username, "TestUser"),
+            (FieldIdent::Email, "test@example.com"),
+            (FieldIdent::Age, "30"),
+            (FieldIdent::Address, "10 Downing Street"),
+        ] {
+            let _ = handler.handle_update_value(ident, value.to_string());
+        }
+        let _ = handler.handle_submit();
+        let form = handler.get_form();
+        assert!(form.submitted);
+        assert!(!form.is_dirty());
+        assert_eq!(form.age.initial_value, Some(30));
+
+        let _ = handler.handle_edit();
+        let _ = handler.handle_update_value(FieldIdent::Username, "TestUser".to_string());
+        assert!(!handler.get_form().username.dirty);
+        let _ = handler.handle_update_value(FieldIdent::Username, "OtherUser".to_string());
+        let form = handler.get_form();
+        assert!(form.is_dirty());
+        assert_eq!(form.dirty_fields(), vec![FieldIdent::Username]);
+        assert_eq!(form.dirty_field_count(), 1);
+    }
+
     #[test]
     fn test_form_handler_reset() {
         let mut handler = FormHandler::new();
-        handler.handle_update_value(FieldIdent::Username, "TestUser".to_string());
+        let _ = handler.handle_update_value(FieldIdent::Username, "TestUser".to_string());
         let mut cmd = handler.handle_reset();
         let effect = cmd.effects().next().unwrap();
         assert!(matches!(effect, Effect::Render(_)));
         assert_eq!(handler.get_form().username.value.0, "");
     }
+
+    #[test]
+    fn test_field_undo_restores_one_change() {
+        let mut field = Field::new(Email(String::new()), true);
+        assert!(!field.undo(), "nothing to undo before an edit");
+
+        field.set_value(Email("a@example.com".to_string()));
+        field.set_value(Email("b@example".to_string()));
+        assert!(!field.valid);
+        assert!(field.undo());
+        assert_eq!(field.value.0, "a@example.com");
+        assert!(field.valid);
+        assert!(field.dirty);
+        assert!(!field.undo(), "only the last change is kept");
+
+        let mut handler = FormHandler::new();
+        let _ = handler.handle_update_value(FieldIdent::Username, "TestUser".to_string());
+        let _ = handler.handle_reset();
+        assert!(
+            !handler
+                .get_form()
+                .username
+                .view_model(|v| v.0.clone())
+                .undoable
+        );
+        let mut cmd = handler.handle_undo_field(FieldIdent::Username);
+        assert!(cmd.effects().next().is_none());
+        assert_eq!(handler.get_form().username.value.0, "");
+    }
+
+    #[test]
+    fn test_validators_compose_before_and_after_construction() {
+        let at_least = |min: usize| {
+            move |text: &OptionalText| {
+                (text.0.len() >= min)
+                    .then_some(())
+                    .ok_or_else(|| format!("At least {min}"))
+            }
+        };
+        let at_most = |max: usize| {
+            move |text: &OptionalText| {
+                (text.0.len() <= max)
+                    .then_some(())
+                    .ok_or_else(|| format!("At most {max}"))
+            }
+        };
+        let check = |field: &mut Field<OptionalText>, text: &str| {
+            field.value = OptionalText(text.to_string());
+            field.validate();
+            field.error.clone()
+        };
+
+        let mut field = Field::new(OptionalText::default(), false);
+        assert_eq!(check(&mut field, ""), None);
+        assert_eq!(check(&mut field, "anything at all"), None);
+
+        let mut field = Field::new(OptionalText::default(), false)
+            .with_validator(at_least(2))
+            .with_validator(at_most(4));
+        assert_eq!(check(&mut field, "abc"), None);
+        assert_eq!(check(&mut field, "a"), Some("At least 2".to_string()));
+        assert_eq!(check(&mut field, "abcde"), Some("At most 4".to_string()));
+
+        // Takes effect from the next check
+        field.add_validator(|text: &OptionalText| {
+            (!text.0.contains('x'))
+                .then_some(())
+                .ok_or_else(|| "No x".to_string())
+        });
+        assert!(!field.validated);
+        assert_eq!(check(&mut field, "abx"), Some("No x".to_string()));
+    }
+
+    #[test]
+    fn test_added_validators_accumulate_errors_in_order() {
+        let mut field = Field::new(OptionalText::default(), false);
+        field.add_validator(|text: &OptionalText| {
+            (text.0.chars().count() >= 8)
+                .then_some(())
+                .ok_or_else(|| "Too short".to_string())
+        });
+        field.add_validator(|text: &OptionalText| {
+            text.0
+                .contains(|c: char| c.is_ascii_digit())
+                .then_some(())
+                .ok_or_else(|| "Missing a digit".to_string())
+        });
+
+        field.set_value(OptionalText("secret".to_string()));
+        assert!(!field.valid);
+        assert_eq!(field.errors, vec!["Too short", "Missing a digit"]);
+        assert_eq!(field.error.as_deref(), Some("Too short"));
+        assert_eq!(field.to_field_view_model().errors, field.errors);
+
+        field.set_value(OptionalText("secret-phrase".to_string()));
+        assert_eq!(field.errors, vec!["Missing a digit"]);
+        field.set_value(OptionalText("secret-phrase-1".to_string()));
+        assert!(field.valid);
+        assert!(field.errors.is_empty());
+        assert_eq!(field.error, None);
+
+        let mut form = Form {
+            middle_name: field,
+            ..Default::default()
+        };
+        form.reset();
+        form.set_input(FieldIdent::MiddleName, "x".to_string());
+        form.validate_all();
+        assert_eq!(
+            form.middle_name.errors,
+            vec!["Too short", "Missing a digit"]
+        );
+    }
+
+    #[test]
+    fn test_cross_validator_needs_valid_fields_and_follows_either_field() {
+        let mut handler = FormHandler::new();
+        handler.add_cross_validator(address_without_username());
+        let address_error = |handler: &FormHandler| handler.get_form().address.error.clone();
+        let cross_error = Some(ADDRESS_CONTAINS_USERNAME_MESSAGE.to_string());
+
+        let _ = handler.handle_update_value(FieldIdent::Address, "1 Alice Street".to_string());
+        assert_eq!(address_error(&handler), None, "no username yet");
+        let _ = handler.handle_update_value(FieldIdent::Username, "alice".to_string());
+        assert_eq!(address_error(&handler), cross_error);
+        assert!(!handler.get_form().is_valid());
+
+        // Changing the other field clears it
+        let _ = handler.handle_update_value(FieldIdent::Username, "bob".to_string());
+        assert_eq!(address_error(&handler), None);
+        assert!(handler.get_form().address.valid);
+
+        let _ = handler.handle_update_value(FieldIdent::Username, "alice".to_string());
+        assert_eq!(address_error(&handler), cross_error);
+        let _ = handler.handle_update_value(FieldIdent::Address, "2 Baker Street".to_string());
+        assert_eq!(address_error(&handler), None);
+
+        // An invalid field keeps its own error
+        let _ = handler.handle_update_value(FieldIdent::Username, "al".to_string());
+        let _ = handler.handle_update_value(FieldIdent::Address, "1 al street".to_string());
+        assert!(!handler.get_form().username.valid);
+        assert_eq!(address_error(&handler), None);
+
+        // Validators survive a reset
+        let _ = handler.handle_reset();
+        let _ = handler.handle_update_value(FieldIdent::Username, "alice".to_string());
+        let _ = handler.handle_update_value(FieldIdent::Address, "1 Alice Street".to_string());
+        assert_eq!(address_error(&handler), cross_error);
+    }
+
+    #[test]
+    fn test_email_rule_is_the_pattern_everywhere() {
+        let pattern = Regex::new(EMAIL_PATTERN).unwrap();
+        let Some(ValidatorSpec::Pattern { pattern: described }) =
+            Email::validators().into_iter().next()
+        else {
+            panic!("email is described by its pattern");
+        };
+        assert_eq!(described, EMAIL_PATTERN);
+
+        // All of these contain '@' and '.', which was once enough
+        for (input, valid) in [
+            ("user@example.com", true),
+            ("first.last+tag@mail.example.co", true),
+            ("user@example.", false),
+            ("@example.com", false),
+            ("user@.com", false),
+            ("first last@example.com", false),
+            ("user@example.c", false),
+            ("a.b@c", false),
+        ] {
+            assert_eq!(pattern.is_match(input), valid, "{input}");
+            assert_eq!(Email(input.to_string()).is_valid(), valid, "{input}");
+
+            let mut handler = FormHandler::new();
+            let _ = handler.handle_update_value(FieldIdent::Email, input.to_string());
+            let email = &handler.get_form().email;
+            assert_eq!(email.valid, valid, "{input}");
+            assert_eq!(email.error.is_none(), valid, "{input}");
+
+            // A restore validates without going through the handler
+            let mut restored = Form::default();
+            restored.restore_snapshot(handler.get_form().snapshot());
+            assert_eq!(restored.email.valid, valid, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_merge_partial_updates_only_provided_fields() {
+        let mut handler = FormHandler::new();
+        let _ = handler.handle_update_value(FieldIdent::Username, "TestUser".to_string());
+
+        handler.form.merge_partial(PartialSubmission {
+            address: Some("221B Baker Street, London, NW1 6XE UK".to_string()),
+            ..Default::default()
+        });
+
+        let form = handler.get_form();
+        assert_eq!(form.address.value, "221B Baker Street, London, NW1 6XE UK");
+        assert!(form.address.valid);
+        assert!(form.address.dirty);
+        assert_eq!(form.username.value.0, "TestUser");
+        assert_eq!(form.email.value.0, "");
+        assert!(!form.email.valid);
+        assert_eq!(form.age.value, None);
+    }
+
+    #[test]
+    fn test_merge_partial_waits_for_deferred_validation() {
+        let mut handler = FormHandler::new();
+        handler.set_validate_on_change(false);
+        handler.form.merge_partial(PartialSubmission {
+            email: Some("bob@".to_string()),
+            ..Default::default()
+        });
+
+        let email = &handler.get_form().email;
+        assert_eq!(email.value.0, "bob@");
+        assert!(email.dirty);
+        assert!(!email.validated);
+        assert_eq!(email.error, None);
+
+        handler.form.validate_all();
+        assert!(!handler.get_form().email.valid);
+    }
+
+    #[test]
+    fn test_tick_past_birthday_flips_date_of_birth_validity() {
+        let mut handler = FormHandler::new();
+        let _ = handler.handle_update_value(FieldIdent::DateOfBirth, "2000-06-15".to_string());
+        assert!(handler.get_form().date_of_birth.valid);
+
+        let mut cmd = handler.handle_tick(NaiveDate::from_ymd_opt(2018, 6, 14).unwrap());
+        let effect = cmd.effects().next().unwrap();
+        assert!(matches!(effect, Effect::Render(_)));
+        assert!(!handler.get_form().date_of_birth.valid);
+        assert_eq!(
+            handler.get_form().date_of_birth.error,
+            Some("You must be at least 18 years old".to_string())
+        );
+        assert!(!handler.get_form().is_valid());
+
+        let mut cmd = handler.handle_tick(NaiveDate::from_ymd_opt(2018, 6, 15).unwrap());
+        let effect = cmd.effects().next().unwrap();
+        assert!(matches!(effect, Effect::Render(_)));
+        assert!(handler.get_form().date_of_birth.valid);
+        assert_eq!(handler.get_form().date_of_birth.error, None);
+
+        // nothing time-dependent changed, so no render
+        let mut cmd = handler.handle_tick(NaiveDate::from_ymd_opt(2018, 6, 16).unwrap());
+        assert!(cmd.effects().next().is_none());
+    }
+
+    #[test]
+    fn test_tick_renders_a_changed_date_of_birth_error() {
+        let mut handler = FormHandler::new();
+        let _ = handler.handle_tick(NaiveDate::from_ymd_opt(2018, 6, 14).unwrap());
+        let _ = handler.handle_update_value(FieldIdent::DateOfBirth, "2018-06-15".to_string());
+        assert_eq!(
+            handler.get_form().date_of_birth.error.as_deref(),
+            Some("Date of birth cannot be in the future")
+        );
+
+        let mut cmd = handler.handle_tick(NaiveDate::from_ymd_opt(2018, 6, 16).unwrap());
+        assert!(matches!(cmd.effects().next(), Some(Effect::Render(_))));
+        assert_eq!(
+            handler.get_form().date_of_birth.error.as_deref(),
+            Some("You must be at least 18 years old")
+        );
+    }
+
+    #[test]
+    fn test_date_of_birth_that_is_not_a_date_is_an_error() {
+        let mut handler = FormHandler::new();
+        for input in ["abc", "2000-13-45"] {
+            let _ = handler.handle_set_field_editing(FieldIdent::DateOfBirth, true);
+            let _ = handler.handle_update_value(FieldIdent::DateOfBirth, input.to_string());
+            let _ = handler.handle_set_field_editing(FieldIdent::DateOfBirth, false);
+            let date_of_birth = &handler.get_form().date_of_birth;
+            assert!(!date_of_birth.valid, "{input}");
+            assert_eq!(
+                date_of_birth.error.as_deref(),
+                Some(DATE_OF_BIRTH_NOT_A_DATE_MESSAGE),
+                "{input}"
+            );
+            assert_eq!(handler.view().date_of_birth.value, input, "kept to correct");
+        }
+
+        for input in ["", "2000-01-31"] {
+            let _ = handler.handle_update_value(FieldIdent::DateOfBirth, input.to_string());
+            assert!(handler.get_form().date_of_birth.valid, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_age_echoes_raw_input_while_editing() {
+        let mut handler = FormHandler::new();
+        let _ = handler.handle_set_field_editing(FieldIdent::Age, true);
+        let _ = handler.handle_update_value(FieldIdent::Age, "05".to_string());
+
+        assert_eq!(handler.view().age.value, "05");
+        assert_eq!(handler.get_form().age.value, Some(5));
+
+        let _ = handler.handle_set_field_editing(FieldIdent::Age, false);
+
+        assert_eq!(handler.view().age.value, "5");
+        assert_eq!(handler.get_form().age.value, Some(5));
+
+        let _ = handler.handle_set_field_editing(FieldIdent::Age, true);
+        let _ = handler.handle_update_value(FieldIdent::Age, "25a".to_string());
+        let _ = handler.handle_touch_field(FieldIdent::Age);
+        assert_eq!(handler.view().age.value, "25a", "kept across renders");
+        assert_eq!(handler.get_form().age.value, Some(25));
+
+        let _ = handler.handle_set_field_editing(FieldIdent::Age, false);
+        assert_eq!(handler.view().age.value, "25");
+    }
+
+    #[test]
+    fn test_required_and_optional_fields() {
+        let form = Form::default();
+        assert_eq!(
+            form.required_fields(),
+            vec![FieldIdent::Username, FieldIdent::Email, FieldIdent::Address]
+        );
+        assert_eq!(
+            form.optional_fields(),
+            vec![
+                FieldIdent::Age,
+                FieldIdent::DateOfBirth,
+                FieldIdent::MiddleName,
+                FieldIdent::PreferredName
+            ]
+        );
+        assert!(!FormHandler::new().view().age.required);
+    }
+
+    #[test]
+    fn test_optional_name_fields() {
+        let mut handler = FormHandler::new();
+        for (ident, value) in [
+            (FieldIdent::Username, "TestUser"),
+            (FieldIdent::Email, "test@example.com"),
+            (FieldIdent::Address, "10 Downing Street"),
+        ] {
+            let _ = handler.handle_update_value(ident, value.to_string());
+        }
+
+        let _ = handler.handle_update_value(FieldIdent::PreferredName, "Tess".to_string());
+        let _ = handler.handle_touch_field(FieldIdent::MiddleName);
+        let view = handler.view();
+        assert_eq!(view.preferred_name.value, "Tess");
+        assert!(view.preferred_name.dirty);
+        assert!(view.middle_name.touched);
+        assert!(!view.middle_name.dirty);
+        assert!(view.middle_name.valid);
+
+        let _ = handler.handle_update_value(FieldIdent::PreferredName, String::new());
+        assert!(!handler.view().preferred_name.dirty);
+
+        let _ = handler.handle_submit();
+        assert!(handler.get_form().submitted);
+
+        let _ = handler.handle_edit();
+        let _ = handler.handle_update_value(FieldIdent::MiddleName, "Anne".to_string());
+        let _ = handler.handle_reset();
+        let view = handler.view();
+        assert_eq!(view.middle_name.value, "");
+        assert!(!view.middle_name.dirty);
+        assert!(!view.middle_name.touched);
+    }
+
+    #[test]
+    fn test_username_policies() {
+        let mut handler = FormHandler::new();
+        let _ = handler.handle_update_value(FieldIdent::Username, "Jane   D ".to_string());
+        assert!(handler.get_form().username.valid);
+        let _ = handler.handle_set_field_editing(FieldIdent::Username, false);
+        assert_eq!(handler.get_form().username.value.0, "Jane D");
+        assert_eq!(handler.view().username_policy, UsernamePolicy::Display);
+
+        // Switching re-checks the value already entered
+        handler.set_username_policy(UsernamePolicy::Handle);
+        let username = &handler.get_form().username;
+        assert!(!username.valid);
+        assert_eq!(
+            username.error.as_deref(),
+            Some("Username cannot contain spaces")
+        );
+        let view = handler.view();
+        assert_eq!(view.username_policy, UsernamePolicy::Handle);
+        assert_eq!(view.username.error_code.as_deref(), Some("username.spaces"));
+
+        let _ = handler.handle_update_value(FieldIdent::Username, "Jane-D".to_string());
+        assert_eq!(
+            handler.get_form().username.error.as_deref(),
+            Some("Username may only contain letters, numbers and underscores")
+        );
+        let _ = handler.handle_touch_field(FieldIdent::Username);
+        let view = handler.view();
+        assert_eq!(
+            view.username.error_code.as_deref(),
+            Some("username.symbols")
+        );
+        assert_eq!(
+            view.error_summary[0].code.as_deref(),
+            Some("username.symbols")
+        );
+
+        let _ = handler.handle_update_value(FieldIdent::Username, "Jane_D".to_string());
+        assert!(handler.get_form().username.valid);
+        let _ = handler.handle_set_field_editing(FieldIdent::Username, false);
+        assert_eq!(handler.get_form().username.value.0, "jane_d");
+
+        handler.set_username_policy(UsernamePolicy::Display);
+        let _ = handler.handle_update_value(FieldIdent::Username, "Jane D".to_string());
+        assert!(handler.get_form().username.valid);
+
+        // While validation is deferred, the switch waits for it
+        handler.set_validate_on_change(false);
+        handler.set_username_policy(UsernamePolicy::Handle);
+        let username = &handler.get_form().username;
+        assert!(!username.validated);
+        assert_eq!(username.error, None);
+        handler.set_validate_on_change(true);
+        assert_eq!(
+            handler.get_form().username.error.as_deref(),
+            Some(USERNAME_HAS_SPACES_MESSAGE)
+        );
+    }
+
+    #[test]
+    fn test_validation_deferred_until_submit() {
+        let mut handler = FormHandler::new();
+        handler.set_validate_on_change(false);
+        assert!(handler.get_form().email.error.is_none());
+        // Errors are hidden, but the empty form would not pass a submit
+        assert!(handler.get_form().username.valid);
+        assert!(!handler.view().can_submit);
+
+        let mut cmd = handler.handle_update_value(FieldIdent::Email, "not-an-email".to_string());
+        assert!(matches!(cmd.effects().next(), Some(Effect::Render(_))));
+        let _ = handler.handle_touch_field(FieldIdent::Email);
+        let email = &handler.get_form().email;
+        assert_eq!(email.value.0, "not-an-email");
+        assert!(email.dirty);
+        assert!(email.touched);
+        assert!(email.valid);
+        assert_eq!(email.error, None);
+
+        let _ = handler.handle_submit();
+        let form = handler.get_form();
+        assert!(!form.submitted);
+        assert!(!form.email.valid);
+        assert_eq!(
+            form.email.error.as_deref(),
+            Some("Please enter a valid email address (e.g. user@example.com)")
+        );
+
+        for (ident, value) in [
+            (FieldIdent::Username, "TestUser"),
+            (FieldIdent::Email, "test@example.com"),
+            (FieldIdent::Address, "10 Downing Street"),
+        ] {
+            let _ = handler.handle_update_value(ident, value.to_string());
+        }
+        assert!(handler.view().can_submit);
+
+        let _ = handler.handle_reset();
+        assert!(!handler.get_form().validate_on_change);
+        assert_eq!(handler.get_form().username.error, None);
+        assert!(!handler.view().can_submit);
+    }
+
+    #[test]
+    fn test_status_truth_table() {
+        use FormStatus::*;
+        // (submitted, is_editing, any dirty, all valid) => status
+        let table = [
+            ((false, false, false, false), ViewOnly),
+            ((false, false, false, true), ViewOnly),
+            ((false, false, true, false), ViewOnly),
+            ((false, false, true, true), ViewOnly),
+            ((false, true, false, false), Invalid),
+            ((false, true, false, true), Ready),
+            ((false, true, true, false), UnsavedChanges),
+            ((false, true, true, true), UnsavedChanges),
+            ((true, false, false, false), Submitted),
+            ((true, false, false, true), Submitted),
+            ((true, false, true, false), Submitted),
+            ((true, false, true, true), Submitted),
+            ((true, true, false, false), Invalid),
+            ((true, true, false, true), Ready),
+            ((true, true, true, false), UnsavedChanges),
+            ((true, true, true, true), UnsavedChanges),
+        ];
+
+        for ((submitted, editing, dirty, valid), expected) in table {
+            let mut form = Form::default();
+            for (ident, value) in [
+                (FieldIdent::Username, "TestUser"),
+                (FieldIdent::Email, "test@example.com"),
+                (FieldIdent::Age, "30"),
+                (FieldIdent::Address, "10 Downing Street"),
+            ] {
+                form.set_input(ident, value.to_string());
+            }
+            form.validate_all();
+            form.username.dirty = dirty;
+            form.email.dirty = false;
+            form.age.dirty = false;
+            form.address.dirty = false;
+            form.email.valid = valid;
+            form.submitted = submitted;
+            form.set_editing(editing);
+            assert_eq!(form.is_dirty(), dirty);
+            assert_eq!(form.is_valid(), valid);
+
+            assert_eq!(
+                form.status(),
+                expected,
+                "submitted={submitted} editing={editing} dirty={dirty} valid={valid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_field_accepts_updates() {
+        let mut handler = FormHandler::new();
+        for ident in FieldIdent::ALL {
+            let value = match ident {
+                FieldIdent::Username => "TestUser",
+                FieldIdent::Email => "test@example.com",
+                FieldIdent::Age => "30",
+                FieldIdent::Address => "10 Downing Street",
+                FieldIdent::DateOfBirth => "1990-01-01",
+                FieldIdent::MiddleName => "Jo",
+                FieldIdent::PreferredName => "Sam",
+            };
+            let mut cmd = handler.handle_update_value(ident, value.to_string());
+            assert!(
+                cmd.effects()
+                    .any(|effect| matches!(effect, Effect::Render(_))),
+                "{ident:?} did not render"
+            );
+            let form = handler.get_form();
+            assert_eq!(form.input(ident), value, "{ident:?} lost its value");
+            assert!(form.field(ident).is_dirty(), "{ident:?} is not dirty");
+            assert!(form.field(ident).is_valid(), "{ident:?} is not valid");
+        }
+        assert_eq!(
+            handler.get_form().dirty_field_count(),
+            FieldIdent::ALL.len()
+        );
+    }
+
+    #[test]
+    fn test_validate_all_skips_unchanged_fields() {
+        let mut form = Form::default();
+        form.set_input(FieldIdent::Email, "test@example.com".to_string());
+        form.set_input(FieldIdent::Address, "10 Downing Street".to_string());
+        form.set_input(FieldIdent::Username, "TestUser".to_string());
+        form.set_input(FieldIdent::Age, "30".to_string());
+        form.set_input(FieldIdent::DateOfBirth, "1990-01-01".to_string());
+        form.today = NaiveDate::from_ymd_opt(2024, 1, 1);
+        form.validate_all();
+
+        // A planted result survives only as long as nothing re-validates
+        // it, the contextual rules of the username, age and date of birth
+        // included
+        let stale = Some("stale".to_string());
+        form.address.error = stale.clone();
+        form.username.error = stale.clone();
+        form.age.error = stale.clone();
+        form.date_of_birth.error = stale.clone();
+        form.set_input(FieldIdent::Email, "not an email".to_string());
+        form.validate_all();
+        for ident in [
+            FieldIdent::Address,
+            FieldIdent::Username,
+            FieldIdent::Age,
+            FieldIdent::DateOfBirth,
+        ] {
+            assert_eq!(form.field(ident).error(), stale.as_deref(), "{ident:?}");
+        }
+        assert!(!form.email.valid);
+
+        // Until their inputs change
+        form.set_username_policy(UsernamePolicy::Handle);
+        form.set_age_required(true);
+        assert_eq!(form.username.error, None);
+        assert_eq!(form.age.error, None);
+        form.set_today(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+        assert_eq!(form.date_of_birth.error, None);
+
+        form.revalidate_all();
+        assert_eq!(form.address.error, None);
+    }
+
+    #[test]
+    fn test_username_normalized_for_comparison() {
+        let mut form = Form::default();
+        form.set_input(FieldIdent::Username, "John.Doe".to_string());
+        assert_eq!(form.username_normalized(), "johndoe");
+        assert_eq!(form.input(FieldIdent::Username), "John.Doe");
+
+        form.username_ignored_characters = "._-".to_string();
+        form.set_input(FieldIdent::Username, "J_O-H.N".to_string());
+        assert_eq!(form.username_normalized(), "john");
+    }
+
+    #[test]
+    fn test_weak_password_reports_every_missing_requirement() {
+        let weak = Field::new(PasswordStrength("abc".to_string()), true);
+        let view = weak.view_model(|v| v.0.clone());
+        assert!(!view.valid);
+        assert_eq!(
+            view.errors,
+            vec![
+                "Password must be at least 8 characters",
+                "Password must contain a number",
+                "Password must contain an uppercase letter",
+            ]
+        );
+        assert_eq!(view.error.as_ref(), view.errors.first());
+
+        let empty = Field::new(PasswordStrength::default(), true);
+        assert_eq!(
+            empty.view_model(|v| v.0.clone()).errors,
+            vec!["Password cannot be empty"]
+        );
+        let strong = Field::new(PasswordStrength("Corr3ct horse".to_string()), true);
+        assert!(strong.view_model(|v| v.0.clone()).errors.is_empty());
+
+        // Single-error fields list just their error
+        let mut handler = FormHandler::new();
+        let _ = handler.handle_update_value(FieldIdent::Email, "not an email".to_string());
+        let email = handler.view().email;
+        assert_eq!(email.errors, email.error.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_toggle_field_mask_changes_only_the_flag() {
+        let mut handler = FormHandler::new();
+        let _ = handler.handle_update_value(FieldIdent::Email, "not an email".to_string());
+        let before = handler.view().email;
+
+        let _ = handler.handle_toggle_field_mask(FieldIdent::Email);
+        let masked = handler.view().email;
+        assert!(masked.masked);
+        assert_eq!(
+            FieldViewModel {
+                masked: false,
+                ..masked
+            },
+            before
+        );
+
+        let _ = handler.handle_toggle_field_mask(FieldIdent::Email);
+        assert_eq!(handler.view().email, before);
+    }
 }
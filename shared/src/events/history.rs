@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::events::form::{FieldIdent, Form};
+
+/// Undo steps kept; the oldest is dropped beyond this.
+pub const UNDO_LIMIT: usize = 50;
+
+/// A pause in typing this long, in milliseconds, ends a run of edits.
+pub const UNDO_WINDOW_MS: u64 = 1_000;
+
+/// The text of every field, in `FieldIdent::ALL` order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FormInputs(Vec<String>);
+
+impl FormInputs {
+    pub fn capture(form: &Form) -> Self {
+        Self(
+            FieldIdent::ALL
+                .into_iter()
+                .map(|ident| form.input(ident))
+                .collect(),
+        )
+    }
+
+    pub fn into_fields(self) -> impl Iterator<Item = (FieldIdent, String)> {
+        FieldIdent::ALL.into_iter().zip(self.0)
+    }
+}
+
+/// Form-wide undo and redo of field values.
+///
+/// A run of edits to one field is a single step. The run ends when
+/// another field is edited, the field loses focus, typing pauses for the
+/// undo window, or on undo/redo.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct FormHistory {
+    undo: VecDeque<FormInputs>,
+    redo: Vec<FormInputs>,
+    /// Field whose edits currently coalesce into the last undo step
+    run: Option<FieldIdent>,
+    /// Edits recorded, which number the undo window timers
+    #[serde(default)]
+    edits: u64,
+}
+
+impl FormHistory {
+    /// Records an edit to `ident` that changed the form from `before`, and
+    /// returns its number for `end_run_after`.
+    pub fn record(&mut self, ident: FieldIdent, before: FormInputs) -> u64 {
+        self.edits += 1;
+        self.redo.clear();
+        if self.run == Some(ident) {
+            return self.edits;
+        }
+        self.run = Some(ident);
+        self.undo.push_back(before);
+        if self.undo.len() > UNDO_LIMIT {
+            self.undo.pop_front();
+        }
+        self.edits
+    }
+
+    /// Ends the current run unless another edit followed edit `edit`.
+    pub fn end_run_after(&mut self, edit: u64) {
+        if self.edits == edit {
+            self.run = None;
+        }
+    }
+
+    /// Ends the current run if it belongs to `ident`.
+    pub fn end_run(&mut self, ident: FieldIdent) {
+        if self.run == Some(ident) {
+            self.run = None;
+        }
+    }
+
+    /// The inputs to restore, given the form currently holds `current`.
+    pub fn undo(&mut self, current: FormInputs) -> Option<FormInputs> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        self.run = None;
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: FormInputs) -> Option<FormInputs> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current);
+        self.run = None;
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Keeps counting edits, so a window timer from before does not end a
+    /// later run.
+    pub fn clear(&mut self) {
+        *self = Self {
+            edits: self.edits,
+            ..Self::default()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(username: &str) -> FormInputs {
+        let mut form = Form::default();
+        form.set_input(FieldIdent::Username, username.to_string());
+        FormInputs::capture(&form)
+    }
+
+    #[test]
+    fn test_same_field_edits_coalesce_until_the_run_ends() {
+        let mut history = FormHistory::default();
+        history.record(FieldIdent::Username, inputs(""));
+        history.record(FieldIdent::Username, inputs("a"));
+        history.record(FieldIdent::Username, inputs("ab"));
+        assert_eq!(history.undo.len(), 1);
+
+        history.end_run(FieldIdent::Email);
+        history.record(FieldIdent::Username, inputs("abc"));
+        assert_eq!(history.undo.len(), 1);
+
+        history.end_run(FieldIdent::Username);
+        history.record(FieldIdent::Username, inputs("abcd"));
+        assert_eq!(history.undo.len(), 2);
+
+        assert_eq!(history.undo(inputs("abcde")), Some(inputs("abcd")));
+        assert_eq!(history.undo(inputs("abcd")), Some(inputs("")));
+        assert_eq!(history.undo(inputs("")), None);
+    }
+
+    #[test]
+    fn test_a_pause_in_typing_ends_the_run() {
+        let mut history = FormHistory::default();
+        let first = history.record(FieldIdent::Username, inputs(""));
+        let second = history.record(FieldIdent::Username, inputs("a"));
+
+        // Stale: an edit followed within the window
+        history.end_run_after(first);
+        history.record(FieldIdent::Username, inputs("ab"));
+        assert_eq!(history.undo.len(), 1);
+
+        history.end_run_after(second + 1);
+        history.record(FieldIdent::Username, inputs("abc"));
+        assert_eq!(history.undo.len(), 2);
+
+        // Cleared, the count goes on, so an old timer cannot end a new run
+        history.clear();
+        let after_clear = history.record(FieldIdent::Username, inputs(""));
+        assert!(after_clear > second);
+        history.end_run_after(second);
+        history.record(FieldIdent::Username, inputs("a"));
+        assert_eq!(history.undo.len(), 1);
+    }
+
+    #[test]
+    fn test_new_edit_invalidates_redo() {
+        let mut history = FormHistory::default();
+        history.record(FieldIdent::Username, inputs(""));
+        assert_eq!(history.undo(inputs("a")), Some(inputs("")));
+        assert!(history.can_redo());
+
+        history.record(FieldIdent::Email, inputs(""));
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(inputs("")), None);
+    }
+
+    #[test]
+    fn test_undo_stack_is_bounded() {
+        let mut history = FormHistory::default();
+        for n in 0..UNDO_LIMIT + 10 {
+            history.end_run(FieldIdent::Username);
+            history.record(FieldIdent::Username, inputs(&n.to_string()));
+        }
+        assert_eq!(history.undo.len(), UNDO_LIMIT);
+        assert_eq!(history.undo.front(), Some(&inputs("10")));
+    }
+}
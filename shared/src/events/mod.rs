@@ -1,5 +1,19 @@
 pub mod address;
+pub mod builder;
+pub mod diagnostics;
+pub mod draft;
+pub mod dynamic;
 pub mod form;
+pub mod history;
+pub mod retry;
+pub mod signals;
+pub mod snapshot;
+pub mod validators;
 
 pub use address::*;
+pub use diagnostics::*;
+pub use draft::*;
 pub use form::*;
+pub use history::*;
+pub use retry::*;
+pub use signals::*;
@@ -0,0 +1,147 @@
+use crux_core::command::CommandContext;
+use crux_http::HttpError;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+use crate::app::{Effect, Event};
+use crate::capabilities::timer::{TimerKind, TimerOperation};
+use crate::config::RetryPolicy;
+
+/// Pseudo-random source spreading retry delays, so that clients failing
+/// together do not retry together. Seeded from `AppConfig::random_seed`,
+/// which makes every schedule reproducible, or else from the shell's
+/// random source.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Jitter {
+    state: u64,
+}
+
+impl Jitter {
+    pub fn seeded(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// SplitMix64
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// The delays before each retry `policy` allows, in milliseconds. Each
+    /// is the retry's backoff plus up to `jitter_ms` more.
+    pub fn schedule(&mut self, policy: &RetryPolicy) -> Vec<u64> {
+        (0..policy.max_retries)
+            .map(|retry| {
+                let spread = match policy.jitter_ms.checked_add(1) {
+                    Some(window) => self.next_u64() % window,
+                    None => self.next_u64(),
+                };
+                policy.backoff(retry).saturating_add(spread)
+            })
+            .collect()
+    }
+}
+
+/// Whether trying the same request again may succeed.
+pub fn is_retryable(error: &HttpError) -> bool {
+    match error {
+        HttpError::Io(_) | HttpError::Timeout => true,
+        HttpError::Http { code, .. } => code.is_server_error(),
+        HttpError::Json(_) | HttpError::Url(_) => false,
+    }
+}
+
+/// The waits before each retry of one fetch.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Backoff {
+    /// Shared by the fetch's retry timers, which never overlap
+    pub timer_id: u64,
+    pub delays: Vec<u64>,
+}
+
+impl Backoff {
+    /// The backoff of the fetch after the `fetches` so far, which it counts.
+    pub fn next(fetches: &mut u64, delays: Vec<u64>) -> Self {
+        *fetches += 1;
+        Self {
+            timer_id: TimerKind::RetryBackoff.id(*fetches),
+            delays,
+        }
+    }
+}
+
+/// Runs `attempt`, and again after each of the `backoff` delays while it
+/// fails with a retryable error. Returns the last outcome.
+pub async fn retrying<T, Fut>(
+    ctx: &CommandContext<Effect, Event>,
+    backoff: &Backoff,
+    mut attempt: impl FnMut() -> Fut,
+) -> Result<T, HttpError>
+where
+    Fut: Future<Output = Result<T, HttpError>>,
+{
+    let mut delays = backoff.delays.iter();
+    loop {
+        match attempt().await {
+            Err(error) if is_retryable(&error) => match delays.next() {
+                Some(&millis) => {
+                    ctx.request_from_shell(TimerOperation::Start {
+                        id: backoff.timer_id,
+                        millis,
+                    })
+                    .await;
+                }
+                None => return Err(error),
+            },
+            outcome => return outcome,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_is_fixed_by_seed() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            base_delay_ms: 100,
+            jitter_ms: 50,
+        };
+        let schedule = Jitter::seeded(7).schedule(&policy);
+        // Backoffs of 100, 200 and 400 plus jitter
+        assert_eq!(schedule, vec![100, 224, 412]);
+        assert_eq!(Jitter::seeded(7).schedule(&policy), schedule);
+        assert_ne!(Jitter::seeded(8).schedule(&policy), schedule);
+    }
+
+    #[test]
+    fn test_schedule_stays_within_bounds() {
+        let mut policies = Jitter::seeded(0);
+        for seed in 0..500 {
+            let policy = RetryPolicy {
+                max_retries: (policies.next_u64() % 8) as u32,
+                base_delay_ms: policies.next_u64() % 2_000,
+                jitter_ms: match seed % 3 {
+                    0 => 0,
+                    1 => policies.next_u64() % 1_000,
+                    _ => u64::MAX,
+                },
+            };
+            let schedule = Jitter::seeded(seed).schedule(&policy);
+            assert_eq!(schedule.len(), policy.max_retries as usize);
+            for (retry, delay) in (0..).zip(schedule) {
+                let low = policy.backoff(retry);
+                let high = low.saturating_add(policy.jitter_ms);
+                assert!(
+                    (low..=high).contains(&delay),
+                    "{delay} outside {low}..={high} for {policy:?}"
+                );
+            }
+        }
+    }
+}
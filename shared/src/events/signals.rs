@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+use crate::events::draft::DraftWarning;
+use crate::events::form::FieldIdent;
+
+/// Something the shell should act on once, such as moving the caret.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Signal {
+    /// The core rewrote the value of `ident`; put the caret at `position`,
+    /// in characters
+    Caret { ident: FieldIdent, position: u32 },
+    /// The last draft was saved without some fields because it was too large
+    DraftTruncated(DraftWarning),
+}
+
+impl Signal {
+    /// Whether `self` makes `older` obsolete: a later caret for the same
+    /// field, or a later draft warning.
+    fn replaces(&self, older: &Signal) -> bool {
+        match (self, older) {
+            (Signal::Caret { ident, .. }, Signal::Caret { ident: older, .. }) => ident == older,
+            (Signal::DraftTruncated(_), Signal::DraftTruncated(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RaisedSignal {
+    pub seq: u64,
+    pub signal: Signal,
+}
+
+/// Signals the shell has not acknowledged yet, oldest first. Sequence
+/// numbers only ever grow, so an acknowledgement never covers a signal
+/// raised after the view it was based on.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SignalQueue {
+    last_seq: u64,
+    pending: Vec<RaisedSignal>,
+}
+
+impl SignalQueue {
+    /// Queues `signal`, dropping any pending one it replaces.
+    pub fn raise(&mut self, signal: Signal) -> u64 {
+        self.pending
+            .retain(|raised| !signal.replaces(&raised.signal));
+        self.last_seq += 1;
+        self.pending.push(RaisedSignal {
+            seq: self.last_seq,
+            signal,
+        });
+        self.last_seq
+    }
+
+    /// Drops pending signals that no longer apply. Returns whether any were.
+    pub fn withdraw(&mut self, stale: impl Fn(&Signal) -> bool) -> bool {
+        self.retain(|raised| !stale(&raised.signal))
+    }
+
+    /// Drops the signals up to and including `up_to_seq`. Returns whether
+    /// any were pending.
+    pub fn acknowledge(&mut self, up_to_seq: u64) -> bool {
+        self.retain(|raised| raised.seq > up_to_seq)
+    }
+
+    pub fn clear(&mut self) -> bool {
+        self.retain(|_| false)
+    }
+
+    fn retain(&mut self, keep: impl Fn(&RaisedSignal) -> bool) -> bool {
+        let before = self.pending.len();
+        self.pending.retain(keep);
+        self.pending.len() != before
+    }
+
+    pub fn pending(&self) -> &[RaisedSignal] {
+        &self.pending
+    }
+
+    pub fn last_seq(&self) -> u64 {
+        self.last_seq
+    }
+
+    /// Continues numbering after `last_seq`, e.g. for a queue that replaces
+    /// one the shell has seen.
+    pub fn resume_after(&mut self, last_seq: u64) {
+        self.last_seq = self.last_seq.max(last_seq);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caret(ident: FieldIdent, position: u32) -> Signal {
+        Signal::Caret { ident, position }
+    }
+
+    fn seqs(queue: &SignalQueue) -> Vec<u64> {
+        queue.pending().iter().map(|raised| raised.seq).collect()
+    }
+
+    #[test]
+    fn test_signals_keep_raise_order_and_replace_their_own_kind() {
+        let mut queue = SignalQueue::default();
+        assert_eq!(queue.raise(caret(FieldIdent::Username, 3)), 1);
+        assert_eq!(queue.raise(caret(FieldIdent::Address, 5)), 2);
+        assert_eq!(queue.raise(caret(FieldIdent::Username, 4)), 3);
+        assert_eq!(seqs(&queue), vec![2, 3]);
+        assert_eq!(queue.pending()[1].signal, caret(FieldIdent::Username, 4));
+    }
+
+    #[test]
+    fn test_acknowledgement_clears_only_up_to_its_seq() {
+        let mut queue = SignalQueue::default();
+        for ident in &FieldIdent::ALL[..3] {
+            queue.raise(caret(*ident, 0));
+        }
+        assert!(queue.acknowledge(2));
+        assert_eq!(seqs(&queue), vec![3]);
+        assert!(!queue.acknowledge(2));
+
+        queue.raise(caret(FieldIdent::Username, 1));
+        assert!(queue.acknowledge(3));
+        assert_eq!(seqs(&queue), vec![4]);
+    }
+
+    #[test]
+    fn test_numbering_survives_clearing() {
+        let mut queue = SignalQueue::default();
+        queue.raise(caret(FieldIdent::Username, 1));
+        assert!(queue.clear());
+
+        let mut fresh = SignalQueue::default();
+        fresh.resume_after(queue.last_seq());
+        assert_eq!(fresh.raise(caret(FieldIdent::Email, 1)), 2);
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::events::form::{FieldIdent, Form};
+
+/// One field of a `FormSnapshot`. Values are kept as text, in the form
+/// `Form::input` gives, so an age that is not a number survives.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FieldSnapshot {
+    pub ident: FieldIdent,
+    pub input: String,
+    /// What `dirty` compares against, e.g. the last submitted value
+    pub baseline: String,
+    pub touched: bool,
+    pub dirty: bool,
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+/// The state of the form at one point, to come back to later. Validation
+/// results are informational: restoring validates the values afresh, with
+/// the rules of the form restored into. Server errors are not kept.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FormSnapshot {
+    pub fields: Vec<FieldSnapshot>,
+    pub submitted: bool,
+    pub is_editing: bool,
+}
+
+impl Form {
+    pub fn snapshot(&self) -> FormSnapshot {
+        FormSnapshot {
+            fields: FieldIdent::ALL
+                .into_iter()
+                .map(|ident| {
+                    let field = self.field(ident);
+                    FieldSnapshot {
+                        ident,
+                        input: self.input(ident),
+                        baseline: self.baseline_input(ident),
+                        touched: field.is_touched(),
+                        dirty: field.is_dirty(),
+                        valid: field.is_valid(),
+                        error: field.error().map(str::to_string),
+                    }
+                })
+                .collect(),
+            submitted: self.submitted,
+            is_editing: self.is_editing,
+        }
+    }
+
+    /// Puts the values of `snapshot` back and validates them. Fields missing
+    /// from it are left as they are.
+    pub fn restore_snapshot(&mut self, snapshot: FormSnapshot) {
+        for field in snapshot.fields {
+            self.set_input(field.ident, field.baseline);
+            self.field_mut(field.ident).commit_baseline();
+            self.set_input(field.ident, field.input);
+            self.field_mut(field.ident).set_touched(field.touched);
+        }
+        self.submitted = snapshot.submitted;
+        self.set_editing(snapshot.is_editing);
+        self.validate_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::form::FormHandler;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut handler = FormHandler::new();
+        for (ident, value) in [
+            (FieldIdent::Username, "TestUser"),
+            (FieldIdent::Email, "test@example.com"),
+            (FieldIdent::Address, "10 Downing Street"),
+        ] {
+            let _ = handler.handle_update_value(ident, value.to_string());
+        }
+        let _ = handler.handle_submit();
+        let _ = handler.handle_edit();
+        for (ident, value) in [(FieldIdent::Email, "other@"), (FieldIdent::Age, "abc")] {
+            let _ = handler.handle_update_value(ident, value.to_string());
+        }
+        let snapshot = handler.get_form().snapshot();
+
+        let payload = serde_json::to_string(&snapshot).unwrap();
+        let mut restored = Form::default();
+        restored.restore_snapshot(serde_json::from_str(&payload).unwrap());
+
+        assert_eq!(restored.snapshot(), snapshot);
+        // The age reads as empty, like its baseline
+        assert_eq!(restored.dirty_fields(), vec![FieldIdent::Email]);
+        assert_eq!(restored.input(FieldIdent::Age), "abc");
+        assert!(restored.email.touched, "by the submit");
+        assert!(!restored.middle_name.dirty);
+        assert!(!restored.submitted);
+        assert!(restored.is_editing);
+        assert!(!restored.can_submit());
+    }
+
+    #[test]
+    fn test_restoring_validates_afresh() {
+        let mut form = Form::default();
+        form.set_input(FieldIdent::Email, "not an email".to_string());
+        let mut snapshot = form.snapshot();
+        // Saved before the rules were run
+        for field in &mut snapshot.fields {
+            field.valid = true;
+            field.error = None;
+        }
+
+        let mut restored = Form::default();
+        restored.username.add_validator(|username| {
+            (username.0 != "admin")
+                .then_some(())
+                .ok_or_else(|| "Reserved".to_string())
+        });
+        snapshot.fields[0].input = "admin".to_string();
+        restored.restore_snapshot(snapshot);
+
+        assert!(!restored.email.valid);
+        assert!(restored.email.error.is_some());
+        assert_eq!(restored.username.error.as_deref(), Some("Reserved"));
+        assert!(restored.address.error.is_some(), "empty and required");
+    }
+}
@@ -0,0 +1,65 @@
+//! Combinators that build a `FieldValidator` out of smaller ones.
+
+use std::sync::Arc;
+
+use crate::events::form::FieldValidator;
+
+/// Passes when both pass; otherwise fails with the message of the first
+/// that fails. `b` is not run once `a` has failed.
+pub fn and<T: 'static>(a: FieldValidator<T>, b: FieldValidator<T>) -> FieldValidator<T> {
+    Arc::new(move |value| a(value).and_then(|()| b(value)))
+}
+
+/// Passes when either passes; otherwise fails with the message of `a`.
+/// `b` is not run once `a` has passed.
+pub fn or<T: 'static>(a: FieldValidator<T>, b: FieldValidator<T>) -> FieldValidator<T> {
+    Arc::new(move |value| a(value).or_else(|error| b(value).map_err(|_| error)))
+}
+
+/// Passes only when `v` fails, failing with `msg` otherwise.
+pub fn not<T: 'static>(v: FieldValidator<T>, msg: &str) -> FieldValidator<T> {
+    let msg = msg.to_string();
+    Arc::new(move |value| match v(value) {
+        Ok(()) => Err(msg.clone()),
+        Err(_) => Ok(()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(message: &'static str, pass: fn(&str) -> bool) -> FieldValidator<String> {
+        Arc::new(move |value: &String| pass(value).then_some(()).ok_or_else(|| message.to_string()))
+    }
+
+    #[test]
+    fn test_combinators() {
+        let short = rule("Too long", |value| value.len() <= 3);
+        let digits = rule("Not digits", |value| {
+            value.chars().all(|c| c.is_ascii_digit())
+        });
+        let check = |validator: &FieldValidator<String>, value: &str| validator(&value.to_string());
+
+        let both = and(short.clone(), digits.clone());
+        assert_eq!(check(&both, "123"), Ok(()));
+        assert_eq!(check(&both, "1234"), Err("Too long".to_string()));
+        assert_eq!(check(&both, "abc"), Err("Not digits".to_string()));
+
+        let either = or(short.clone(), digits.clone());
+        assert_eq!(check(&either, "abc"), Ok(()));
+        assert_eq!(check(&either, "1234"), Ok(()));
+        assert_eq!(check(&either, "abcd"), Err("Too long".to_string()));
+
+        let long = not(short.clone(), "Too short");
+        assert_eq!(check(&long, "abcd"), Ok(()));
+        assert_eq!(check(&long, "abc"), Err("Too short".to_string()));
+
+        let empty = rule("Empty", |value| value.is_empty());
+        let nested = and(not(empty, "Required"), or(short, digits));
+        assert_eq!(check(&nested, ""), Err("Required".to_string()));
+        assert_eq!(check(&nested, "ab"), Ok(()));
+        assert_eq!(check(&nested, "12345"), Ok(()));
+        assert_eq!(check(&nested, "abcd"), Err("Too long".to_string()));
+    }
+}
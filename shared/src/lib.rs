@@ -1,17 +1,34 @@
+// Behind the prelude; paths into these modules are not stable
+#[doc(hidden)]
 pub mod address;
+#[doc(hidden)]
 pub mod app;
+#[doc(hidden)]
 pub mod capabilities;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
+pub mod delta;
+#[doc(hidden)]
+pub mod descriptor;
+#[doc(hidden)]
 pub mod events;
-pub mod field;
-pub mod form;
+#[doc(hidden)]
+pub mod pending;
+pub mod prelude;
+mod render_filter;
+#[doc(hidden)]
+pub mod render_hint;
 
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
 
 pub use crux_core::bridge::{Bridge, Request};
 pub use crux_core::{Core, ResolveError};
 pub use crux_http as http;
 
-pub use app::*;
+pub use prelude::*;
 // We are not using sse capability for the form app for now
 // pub use capabilities::sse;
 
@@ -22,24 +39,127 @@ uniffi::include_scaffolding!("form"); // Changed from "shared"
 lazy_static! {
     // Changed App to FormApp
     static ref CORE: Bridge<App> = Bridge::new(Core::new());
+    static ref VIEW_DELTAS: Mutex<delta::ViewDeltaTracker> = Mutex::default();
+    static ref PENDING: Mutex<pending::PendingOperations> = Mutex::default();
+    static ref RENDERS: Mutex<render_filter::RenderFilter> = Mutex::default();
+}
+
+/// Drops renders of an unchanged view from `effects`.
+fn filter_renders(effects: Vec<u8>) -> Vec<u8> {
+    RENDERS
+        .lock()
+        .expect("render filter poisoned")
+        .filter(effects, view)
+}
+
+fn track_pending(effects: &[u8]) {
+    PENDING
+        .lock()
+        .expect("pending operations poisoned")
+        .track(effects);
 }
 
 #[cfg_attr(target_family = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
 pub fn process_event(data: &[u8]) -> Vec<u8> {
     match CORE.process_event(data) {
-        Ok(effects) => effects,
+        Ok(effects) => {
+            track_pending(&effects);
+            filter_renders(effects)
+        }
         Err(e) => panic!("{e}"),
     }
 }
 
+/// Processes a serialized `Vec<Event>` in order, as if each had been sent
+/// with `process_event`, and returns all their effects in one list. Renders
+/// are coalesced into the last one; other effects keep their order.
+#[cfg_attr(target_family = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn process_events(data: &[u8]) -> Vec<u8> {
+    let events: Vec<Event> = bincode::deserialize(data).expect("event batches are bincode encoded");
+    let mut requests: Vec<Request<EffectFfi>> = Vec::new();
+    for event in events {
+        let event = bincode::serialize(&event).expect("events are serializable");
+        match CORE.process_event(&event) {
+            Ok(effects) => requests.extend(
+                bincode::deserialize::<Vec<Request<EffectFfi>>>(&effects)
+                    .expect("bridge effects are bincode encoded"),
+            ),
+            Err(e) => panic!("{e}"),
+        }
+    }
+    render_filter::coalesce(&mut requests);
+    let effects = bincode::serialize(&requests).expect("bridge effects are serializable");
+    track_pending(&effects);
+    filter_renders(effects)
+}
+
+/// Effects and the resulting view from a single `process_event_with_view`.
+/// Both are encoded exactly as `process_event` and `view` would return them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProcessResult {
+    pub effects: Vec<u8>,
+    pub view: Vec<u8>,
+}
+
+/// `process_event` followed by `view`, in one call across the bridge.
+/// Returns a serialized `ProcessResult`.
+#[cfg_attr(target_family = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn process_event_with_view(data: &[u8]) -> Vec<u8> {
+    let result = ProcessResult {
+        effects: process_event(data),
+        view: view(),
+    };
+    bincode::serialize(&result).expect("process results are serializable")
+}
+
 #[cfg_attr(target_family = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
 pub fn handle_response(id: u32, data: &[u8]) -> Vec<u8> {
+    // Before resolving: the id may be handed out again straight away
+    PENDING
+        .lock()
+        .expect("pending operations poisoned")
+        .resolve(id);
     match CORE.handle_response(id, data) {
-        Ok(effects) => effects,
+        Ok(effects) => {
+            track_pending(&effects);
+            filter_renders(effects)
+        }
         Err(e) => panic!("{e}"),
     }
 }
 
+/// Requests the shell has not resolved yet, with what each one is for.
+/// Returns a serialized `Vec<PendingOperation>`, ordered by request id.
+#[cfg_attr(target_family = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn pending_operations() -> Vec<u8> {
+    let pending = PENDING.lock().expect("pending operations poisoned").list();
+    bincode::serialize(&pending).expect("pending operations are serializable")
+}
+
+/// Forgets the requests outstanding for `timeout_ms` or more, as timed by
+/// the `now_ms` of successive calls; shells call this periodically.
+/// Returns the expired ones as a serialized `Vec<PendingOperation>`.
+#[cfg_attr(target_family = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn expire_pending_operations(now_ms: u64, timeout_ms: u64) -> Vec<u8> {
+    let expired = PENDING
+        .lock()
+        .expect("pending operations poisoned")
+        .expire(now_ms, timeout_ms);
+    bincode::serialize(&expired).expect("pending operations are serializable")
+}
+
+/// The fields of the form as currently configured, for tooling.
+/// Returns a serialized `FormDescriptor`.
+#[cfg_attr(target_family = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn describe_form() -> Vec<u8> {
+    let view: ViewModel = match CORE.view() {
+        Ok(view) => bincode::deserialize(&view).expect("bridge views are bincode encoded"),
+        Err(e) => panic!("{e}"),
+    };
+    let descriptor = descriptor::FormDescriptor::new(&view.form);
+    bincode::serialize(&descriptor).expect("form descriptors are serializable")
+}
+
 #[cfg_attr(target_family = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
 pub fn view() -> Vec<u8> {
     match CORE.view() {
@@ -47,3 +167,59 @@ pub fn view() -> Vec<u8> {
         Err(e) => panic!("{e}"),
     }
 }
+
+/// Like `view`, but returns a serialized `ViewDelta` against the generation
+/// the shell last applied (0 if it has none).
+#[cfg_attr(target_family = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn view_delta(acknowledged_generation: u32) -> Vec<u8> {
+    let view: ViewModel = match CORE.view() {
+        Ok(view) => bincode::deserialize(&view).expect("bridge views are bincode encoded"),
+        Err(e) => panic!("{e}"),
+    };
+    let delta = VIEW_DELTAS
+        .lock()
+        .expect("view delta tracker poisoned")
+        .delta(&view, acknowledged_generation);
+    bincode::serialize(&delta).expect("view deltas are serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::form::FieldIdent;
+
+    #[test]
+    fn process_event_with_view_returns_updated_view() {
+        let event = Event::UpdateValue {
+            ident: FieldIdent::Username,
+            value: "TestUser".to_string(),
+        };
+        let result: ProcessResult = bincode::deserialize(&process_event_with_view(
+            &bincode::serialize(&event).unwrap(),
+        ))
+        .unwrap();
+
+        let view: ViewModel = bincode::deserialize(&result.view).unwrap();
+        assert_eq!(view.form.username.value, "TestUser");
+        assert!(!result.effects.is_empty());
+    }
+
+    #[test]
+    fn process_events_coalesces_renders() {
+        let events: Vec<Event> = ["Batched", "BatchedUser"]
+            .into_iter()
+            .map(|value| Event::UpdateValue {
+                ident: FieldIdent::Username,
+                value: value.to_string(),
+            })
+            .collect();
+        let requests: Vec<Request<EffectFfi>> =
+            bincode::deserialize(&process_events(&bincode::serialize(&events).unwrap())).unwrap();
+
+        let renders = requests
+            .iter()
+            .filter(|request| matches!(request.effect, EffectFfi::Render(_)))
+            .count();
+        assert_eq!(renders, 1);
+    }
+}
@@ -0,0 +1,392 @@
+use crux_core::bridge::Request;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::app::EffectFfi;
+use crate::capabilities::timer::{TimerKind, TimerOperation};
+use crate::events::form::FieldIdent;
+
+/// What an outstanding bridge request is for, so shells can label their
+/// request id logs.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum OperationKind {
+    SuggestionsFetch {
+        query: String,
+    },
+    EmailCheck {
+        email: String,
+    },
+    RemoteValidation {
+        ident: FieldIdent,
+    },
+    AsyncValidation {
+        ident: FieldIdent,
+    },
+    Submission {
+        url: String,
+    },
+    ErrorDebounce {
+        millis: u64,
+    },
+    RetryBackoff {
+        millis: u64,
+    },
+    SuggestionDebounce {
+        millis: u64,
+    },
+    RemoteCheckDebounce {
+        millis: u64,
+    },
+    UndoWindow {
+        millis: u64,
+    },
+    RandomValue,
+    /// An HTTP request that was not labelled where it was made
+    Http {
+        method: String,
+        url: String,
+    },
+}
+
+/// A request the shell has not yet resolved.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PendingOperation {
+    pub id: u32,
+    pub kind: OperationKind,
+}
+
+/// Labels kept for requests not yet seen by `track`, e.g. those made by
+/// commands that never reach the bridge; the oldest is dropped first.
+const LABELS_KEPT: usize = 32;
+
+/// An HTTP request about to be made, as labelled by the code making it.
+struct Label {
+    method: &'static str,
+    url: String,
+    kind: OperationKind,
+}
+
+thread_local! {
+    // Commands run on the thread that called into the bridge, which tracks
+    // their requests before returning
+    static LABELS: RefCell<VecDeque<Label>> = RefCell::default();
+}
+
+/// The part of `url` a request is matched on; the query is left out, as
+/// it is added after labelling.
+fn endpoint(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut url) => {
+            url.set_query(None);
+            url.to_string()
+        }
+        Err(_) => url.split('?').next().unwrap_or_default().to_string(),
+    }
+}
+
+/// Says what the HTTP request about to be made to `url` is for. Called
+/// where requests are built, right before each is sent.
+pub fn label(method: &'static str, url: &str, kind: OperationKind) {
+    LABELS.with(|labels| {
+        let mut labels = labels.borrow_mut();
+        if labels.len() == LABELS_KEPT {
+            labels.pop_front();
+        }
+        labels.push_back(Label {
+            method,
+            url: endpoint(url),
+            kind,
+        });
+    });
+}
+
+impl OperationKind {
+    /// `None` for effects the shell never responds to.
+    fn of(effect: &EffectFfi) -> Option<Self> {
+        match effect {
+            // Requests are labelled in the order they are made, which is
+            // the order their effects come in
+            EffectFfi::Http(request) => {
+                let url = endpoint(&request.url);
+                let labelled = LABELS.with(|labels| {
+                    let mut labels = labels.borrow_mut();
+                    labels
+                        .iter()
+                        .position(|label| label.method == request.method && label.url == url)
+                        .and_then(|index| labels.remove(index))
+                        .map(|label| label.kind)
+                });
+                Some(labelled.unwrap_or_else(|| Self::Http {
+                    method: request.method.clone(),
+                    url: request.url.clone(),
+                }))
+            }
+            EffectFfi::Timer(TimerOperation::Start { id, millis }) => {
+                let millis = *millis;
+                TimerKind::of(*id).map(|kind| match kind {
+                    TimerKind::ErrorDebounce => Self::ErrorDebounce { millis },
+                    TimerKind::RetryBackoff => Self::RetryBackoff { millis },
+                    TimerKind::SuggestionDebounce => Self::SuggestionDebounce { millis },
+                    TimerKind::RemoteCheckDebounce => Self::RemoteCheckDebounce { millis },
+                    TimerKind::UndoWindow => Self::UndoWindow { millis },
+                })
+            }
+            EffectFfi::AsyncValidate(operation) => Some(Self::AsyncValidation {
+                ident: operation.ident,
+            }),
+            EffectFfi::Random(_) => Some(Self::RandomValue),
+            EffectFfi::Render(_)
+            | EffectFfi::Analytics(_)
+            | EffectFfi::Navigate(_)
+            | EffectFfi::Storage(_)
+            | EffectFfi::Diagnostics(_) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Tracked {
+    kind: OperationKind,
+    /// When `expire` first saw the request, in the shell's milliseconds
+    first_swept_ms: Option<u64>,
+}
+
+/// Outstanding requests by bridge request id.
+#[derive(Default, Debug)]
+pub struct PendingOperations(BTreeMap<u32, Tracked>);
+
+impl PendingOperations {
+    /// Records the requests in a serialized effects list, as returned by
+    /// `process_event` or `handle_response`.
+    pub fn track(&mut self, effects: &[u8]) {
+        let requests: Vec<Request<EffectFfi>> =
+            bincode::deserialize(effects).expect("bridge effects are bincode encoded");
+        for request in requests {
+            if let Some(kind) = OperationKind::of(&request.effect) {
+                self.0.insert(
+                    request.id.0,
+                    Tracked {
+                        kind,
+                        first_swept_ms: None,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Forgets a request once the shell has resolved it.
+    pub fn resolve(&mut self, id: u32) {
+        self.0.remove(&id);
+    }
+
+    /// Forgets and returns the requests outstanding for `timeout_ms` or
+    /// more at `now_ms`. The core has no clock, so a request is timed from
+    /// the first call that sees it: shells call this periodically, and
+    /// timeouts are as precise as that period.
+    pub fn expire(&mut self, now_ms: u64, timeout_ms: u64) -> Vec<PendingOperation> {
+        let mut expired = Vec::new();
+        self.0.retain(|id, tracked| {
+            let since = *tracked.first_swept_ms.get_or_insert(now_ms);
+            let outstanding = now_ms.saturating_sub(since) < timeout_ms;
+            if !outstanding {
+                expired.push(PendingOperation {
+                    id: *id,
+                    kind: tracked.kind.clone(),
+                });
+            }
+            outstanding
+        });
+        expired
+    }
+
+    pub fn list(&self) -> Vec<PendingOperation> {
+        self.0
+            .iter()
+            .map(|(id, tracked)| PendingOperation {
+                id: *id,
+                kind: tracked.kind.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{App, Event};
+    use crate::capabilities::random::RandomValue;
+    use crate::config::{AppConfig, RemoteCheck, SubmitHook};
+    use crate::events::form::FieldIdent;
+    use crate::events::history::UNDO_WINDOW_MS;
+    use crux_core::{bridge::Bridge, Core};
+    use crux_http::protocol::{HttpResponse, HttpResult};
+
+    fn send(bridge: &Bridge<App>, pending: &mut PendingOperations, event: Event) {
+        let effects = bridge
+            .process_event(&bincode::serialize(&event).unwrap())
+            .unwrap();
+        pending.track(&effects);
+    }
+
+    /// Leaves the undo windows of the edits out of the listing.
+    fn forget_undo_windows(pending: &mut PendingOperations) {
+        for op in pending.list() {
+            if let OperationKind::UndoWindow { millis } = op.kind {
+                assert_eq!(millis, UNDO_WINDOW_MS);
+                pending.resolve(op.id);
+            }
+        }
+    }
+
+    fn resolve(bridge: &Bridge<App>, pending: &mut PendingOperations, id: u32, status: u16) {
+        pending.resolve(id);
+        let response = HttpResult::Ok(HttpResponse::status(status).build());
+        let effects = bridge
+            .handle_response(id, &bincode::serialize(&response).unwrap())
+            .unwrap();
+        pending.track(&effects);
+    }
+
+    #[test]
+    fn test_pending_operations_follow_requests() {
+        let bridge = Bridge::new(Core::<App>::new());
+        let mut pending = PendingOperations::default();
+        send(
+            &bridge,
+            &mut pending,
+            Event::Configure(AppConfig {
+                submit_hooks: vec![SubmitHook::HttpPost {
+                    url: "https://api.example.com/submit".to_string(),
+                }],
+                address_api_url: Some("https://api.example.com/suggestions".to_string()),
+                suggestion_debounce_ms: 0,
+                random_seed: Some(7),
+                ..Default::default()
+            }),
+        );
+        for (ident, value) in [
+            (FieldIdent::Username, "TestUser"),
+            (FieldIdent::Email, "test@example.com"),
+            (FieldIdent::Address, "10 Downing Street"),
+        ] {
+            send(
+                &bridge,
+                &mut pending,
+                Event::UpdateValue {
+                    ident,
+                    value: value.to_string(),
+                },
+            );
+        }
+        forget_undo_windows(&mut pending);
+        send(&bridge, &mut pending, Event::Submit);
+
+        let listed = pending.list();
+        let kinds: Vec<&OperationKind> = listed.iter().map(|op| &op.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &OperationKind::RandomValue,
+                &OperationKind::SuggestionsFetch {
+                    query: "10 Downing Street".to_string()
+                },
+                &OperationKind::Submission {
+                    url: "https://api.example.com/submit".to_string()
+                },
+            ]
+        );
+        let (salt, fetch, submission) = (listed[0].id, listed[1].id, listed[2].id);
+
+        pending.resolve(salt);
+        let effects = bridge
+            .handle_response(salt, &bincode::serialize(&RandomValue(7)).unwrap())
+            .unwrap();
+        pending.track(&effects);
+
+        resolve(&bridge, &mut pending, fetch, 503);
+        assert_eq!(
+            pending.list(),
+            vec![PendingOperation {
+                id: submission,
+                kind: OperationKind::Submission {
+                    url: "https://api.example.com/submit".to_string()
+                },
+            }]
+        );
+
+        resolve(&bridge, &mut pending, submission, 200);
+        assert!(pending.list().is_empty());
+    }
+
+    #[test]
+    fn test_requests_are_labelled_where_made_and_expire() {
+        let bridge = Bridge::new(Core::<App>::new());
+        let mut pending = PendingOperations::default();
+        // One endpoint answers both checks
+        let checks = "https://api.example.com/check".to_string();
+        send(
+            &bridge,
+            &mut pending,
+            Event::Configure(AppConfig {
+                email_check_url: Some(checks.clone()),
+                remote_checks: vec![RemoteCheck {
+                    ident: FieldIdent::Username,
+                    url: checks,
+                    on_edit: false,
+                    debounce_ms: 0,
+                }],
+                suggestion_debounce_ms: 0,
+                random_seed: Some(7),
+                ..Default::default()
+            }),
+        );
+        for (ident, value) in [
+            (FieldIdent::Username, "TestUser"),
+            (FieldIdent::Email, "test@example.com"),
+            (FieldIdent::Address, "10 Downing Street"),
+        ] {
+            send(
+                &bridge,
+                &mut pending,
+                Event::UpdateValue {
+                    ident,
+                    value: value.to_string(),
+                },
+            );
+        }
+        forget_undo_windows(&mut pending);
+        send(&bridge, &mut pending, Event::Submit);
+        send(
+            &bridge,
+            &mut pending,
+            Event::ValidateFieldRemote {
+                ident: FieldIdent::Username,
+            },
+        );
+        let kinds: Vec<OperationKind> = pending.list().into_iter().map(|op| op.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                OperationKind::RandomValue,
+                OperationKind::EmailCheck {
+                    email: "test@example.com".to_string()
+                },
+                OperationKind::RemoteValidation {
+                    ident: FieldIdent::Username
+                },
+            ]
+        );
+
+        // Timed from the first sweep that sees them
+        assert!(pending.expire(5_000, 1_000).is_empty());
+        assert!(pending.expire(5_999, 1_000).is_empty());
+        let expired: Vec<OperationKind> = pending
+            .expire(6_000, 1_000)
+            .into_iter()
+            .map(|op| op.kind)
+            .collect();
+        assert_eq!(expired, kinds);
+        assert!(pending.list().is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+//! The intended public surface of the core, also re-exported from the crate
+//! root. Import from here rather than from the modules behind it, which may
+//! be rearranged at any time. `tests/golden/public_api.txt` lists every item,
+//! so a change to this surface shows up in review.
+
+pub use crate::app::{App, Effect, EffectFfi, Event, Model, ViewModel, MODEL_VERSION};
+pub use crate::capabilities::analytics::AnalyticsOperation;
+pub use crate::capabilities::diagnostics::{DiagnosticsOperation, DiagnosticsReport};
+pub use crate::capabilities::navigate::NavigateOperation;
+pub use crate::capabilities::random::{RandomOperation, RandomValue};
+pub use crate::capabilities::storage::StorageOperation;
+pub use crate::capabilities::timer::{TimerKind, TimerOperation, TimerOutput};
+pub use crate::capabilities::validate::{AsyncValidateOperation, AsyncVerdict};
+pub use crate::config::{
+    AppConfig, ConfigError, DynamicFieldSpec, RemoteCheck, ResubmitPolicy, RetryPolicy,
+    SubmissionMode, SubmissionReceipt, SubmitHook,
+};
+pub use crate::delta::{DeltaError, ViewDelta, ViewDeltaBody, ViewPatch, VIEW_DELTA_VERSION};
+pub use crate::descriptor::{FieldKind, FieldMeta, FormDescriptor, ValidatorSpec};
+pub use crate::events::address::{
+    Address, AddressProvider, AddressSuggestionsResult, Coordinates, ProviderResponseFormat,
+    SuggestionViewModel,
+};
+pub use crate::events::builder::{FieldBuilder, FieldText};
+pub use crate::events::diagnostics::{
+    FieldErrorStats, SkipReason, SkippedFetches, SuggestionAttempt, SuggestionOutcome,
+};
+pub use crate::events::draft::DraftWarning;
+pub use crate::events::dynamic::{DynamicFieldViewModel, DynamicForm};
+pub use crate::events::form::{
+    Field, FieldError, FieldIdent, FieldViewModel, Form, FormStatus, FormSubmission, FormValidator,
+    FormViewModel, PendingCheck, ServerFieldError, SubmitResult, UsernamePolicy,
+};
+pub use crate::events::signals::{RaisedSignal, Signal};
+pub use crate::events::snapshot::{FieldSnapshot, FormSnapshot};
+pub use crate::pending::{OperationKind, PendingOperation};
+pub use crate::render_hint::RenderHint;
+pub use crate::ProcessResult;
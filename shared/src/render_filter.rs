@@ -0,0 +1,91 @@
+use crux_core::bridge::Request;
+
+use crate::app::{EffectFfi, ViewModel};
+
+/// Drops renders that would show the shell the view it already has, to
+/// save it a `view` call and a redraw.
+#[derive(Default, Debug)]
+pub struct RenderFilter {
+    /// The view as of the last render let through, without render hints
+    last_rendered: Option<ViewModel>,
+}
+
+fn is_render(request: &Request<EffectFfi>) -> bool {
+    matches!(request.effect, EffectFfi::Render(_))
+}
+
+/// Keeps only the last render in `requests`, where it was. The shell reads
+/// the view when it handles a render, so earlier ones would only make it
+/// fetch the same view again.
+pub fn coalesce(requests: &mut Vec<Request<EffectFfi>>) {
+    let Some(last) = requests.iter().rposition(is_render) else {
+        return;
+    };
+    let mut index = 0;
+    requests.retain(|request| {
+        let keep = index == last || !is_render(request);
+        index += 1;
+        keep
+    });
+}
+
+impl RenderFilter {
+    /// Filters a serialized effects list, as returned by `process_event` or
+    /// `handle_response`. `view` returns the current serialized view; it is
+    /// only called when there is a render to check.
+    pub fn filter(&mut self, effects: Vec<u8>, view: impl FnOnce() -> Vec<u8>) -> Vec<u8> {
+        let mut requests: Vec<Request<EffectFfi>> =
+            bincode::deserialize(&effects).expect("bridge effects are bincode encoded");
+        if !requests.iter().any(is_render) {
+            return effects;
+        }
+
+        let mut view: ViewModel =
+            bincode::deserialize(&view()).expect("bridge views are bincode encoded");
+        // Hints describe a change; on their own they are not one
+        view.render_hints.clear();
+        if self.last_rendered.as_ref() != Some(&view) {
+            self.last_rendered = Some(view);
+            return effects;
+        }
+        requests.retain(|request| !is_render(request));
+        bincode::serialize(&requests).expect("bridge effects are serializable")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{App, Event};
+    use crate::events::form::FieldIdent;
+    use crux_core::{bridge::Bridge, Core};
+
+    /// Sends `event` and returns how many renders got through the filter.
+    fn renders(bridge: &Bridge<App>, filter: &mut RenderFilter, event: Event) -> usize {
+        let effects = bridge
+            .process_event(&bincode::serialize(&event).unwrap())
+            .unwrap();
+        let effects = filter.filter(effects, || bridge.view().unwrap());
+        let requests: Vec<Request<EffectFfi>> = bincode::deserialize(&effects).unwrap();
+        requests.iter().filter(|request| is_render(request)).count()
+    }
+
+    #[test]
+    fn test_render_skipped_when_view_is_unchanged() {
+        let bridge = Bridge::new(Core::<App>::new());
+        let mut filter = RenderFilter::default();
+        let username = |value: &str| Event::UpdateValue {
+            ident: FieldIdent::Username,
+            value: value.to_string(),
+        };
+
+        assert_eq!(renders(&bridge, &mut filter, username("TestUser")), 1);
+        // Removing an override that was never set renders an identical view
+        assert_eq!(
+            renders(&bridge, &mut filter, Event::SetStatusOverride(None)),
+            0
+        );
+        assert_eq!(renders(&bridge, &mut filter, username("TestUser")), 0);
+        assert_eq!(renders(&bridge, &mut filter, username("OtherUser")), 1);
+    }
+}
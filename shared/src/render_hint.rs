@@ -0,0 +1,156 @@
+//! Which parts of the ViewModel an update changed, so shells can re-render
+//! only those regions. Hints are advisory: a shell that ignores them and
+//! redraws everything on each `Render` stays correct.
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::Event;
+use crate::events::form::FieldIdent;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum RenderHint {
+    Fields(Vec<FieldIdent>),
+    Suggestions,
+    /// `status_message`, `can_submit` and the other form-level flags
+    Status,
+    Whole,
+    /// The pending `signals`
+    Signals,
+}
+
+/// Hints for the next render. Follow-up events the core dispatches to itself
+/// add to the current hints rather than replacing them, since the shell only
+/// renders once they have all been processed.
+#[derive(Debug, Default)]
+pub struct RenderHints {
+    hints: Vec<RenderHint>,
+    merge_next: bool,
+}
+
+impl RenderHints {
+    pub fn current(&self) -> &[RenderHint] {
+        &self.hints
+    }
+
+    pub fn record(&mut self, hints: Vec<RenderHint>) {
+        if !self.merge_next {
+            self.hints.clear();
+        }
+        self.merge_next = false;
+        for hint in hints {
+            self.add(hint);
+        }
+    }
+
+    /// The update being handled dispatched a follow-up event.
+    pub fn merge_next(&mut self) {
+        self.merge_next = true;
+    }
+
+    fn add(&mut self, hint: RenderHint) {
+        if self.hints.contains(&RenderHint::Whole) {
+            return;
+        }
+        match hint {
+            RenderHint::Whole => self.hints = vec![RenderHint::Whole],
+            RenderHint::Fields(idents) => {
+                let existing = self.hints.iter_mut().find_map(|hint| match hint {
+                    RenderHint::Fields(existing) => Some(existing),
+                    _ => None,
+                });
+                match existing {
+                    Some(existing) => {
+                        for ident in idents {
+                            if !existing.contains(&ident) {
+                                existing.push(ident);
+                            }
+                        }
+                    }
+                    None => self.hints.push(RenderHint::Fields(idents)),
+                }
+            }
+            hint if !self.hints.contains(&hint) => self.hints.push(hint),
+            _ => {}
+        }
+    }
+}
+
+/// Regions `event` may change, or `None` if it never renders. Anything not
+/// known to be local falls back to `Whole`.
+pub fn hints_for(event: &Event) -> Option<Vec<RenderHint>> {
+    let hints = match event {
+        // Editing drops carets placed for an earlier rewrite
+        Event::UpdateValue { ident, .. } | Event::SetFieldValue { ident, .. } => vec![
+            RenderHint::Fields(vec![*ident]),
+            RenderHint::Status,
+            RenderHint::Signals,
+        ],
+        Event::BatchUpdate(values) => vec![
+            RenderHint::Fields(values.iter().map(|(ident, _)| *ident).collect()),
+            RenderHint::Status,
+            RenderHint::Signals,
+        ],
+        // Undoing rewrites the value, so it places the caret
+        Event::UndoField { ident } => vec![
+            RenderHint::Fields(vec![*ident]),
+            RenderHint::Status,
+            RenderHint::Signals,
+        ],
+        Event::TouchField { ident } | Event::SetFieldError { ident, .. } => {
+            vec![RenderHint::Fields(vec![*ident]), RenderHint::Status]
+        }
+        // Leaving the username may rewrite it and place the caret
+        Event::SetFieldEditing { ident, .. } => {
+            vec![RenderHint::Fields(vec![*ident]), RenderHint::Signals]
+        }
+        Event::AcknowledgeSignals { .. } => vec![RenderHint::Signals],
+        Event::ValidateFieldRemote { ident } => vec![RenderHint::Fields(vec![*ident])],
+        Event::RemoteValidationReceived { ident, .. }
+        | Event::AsyncValidationResult { ident, .. } => {
+            vec![RenderHint::Fields(vec![*ident]), RenderHint::Status]
+        }
+        Event::ToggleFieldMask { ident }
+        | Event::SetErrorDebounce { ident, .. }
+        | Event::ErrorDebounceElapsed { ident, .. } => vec![RenderHint::Fields(vec![*ident])],
+        Event::SetStatusOverride(_) | Event::SubmissionAccepted { .. } => vec![RenderHint::Status],
+        Event::MarkAsPristine => vec![
+            RenderHint::Fields(FieldIdent::ALL.to_vec()),
+            RenderHint::Status,
+        ],
+        Event::Tick { .. } => vec![
+            RenderHint::Fields(vec![FieldIdent::DateOfBirth]),
+            RenderHint::Status,
+        ],
+        Event::FetchSuggestions { .. }
+        | Event::FetchSuggestionsForced { .. }
+        | Event::SuggestionsReceived { .. }
+        | Event::SelectSuggestion { .. }
+        | Event::SelectSuggestionAt { .. }
+        | Event::ClearSuggestions => vec![RenderHint::Suggestions],
+        Event::Configure(_)
+        | Event::Submit
+        | Event::EmailChecked { .. }
+        | Event::SubmitResponseReceived(_)
+        | Event::SubmitRejected { .. }
+        | Event::Edit
+        | Event::ResetForm
+        | Event::ResetAll
+        | Event::Abandon { .. }
+        | Event::Undo
+        | Event::Redo
+        | Event::SaveDraft
+        | Event::RestoreDraft { .. }
+        | Event::RestoreSnapshot(_)
+        | Event::UpdateDynamicValue { .. } => vec![RenderHint::Whole],
+        Event::RetrySuggestions { .. }
+        | Event::AddressDebounceElapsed { .. }
+        | Event::RemoteCheckDebounceElapsed { .. }
+        | Event::UndoWindowElapsed { .. }
+        | Event::SelectCountry { .. }
+        | Event::SetUserLocation { .. }
+        | Event::ExportDiagnostics
+        | Event::JitterSeeded { .. }
+        | Event::ErrorSaltReceived { .. } => return None,
+    };
+    Some(hints)
+}
@@ -0,0 +1,155 @@
+//! A realistic session driven through the exported bridge functions, shared
+//! by the native and wasm golden tests so both builds are held to the same
+//! bytes. Shells can decode `golden/session.txt` to check their own
+//! deserializers against the core.
+
+use serde::Serialize;
+use serde_json::json;
+use shared::http::protocol::{HttpResponse, HttpResult};
+use shared::prelude::{AppConfig, EffectFfi, Event, FieldIdent, TimerOperation, TimerOutput};
+use shared::Request;
+
+pub const GOLDEN: &str = include_str!("../golden/session.txt");
+
+/// The bytes each bridge call returned, labelled by step.
+#[derive(Default)]
+pub struct Session {
+    pub outputs: Vec<(String, Vec<u8>)>,
+}
+
+impl Session {
+    fn record(&mut self, step: &str, effects: Vec<u8>) -> Vec<Request<EffectFfi>> {
+        let requests = bincode::deserialize(&effects).expect("bridge effects decode");
+        self.outputs.push((format!("{step}.effects"), effects));
+        self.outputs.push((format!("{step}.view"), shared::view()));
+        requests
+    }
+
+    fn send(&mut self, step: &str, event: Event) -> Vec<Request<EffectFfi>> {
+        let effects = shared::process_event(&bincode::serialize(&event).unwrap());
+        self.record(step, effects)
+    }
+
+    fn respond(&mut self, step: &str, id: u32, output: &impl Serialize) -> Vec<Request<EffectFfi>> {
+        let effects = shared::handle_response(id, &bincode::serialize(output).unwrap());
+        self.record(step, effects)
+    }
+}
+
+/// Configures a suggestion endpoint, fills in the form, takes an address suggestion with and without
+/// coordinates, and submits. Must run once per process: the bridge keeps
+/// its state in globals.
+pub fn run_session() -> Session {
+    let mut session = Session::default();
+    session.send(
+        "configure",
+        Event::Configure(AppConfig {
+            address_api_url: Some("http://localhost:8000/api/suggestions".to_string()),
+            ..Default::default()
+        }),
+    );
+    for (step, ident, value) in [
+        ("username", FieldIdent::Username, "TestUser"),
+        ("email", FieldIdent::Email, "test@example.com"),
+        ("age", FieldIdent::Age, "30"),
+        ("date-of-birth", FieldIdent::DateOfBirth, "1990-02-28"),
+    ] {
+        session.send(
+            step,
+            Event::UpdateValue {
+                ident,
+                value: value.to_string(),
+            },
+        );
+    }
+
+    let requests = session.send(
+        "address",
+        Event::UpdateValue {
+            ident: FieldIdent::Address,
+            value: "Baker".to_string(),
+        },
+    );
+    let (pause, id) = requests
+        .iter()
+        .find_map(|request| match request.effect {
+            EffectFfi::Timer(TimerOperation::Start { id, .. }) => Some((request.id.0, id)),
+            _ => None,
+        })
+        .expect("typing an address waits for a pause");
+    let requests = session.respond("pause", pause, &TimerOutput::Elapsed { id });
+    let fetch = requests
+        .iter()
+        .find(|request| matches!(request.effect, EffectFfi::Http(_)))
+        .expect("the pause fetches suggestions");
+    let suggestions = json!([
+        {
+            "street": "221B Baker Street",
+            "city": "London",
+            "postcode": "NW1 6XE",
+            "country": "UK",
+            "combined": "221B Baker Street, London, NW1 6XE, UK",
+            "lat": 51.5237,
+            "lon": -0.1585
+        },
+        {
+            "street": "Baker Lane",
+            "city": "Grafton",
+            "postcode": "",
+            "country": "IE",
+            "combined": "Baker Lane, Grafton, IE"
+        }
+    ]);
+    session.respond(
+        "suggestions",
+        fetch.id.0,
+        &HttpResult::Ok(HttpResponse::ok().json(suggestions).build()),
+    );
+
+    session.send("select", Event::SelectSuggestionAt { index: 0 });
+    session.send("submit", Event::Submit);
+    session
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Parses lines of `label hex`, as in `GOLDEN`.
+pub fn parse_golden(golden: &str) -> Vec<(String, Vec<u8>)> {
+    golden
+        .lines()
+        .map(|line| {
+            let (label, hex) = line.split_once(' ').expect("golden lines are `label hex`");
+            let bytes = (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("golden bytes are hex"))
+                .collect();
+            (label.to_string(), bytes)
+        })
+        .collect()
+}
+
+/// Panics at the first output that differs from the golden vectors.
+pub fn assert_matches_golden(outputs: &[(String, Vec<u8>)]) {
+    let golden = parse_golden(GOLDEN);
+    assert_eq!(
+        outputs.iter().map(|(label, _)| label).collect::<Vec<_>>(),
+        golden.iter().map(|(label, _)| label).collect::<Vec<_>>(),
+        "the session's steps differ from the golden vectors"
+    );
+    for ((label, actual), (_, expected)) in outputs.iter().zip(&golden) {
+        if actual != expected {
+            let offset = actual
+                .iter()
+                .zip(expected)
+                .position(|(a, e)| a != e)
+                .unwrap_or_else(|| actual.len().min(expected.len()));
+            panic!(
+                "{label} differs from the golden vector at byte {offset}\n  actual: {}\nexpected: {}",
+                to_hex(actual),
+                to_hex(expected)
+            );
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Holds the native bridge output for a realistic session to the golden
+//! vectors. Run with `UPDATE_GOLDEN=1` to rewrite them after an intended
+//! change to the wire format, then check the wasm suite agrees.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+#[test]
+fn native_session_matches_golden_vectors() {
+    let session = common::run_session();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/session.txt");
+        std::fs::write(path, to_golden(&session.outputs)).unwrap();
+        return;
+    }
+    common::assert_matches_golden(&session.outputs);
+}
+
+fn to_golden(outputs: &[(String, Vec<u8>)]) -> String {
+    outputs
+        .iter()
+        .map(|(label, bytes)| format!("{label} {}\n", common::to_hex(bytes)))
+        .collect()
+}
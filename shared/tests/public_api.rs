@@ -0,0 +1,46 @@
+//! Holds the prelude to the item list in `golden/public_api.txt`, so that
+//! additions, removals and moves of public items show up in review. Run with
+//! `UPDATE_GOLDEN=1` to rewrite the list after an intended change.
+#![cfg(not(target_arch = "wasm32"))]
+
+const PRELUDE: &str = include_str!("../src/prelude.rs");
+const PUBLIC_API: &str = include_str!("golden/public_api.txt");
+
+/// The full path of every item `pub use`d by the prelude, sorted.
+fn prelude_items() -> Vec<String> {
+    let code: String = PRELUDE
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .collect();
+    let mut items: Vec<String> = code
+        .split(';')
+        .filter_map(|statement| statement.trim().strip_prefix("pub use "))
+        .flat_map(|path| match path.split_once('{') {
+            Some((prefix, group)) => group
+                .trim_end_matches('}')
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| format!("{prefix}{item}"))
+                .collect(),
+            None => vec![path.to_string()],
+        })
+        .collect();
+    items.sort();
+    items
+}
+
+#[test]
+fn prelude_matches_public_api_list() {
+    let items = prelude_items();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/public_api.txt");
+        std::fs::write(path, items.join("\n") + "\n").unwrap();
+        return;
+    }
+    assert_eq!(
+        items,
+        PUBLIC_API.lines().collect::<Vec<_>>(),
+        "the prelude differs from the public API list"
+    );
+}
@@ -0,0 +1,13 @@
+//! The golden session again, with the core compiled to wasm. Run with
+//! `wasm-pack test --node shared`.
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen_test::wasm_bindgen_test;
+
+mod common;
+
+#[wasm_bindgen_test]
+fn wasm_session_matches_golden_vectors() {
+    let session = common::run_session();
+    common::assert_matches_golden(&session.outputs);
+}
@@ -1,7 +1,11 @@
 use crux_core::typegen::TypeGen;
-use shared::address::{AddressSuggestion, AddressSuggestionsResult};
-use shared::events::{FieldIdent, FormViewModel};
-use shared::App;
+use shared::prelude::{
+    Address, AddressSuggestionsResult, App, AsyncVerdict, ConfigError, FieldIdent, FieldKind,
+    FormDescriptor, FormStatus, FormViewModel, OperationKind, PendingOperation, ProcessResult,
+    ProviderResponseFormat, RenderHint, ResubmitPolicy, Signal, SkipReason, SubmissionMode,
+    SubmitHook, SubmitResult, SuggestionOutcome, UsernamePolicy, ValidatorSpec, ViewDelta,
+    ViewDeltaBody,
+};
 use std::path::PathBuf;
 
 fn main() -> anyhow::Result<()> {
@@ -11,9 +15,30 @@ fn main() -> anyhow::Result<()> {
 
     gen.register_app::<App>()?;
     gen.register_type::<FieldIdent>()?;
-    gen.register_type::<AddressSuggestion>()?;
+    gen.register_type::<Address>()?;
     gen.register_type::<AddressSuggestionsResult>()?;
     gen.register_type::<FormViewModel>()?;
+    gen.register_type::<FormStatus>()?;
+    gen.register_type::<SuggestionOutcome>()?;
+    gen.register_type::<SkipReason>()?;
+    gen.register_type::<Signal>()?;
+    gen.register_type::<SubmitHook>()?;
+    gen.register_type::<SubmitResult>()?;
+    gen.register_type::<AsyncVerdict>()?;
+    gen.register_type::<ResubmitPolicy>()?;
+    gen.register_type::<SubmissionMode>()?;
+    gen.register_type::<ConfigError>()?;
+    gen.register_type::<ProviderResponseFormat>()?;
+    gen.register_type::<UsernamePolicy>()?;
+    gen.register_type::<RenderHint>()?;
+    gen.register_type::<ProcessResult>()?;
+    gen.register_type::<ViewDeltaBody>()?;
+    gen.register_type::<ViewDelta>()?;
+    gen.register_type::<OperationKind>()?;
+    gen.register_type::<PendingOperation>()?;
+    gen.register_type::<FieldKind>()?;
+    gen.register_type::<ValidatorSpec>()?;
+    gen.register_type::<FormDescriptor>()?;
 
     let output_root = PathBuf::from("./generated");
 